@@ -0,0 +1,182 @@
+//! Several cursors moving over a shared grid, one tick at a time.
+//!
+//! On each tick the living agents move once, in reading order of their positions at the
+//! start of the tick (top to bottom, then left to right). As soon as an agent moves onto the
+//! cell of another living agent, both crash and are removed, so a crashed agent never moves
+//! later in the same tick. This is the order of the mine cart puzzle, which is easy to get
+//! subtly wrong by moving everyone first and checking afterwards.
+//!
+//! ```
+//! use aoc_tools::{agents::Agents, cursor::{Cursor, EdgePolicy}, direction::Direction, grid::Grid, pair::Pair};
+//! let grid = Grid::new(5, 1, '-');
+//! let mut agents = Agents::new();
+//! agents.add(Cursor::new(&grid, Pair::new(0, 0), Direction::Right, EdgePolicy::Clip), ());
+//! agents.add(Cursor::new(&grid, Pair::new(3, 0), Direction::Left, EdgePolicy::Clip), ());
+//! let crashes = agents.tick(|cursor, _| {
+//!     cursor.advance();
+//! });
+//! assert!(crashes.is_empty());
+//! let crashes = agents.tick(|cursor, _| {
+//!     cursor.advance();
+//! });
+//! assert_eq!(crashes[0].pos, Pair::new(2, 0));
+//! assert_eq!(agents.len(), 0);
+//! ```
+use crate::{cursor::Cursor, pair::Pair};
+
+/// A cursor with its per-agent state, e.g. the next turn of a cart at an intersection.
+#[derive(Debug, Clone)]
+pub struct Agent<S> {
+    pub cursor: Cursor,
+    pub state: S,
+    alive: bool,
+}
+
+impl<S> Agent<S> {
+    pub fn is_alive(&self) -> bool {
+        self.alive
+    }
+}
+
+/// Two agents ending on the same cell. `moved` is the agent that moved onto `hit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Collision {
+    pub tick: usize,
+    pub pos: Pair<usize>,
+    pub moved: usize,
+    pub hit: usize,
+}
+
+/// Agents addressed by the id returned by `add`, which stays valid after removals.
+#[derive(Debug, Clone)]
+pub struct Agents<S> {
+    agents: Vec<Agent<S>>,
+    ticks: usize,
+}
+
+impl<S> Default for Agents<S> {
+    fn default() -> Self {
+        Agents {
+            agents: Vec::new(),
+            ticks: 0,
+        }
+    }
+}
+
+impl<S> Agents<S> {
+    pub fn new() -> Agents<S> {
+        Agents::default()
+    }
+
+    /// adds an agent, returning its id
+    pub fn add(&mut self, cursor: Cursor, state: S) -> usize {
+        self.agents.push(Agent {
+            cursor,
+            state,
+            alive: true,
+        });
+        self.agents.len() - 1
+    }
+
+    /// removes the agent `id`, returning false if it was already removed
+    pub fn remove(&mut self, id: usize) -> bool {
+        std::mem::replace(&mut self.agents[id].alive, false)
+    }
+
+    pub fn get(&self, id: usize) -> &Agent<S> {
+        &self.agents[id]
+    }
+
+    /// number of living agents
+    pub fn len(&self) -> usize {
+        self.agents.iter().filter(|a| a.alive).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// number of ticks run so far
+    pub fn ticks(&self) -> usize {
+        self.ticks
+    }
+
+    /// ids and agents that are still alive, by id
+    pub fn alive(&self) -> impl Iterator<Item = (usize, &Agent<S>)> {
+        self.agents.iter().enumerate().filter(|(_, a)| a.alive)
+    }
+
+    /// moves each living agent once with `step`, in reading order, removing the agents that
+    /// crash. Returns the collisions of this tick in the order they happened.
+    pub fn tick<F>(&mut self, mut step: F) -> Vec<Collision>
+    where
+        F: FnMut(&mut Cursor, &mut S),
+    {
+        let mut order: Vec<usize> = self.alive().map(|(id, _)| id).collect();
+        order.sort_by_key(|&id| (self.agents[id].cursor.pos.y, self.agents[id].cursor.pos.x));
+
+        let mut collisions = Vec::new();
+        for id in order {
+            if !self.agents[id].alive {
+                continue;
+            }
+            let agent = &mut self.agents[id];
+            step(&mut agent.cursor, &mut agent.state);
+            let pos = agent.cursor.pos;
+            let hit = self
+                .alive()
+                .find(|&(other, a)| other != id && a.cursor.pos == pos)
+                .map(|(other, _)| other);
+            if let Some(hit) = hit {
+                self.agents[id].alive = false;
+                self.agents[hit].alive = false;
+                collisions.push(Collision {
+                    tick: self.ticks,
+                    pos,
+                    moved: id,
+                    hit,
+                });
+            }
+        }
+        self.ticks += 1;
+        collisions
+    }
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cursor::EdgePolicy, direction::Direction, grid::Grid};
+
+    #[test]
+    fn test_crashed_agent_does_not_move() {
+        // a and b swap cells on the same tick: a moves first and crashes into b, so b is
+        // removed before it moves. c, further right, keeps going.
+        let grid = Grid::new(6, 1, ());
+        let mut agents = Agents::new();
+        let cursor = |x, dir| Cursor::new(&grid, Pair::new(x, 0), dir, EdgePolicy::Clip);
+        let a = agents.add(cursor(0, Direction::Right), ());
+        let b = agents.add(cursor(1, Direction::Left), ());
+        let c = agents.add(cursor(4, Direction::Right), ());
+        let crashes = agents.tick(|cursor, _| {
+            cursor.advance();
+        });
+        assert_eq!(
+            crashes,
+            vec![Collision {
+                tick: 0,
+                pos: Pair::new(1, 0),
+                moved: a,
+                hit: b
+            }]
+        );
+        assert_eq!(agents.alive().map(|(id, _)| id).collect::<Vec<_>>(), vec![c]);
+        assert_eq!(agents.get(c).cursor.pos, Pair::new(5, 0));
+        assert!(agents.remove(c) && !agents.remove(c));
+        assert!(agents.is_empty());
+    }
+}
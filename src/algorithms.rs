@@ -0,0 +1,184 @@
+//! Textbook dynamic programming algorithms.
+//!
+//! - `longest_increasing_subsequence`: patience sorting, O(n log n);
+//! - `weighted_interval_scheduling`: the heaviest set of non overlapping intervals, O(n log n);
+//! - `subset_sum`, `count_subsets` and `min_subset`: which subsets of items add up to a
+//!   target, in O(n * target) (divided by 64 for `subset_sum`, which runs on a bitset).
+//!
+//! ```
+//! use aoc_tools::algorithms::{longest_increasing_subsequence, weighted_interval_scheduling};
+//! let values = [3, 1, 4, 1, 5, 9, 2, 6];
+//! let lis = longest_increasing_subsequence(&values);
+//! assert_eq!(lis.iter().map(|&i| values[i]).collect::<Vec<_>>(), vec![1, 4, 5, 6]);
+//!
+//! // (start, end, weight), ends excluded
+//! let jobs = [(0, 3, 5), (2, 5, 6), (4, 6, 5), (6, 7, 4)];
+//! assert_eq!(weighted_interval_scheduling(&jobs), (14, vec![0, 2, 3]));
+//!
+//! use aoc_tools::algorithms::{count_subsets, min_subset};
+//! // containers of 20, 15, 10, 5 and 5 liters holding 25 liters of eggnog
+//! let containers = [20, 15, 10, 5, 5];
+//! assert_eq!(count_subsets(&containers, 25), 4);
+//! assert_eq!(min_subset(&containers, 25), Some((2, 3)));
+//! ```
+use std::ops::Add;
+
+/// returns the indices of a longest strictly increasing subsequence of `values`. Among the
+/// longest ones, the subsequence ending on the smallest possible values is returned.
+pub fn longest_increasing_subsequence<T: Ord>(values: &[T]) -> Vec<usize> {
+    // tails[k]: index of the smallest value ending an increasing subsequence of length k + 1
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev = vec![None; values.len()];
+    for (i, v) in values.iter().enumerate() {
+        let k = tails.partition_point(|&t| values[t] < *v);
+        prev[i] = k.checked_sub(1).map(|k| tails[k]);
+        if k == tails.len() {
+            tails.push(i);
+        } else {
+            tails[k] = i;
+        }
+    }
+
+    let mut lis = Vec::with_capacity(tails.len());
+    let mut cur = tails.last().copied();
+    while let Some(i) = cur {
+        lis.push(i);
+        cur = prev[i];
+    }
+    lis.reverse();
+    lis
+}
+
+/// picks the set of pairwise disjoint `(start, end, weight)` intervals with the largest
+/// total weight. Intervals are half open, so one ending at `t` is compatible with one
+/// starting at `t`.
+///
+/// Returns the total weight and the indices of the chosen intervals, sorted by end.
+pub fn weighted_interval_scheduling<P, W>(intervals: &[(P, P, W)]) -> (W, Vec<usize>)
+where
+    P: Ord + Copy,
+    W: Ord + Copy + Default + Add<Output = W>,
+{
+    let mut order: Vec<usize> = (0..intervals.len()).collect();
+    order.sort_by_key(|&i| intervals[i].1);
+
+    // best[j]: heaviest schedule using the first j intervals of `order`
+    let mut best = vec![W::default(); order.len() + 1];
+    let mut compatible = vec![0; order.len()];
+    for (j, &i) in order.iter().enumerate() {
+        let (start, _, weight) = intervals[i];
+        compatible[j] = order[..j].partition_point(|&k| intervals[k].1 <= start);
+        best[j + 1] = best[j].max(weight + best[compatible[j]]);
+    }
+
+    let mut chosen = Vec::new();
+    let mut j = order.len();
+    while j > 0 {
+        if best[j] == best[j - 1] {
+            j -= 1;
+        } else {
+            chosen.push(order[j - 1]);
+            j = compatible[j - 1];
+        }
+    }
+    chosen.reverse();
+    (best[order.len()], chosen)
+}
+
+//------------------------------
+// Subset sums
+//------------------------------
+
+/// true if some subset of `items` (each used at most once) adds up to `target`. The sums
+/// reachable so far are kept in a bitset, which is shifted and or-ed for each item.
+pub fn subset_sum(items: &[usize], target: usize) -> bool {
+    let words = target / 64 + 1;
+    let mut reach = vec![0u64; words];
+    reach[0] = 1;
+    for &item in items.iter().filter(|&&item| item <= target) {
+        let (shift_words, shift_bits) = (item / 64, item % 64);
+        for i in (shift_words..words).rev() {
+            let j = i - shift_words;
+            let mut shifted = reach[j] << shift_bits;
+            if shift_bits > 0 && j > 0 {
+                shifted |= reach[j - 1] >> (64 - shift_bits);
+            }
+            reach[i] |= shifted;
+        }
+    }
+    reach[target / 64] >> (target % 64) & 1 == 1
+}
+
+/// number of subsets of `items` adding up to `target`. Items are told apart by their
+/// position, so equal items in different positions make different subsets.
+pub fn count_subsets(items: &[usize], target: usize) -> u64 {
+    let mut ways = vec![0u64; target + 1];
+    ways[0] = 1;
+    for &item in items.iter().filter(|&&item| item <= target) {
+        for sum in (item..=target).rev() {
+            ways[sum] += ways[sum - item];
+        }
+    }
+    ways[target]
+}
+
+/// fewest items of a subset adding up to `target`, along with the number of subsets of
+/// that size with that sum. `None` if no subset adds up to `target`.
+pub fn min_subset(items: &[usize], target: usize) -> Option<(usize, u64)> {
+    // best[sum]: (fewest items, number of subsets with that many items)
+    let mut best: Vec<Option<(usize, u64)>> = vec![None; target + 1];
+    best[0] = Some((0, 1));
+    for &item in items.iter().filter(|&&item| item <= target) {
+        for sum in (item..=target).rev() {
+            let Some((count, ways)) = best[sum - item] else {
+                continue;
+            };
+            best[sum] = match best[sum] {
+                Some((c, w)) if c == count + 1 => Some((c, w + ways)),
+                Some((c, w)) if c < count + 1 => Some((c, w)),
+                _ => Some((count + 1, ways)),
+            };
+        }
+    }
+    best[target]
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lis() {
+        assert!(longest_increasing_subsequence::<u8>(&[]).is_empty());
+        // strictly increasing: equal values do not extend a subsequence
+        assert_eq!(longest_increasing_subsequence(&[2, 2, 2]).len(), 1);
+        let values = [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15];
+        let lis = longest_increasing_subsequence(&values);
+        assert_eq!(lis.len(), 6);
+        assert!(lis.windows(2).all(|w| w[0] < w[1] && values[w[0]] < values[w[1]]));
+
+        let (total, chosen) = weighted_interval_scheduling(&[(1, 4, 1), (3, 5, 10), (0, 6, 3), (5, 9, 2)]);
+        assert_eq!((total, chosen), (12, vec![1, 3]));
+        assert_eq!(weighted_interval_scheduling::<u8, u32>(&[]), (0, vec![]));
+    }
+
+    #[test]
+    fn test_subset_sum() {
+        // sums crossing the bitset words
+        let items = [63, 64, 65, 200];
+        for target in 0..500 {
+            let brute = (0..16u32).any(|mask| {
+                let sum: usize = (0..4).filter(|i| mask >> i & 1 == 1).map(|i| items[i]).sum();
+                sum == target
+            });
+            assert_eq!(subset_sum(&items, target), brute, "target {target}");
+        }
+        assert_eq!(count_subsets(&[1, 1, 1, 2], 2), 4);
+        assert_eq!(min_subset(&[1, 1, 1, 2], 2), Some((1, 1)));
+        assert_eq!(min_subset(&[4, 6], 5), None);
+    }
+}
@@ -0,0 +1,88 @@
+//! Convenience layer for character maps, `Grid<u8>` (raw ASCII bytes) and `Grid<char>`.
+//!
+//! Most grids in AoC are character maps. The `AsciiGrid` trait gives them char based
+//! helpers, so the common cases do not need closures or comparisons against references:
+//!
+//! ```
+//! use aoc_tools::{ascii::AsciiGrid, grid::Grid};
+//! let mut map = Grid::from_vec(3, 2, b"#.S..#".to_vec());
+//! assert_eq!(map.find_char('S'), Some((2, 0)));
+//! assert_eq!(map.count_char('#'), 2);
+//! map.replace_char('S', '.');
+//! assert_eq!(map.render(), "#..\n..#\n");
+//! ```
+use crate::grid::Grid;
+
+/// Cell types that represent a single character.
+pub trait AsciiCell: Copy + PartialEq {
+    fn from_char(c: char) -> Self;
+    fn to_char(self) -> char;
+}
+
+impl AsciiCell for u8 {
+    /// # Panics
+    ///
+    /// In debug builds, if `c` is not ASCII.
+    fn from_char(c: char) -> Self {
+        debug_assert!(c.is_ascii(), "'{c}' is not an ASCII character");
+        c as u8
+    }
+
+    fn to_char(self) -> char {
+        self as char
+    }
+}
+
+impl AsciiCell for char {
+    fn from_char(c: char) -> Self {
+        c
+    }
+
+    fn to_char(self) -> char {
+        self
+    }
+}
+
+pub trait AsciiGrid {
+    /// returns the (x, y) coordinates of the first cell holding `c`, in row major order
+    fn find_char(&self, c: char) -> Option<(usize, usize)>;
+
+    /// returns the number of cells holding `c`
+    fn count_char(&self, c: char) -> usize;
+
+    /// replaces every `from` by `to`, returning the number of replaced cells
+    fn replace_char(&mut self, from: char, to: char) -> usize;
+
+    /// renders the grid as text, one line per row, each line terminated by a newline
+    fn render(&self) -> String;
+}
+
+impl<T: AsciiCell> AsciiGrid for Grid<T> {
+    fn find_char(&self, c: char) -> Option<(usize, usize)> {
+        self.find(&T::from_char(c))
+    }
+
+    fn count_char(&self, c: char) -> usize {
+        let c = T::from_char(c);
+        self.iter().filter(|&&v| v == c).count()
+    }
+
+    fn replace_char(&mut self, from: char, to: char) -> usize {
+        let (from, to) = (T::from_char(from), T::from_char(to));
+        let mut count = 0;
+        for v in self.iter_mut().filter(|v| **v == from) {
+            *v = to;
+            count += 1;
+        }
+        count
+    }
+
+    fn render(&self) -> String {
+        let mut s = String::with_capacity((self.len_x + 1) * self.len_y);
+        for y in 0..self.len_y {
+            s.extend(self.row(y).iter().map(|v| v.to_char()));
+            s.push('\n');
+        }
+        s
+    }
+}
@@ -0,0 +1,236 @@
+//! Stepping simulations with automatic cycle skipping.
+//!
+//! - `find_cycle` detects when a deterministic sequence of states starts repeating;
+//! - `Automaton::run` steps a state `n` times, but as soon as a state repeats it jumps ahead
+//!   over the whole cycles, so "after 1e9 cycles" puzzles run in the time of one cycle;
+//! - `step_grid` steps a cellular automaton over a `Grid`, the `Boundary` telling whether
+//!   the world is clipped, toroidal or surrounded by a constant border.
+//!
+//! ```
+//! use aoc_tools::automaton::Automaton;
+//! #[derive(Clone, Hash, PartialEq, Eq)]
+//! struct Counter(u32);
+//! impl Automaton for Counter {
+//!     fn step(&mut self) {
+//!         self.0 = (self.0 + 1) % 7;
+//!     }
+//! }
+//! let mut c = Counter(0);
+//! c.run(1_000_000_000);
+//! assert_eq!(c.0, 1_000_000_000 % 7);
+//! ```
+use crate::{
+    grid::{Connectivity, Grid},
+    hash::FastMap,
+    runner::{count, span},
+};
+use std::hash::Hash;
+
+/// A repeating section found by `find_cycle`: the states at steps `start` and
+/// `start + length` are equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// step at which the cycle is first entered
+    pub start: usize,
+    /// number of steps of one full cycle
+    pub length: usize,
+}
+
+impl Cycle {
+    /// maps step `n` to the earliest step holding the same state
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// steps `start` with `step` until some state repeats, looking at most `max_steps` steps
+/// ahead. Returns the cycle found and the sequence of states up to its first repetition
+/// (`history[i]` is the state after `i` steps), which is handy to read any later state as
+/// `history[cycle.reduce(n)]`.
+pub fn find_cycle<S, F>(start: S, mut step: F, max_steps: usize) -> Option<(Cycle, Vec<S>)>
+where
+    S: Clone + Hash + Eq,
+    F: FnMut(&S) -> S,
+{
+    let _span = span("find_cycle");
+    let mut seen = FastMap::from_iter([(start.clone(), 0)]);
+    let mut history = vec![start];
+    for i in 1..=max_steps {
+        let next = step(&history[i - 1]);
+        if let Some(&first) = seen.get(&next) {
+            return Some((
+                Cycle {
+                    start: first,
+                    length: i - first,
+                },
+                history,
+            ));
+        }
+        seen.insert(next.clone(), i);
+        history.push(next);
+    }
+    None
+}
+
+/// A deterministic simulation advanced one step at a time.
+pub trait Automaton {
+    /// advances the state by one step
+    fn step(&mut self);
+
+    /// advances the state by `n` steps. States are recorded as they are visited, and once
+    /// one repeats the remaining whole cycles are skipped.
+    ///
+    /// Each visited state is cloned into a map, so this trades memory for time; prefer
+    /// calling `step` directly for short runs of big states.
+    fn run(&mut self, n: usize)
+    where
+        Self: Clone + Hash + Eq + Sized,
+    {
+        let _span = span("automaton");
+        let mut seen = FastMap::from_iter([(self.clone(), 0)]);
+        let mut i = 0;
+        while i < n {
+            self.step();
+            i += 1;
+            if let Some(&first) = seen.get(self) {
+                let remaining = (n - i) % (i - first);
+                (0..remaining).for_each(|_| self.step());
+                count("automaton steps", (i + remaining) as u64);
+                return;
+            }
+            seen.insert(self.clone(), i);
+        }
+        count("automaton steps", i as u64);
+    }
+}
+
+//------------------------------
+// Grid automata
+//------------------------------
+
+/// What lies past the edges of the grid, for the neighbours gathered by `step_grid`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Boundary<T> {
+    /// there is nothing past the edges: edge cells have fewer neighbours
+    Clip,
+    /// the edges are glued together, making the grid a torus
+    Wrap,
+    /// every cell past the edges holds this value
+    ConstantBorder(T),
+}
+
+impl<T> Boundary<T> {
+    /// pushes into `out` the neighbours of the cell `idx` of `grid` at `offsets`, in order.
+    /// With `Clip` the neighbours out of the grid are skipped.
+    pub fn neighbors<'a>(&'a self, grid: &'a Grid<T>, idx: usize, offsets: &[(isize, isize)], out: &mut Vec<&'a T>) {
+        for &(dx, dy) in offsets {
+            match (grid.offset(idx, dx, dy), self) {
+                (Some(n), _) => out.push(grid.get_flat(n)),
+                (None, Boundary::Clip) => (),
+                (None, Boundary::ConstantBorder(border)) => out.push(border),
+                (None, Boundary::Wrap) => {
+                    let (x, y) = grid.xy_index(idx);
+                    let wx = (x as isize + dx).rem_euclid(grid.len_x as isize) as usize;
+                    let wy = (y as isize + dy).rem_euclid(grid.len_y as isize) as usize;
+                    out.push(grid.get(wx, wy));
+                }
+            }
+        }
+    }
+}
+
+/// one step of a cellular automaton: each cell becomes `rule(cell, neighbours)`, the
+/// neighbours being the cells adjacent by `connectivity` gathered according to
+/// `boundary`, in the order of the connectivity offsets.
+///
+/// ```
+/// use aoc_tools::{automaton::{step_grid, Boundary}, grid::{Connectivity, Grid}};
+/// // a glider on a 5x5 torus comes back to its place after 20 generations
+/// let life = |&alive: &bool, around: &[&bool]| {
+///     let n = around.iter().filter(|&&&a| a).count();
+///     n == 3 || (alive && n == 2)
+/// };
+/// let start = Grid::from_vec(5, 5, ".#.....#..###".chars().chain(['.'; 12]).map(|c| c == '#').collect());
+/// let mut grid = start.clone();
+/// for _ in 0..20 {
+///     grid = step_grid(&grid, Connectivity::Eight, &Boundary::Wrap, life);
+/// }
+/// assert_eq!(grid, start);
+/// // against a dead border, it crashes into a block in the corner
+/// for _ in 0..20 {
+///     grid = step_grid(&grid, Connectivity::Eight, &Boundary::ConstantBorder(false), life);
+/// }
+/// assert_eq!(grid.iter().filter(|&&a| a).count(), 4);
+/// assert!(*grid.get(4, 4));
+/// ```
+pub fn step_grid<T, F>(grid: &Grid<T>, connectivity: Connectivity, boundary: &Boundary<T>, mut rule: F) -> Grid<T>
+where
+    F: FnMut(&T, &[&T]) -> T,
+{
+    let _span = span("step_grid");
+    count("step_grid cells", grid.size() as u64);
+    let offsets = connectivity.offsets();
+    let mut around = Vec::with_capacity(offsets.len());
+    grid.map_indexed(|x, y, cell| {
+        around.clear();
+        boundary.neighbors(grid, grid.flat_index(x, y), offsets, &mut around);
+        rule(cell, &around)
+    })
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_cycle() {
+        // 0, 1, 2, 3, 4, 2, 3, 4 ...
+        let step = |&s: &u32| if s == 4 { 2 } else { s + 1 };
+        let (cycle, history) = find_cycle(0u32, step, 100).unwrap();
+        assert_eq!(cycle, Cycle { start: 2, length: 3 });
+        assert_eq!(history[cycle.reduce(1_000)], 2 + (1_000 - 2) % 3);
+        assert_eq!(cycle.reduce(1), 1);
+        assert!(find_cycle(0u32, |&s| s + 1, 100).is_none());
+    }
+
+    #[derive(Clone, Hash, PartialEq, Eq)]
+    struct Tail(u32);
+
+    impl Automaton for Tail {
+        fn step(&mut self) {
+            self.0 = if self.0 == 4 { 2 } else { self.0 + 1 };
+        }
+    }
+
+    #[test]
+    fn test_run_with_tail() {
+        for n in 0..20 {
+            let mut fast = Tail(0);
+            fast.run(n);
+            let mut slow = Tail(0);
+            (0..n).for_each(|_| slow.step());
+            assert_eq!(fast.0, slow.0, "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_boundaries() {
+        let grid = Grid::from_vec(3, 2, vec![1, 2, 3, 4, 5, 6]);
+        let sum = |_: &i32, around: &[&i32]| around.iter().copied().sum::<i32>();
+        let clipped = step_grid(&grid, Connectivity::Four, &Boundary::Clip, sum);
+        assert_eq!(clipped.row(0), &[6, 9, 8]);
+        let bordered = step_grid(&grid, Connectivity::Four, &Boundary::ConstantBorder(10), sum);
+        assert_eq!(bordered.row(0), &[26, 19, 28]);
+        // the up and down neighbours of the first row are both the second row
+        let torus = step_grid(&grid, Connectivity::Four, &Boundary::Wrap, sum);
+        assert_eq!(torus.row(0), &[4 + 2 + 4 + 3, 5 + 3 + 5 + 1, 6 + 1 + 6 + 2]);
+    }
+}
@@ -0,0 +1,107 @@
+//! Bitmask helpers for sets of small integers packed into a `u64`.
+//!
+//! ```
+//! use aoc_tools::bits::{bit, iter_set_bits, iter_subsets, set};
+//! let mask = set(bit(0), 3);
+//! assert_eq!(iter_set_bits(mask).collect::<Vec<_>>(), vec![0, 3]);
+//! assert_eq!(iter_subsets(mask).collect::<Vec<_>>(), vec![0b1001, 0b1000, 0b0001, 0]);
+//! ```
+
+/// the mask with only bit `n` set, `n` being in `0..64`
+///
+/// # Panics
+///
+/// Panics if `n` is 64 or more, as do the other helpers taking a bit index.
+pub fn bit(n: u32) -> u64 {
+    assert!(n < 64, "bit {n} does not fit in a u64");
+    1 << n
+}
+
+pub fn is_set(mask: u64, n: u32) -> bool {
+    mask & bit(n) != 0
+}
+
+/// `mask` with bit `n` set
+pub fn set(mask: u64, n: u32) -> u64 {
+    mask | bit(n)
+}
+
+/// `mask` with bit `n` cleared
+pub fn clear(mask: u64, n: u32) -> u64 {
+    mask & !bit(n)
+}
+
+pub fn toggle(mask: u64, n: u32) -> u64 {
+    mask ^ bit(n)
+}
+
+/// number of bits set
+pub fn popcount(mask: u64) -> u32 {
+    mask.count_ones()
+}
+
+/// the mask with the lowest `n` bits set, i.e. the whole set of `n` elements
+pub fn low_bits(n: u32) -> u64 {
+    if n >= 64 { u64::MAX } else { bit(n) - 1 }
+}
+
+/// the mask holding each of the `indices`
+pub fn from_indices<I: IntoIterator<Item = u32>>(indices: I) -> u64 {
+    indices.into_iter().fold(0, set)
+}
+
+/// iterates over the positions of the set bits, from the lowest one
+pub fn iter_set_bits(mut mask: u64) -> impl Iterator<Item = u32> {
+    std::iter::from_fn(move || {
+        if mask == 0 {
+            return None;
+        }
+        let n = mask.trailing_zeros();
+        mask &= mask - 1;
+        Some(n)
+    })
+}
+
+/// iterates over every subset of `mask`, from `mask` itself down to the empty set, using the
+/// `(sub - 1) & mask` trick. A mask with `k` bits set has `2^k` subsets.
+pub fn iter_subsets(mask: u64) -> impl Iterator<Item = u64> {
+    let mut next = Some(mask);
+    std::iter::from_fn(move || {
+        let sub = next?;
+        next = (sub != 0).then(|| (sub - 1) & mask);
+        Some(sub)
+    })
+}
+
+/// iterates over the subsets of `mask` with exactly `k` bits set
+pub fn iter_subsets_of_size(mask: u64, k: u32) -> impl Iterator<Item = u64> {
+    iter_subsets(mask).filter(move |&s| popcount(s) == k)
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bit_range() {
+        assert_eq!(bit(63), 1 << 63);
+        assert_eq!(low_bits(64), u64::MAX);
+        assert!(std::panic::catch_unwind(|| bit(64)).is_err());
+    }
+
+    #[test]
+    fn test_subsets() {
+        let mask = 0b1011_0100;
+        let subsets: Vec<u64> = iter_subsets(mask).collect();
+        assert_eq!(subsets.len(), 1 << popcount(mask));
+        assert!(subsets.iter().all(|&s| s & !mask == 0));
+        assert_eq!(iter_subsets(0).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(iter_subsets_of_size(mask, 2).count(), 6);
+        assert_eq!(from_indices(iter_set_bits(mask)), mask);
+        assert_eq!(clear(toggle(low_bits(3), 5), 0), 0b100110);
+    }
+}
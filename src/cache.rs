@@ -0,0 +1,271 @@
+//! Memoization helpers.
+//!
+//! `Cache` is a keyed memo table that can be cleared in constant time.
+//!
+//! Each entry is stamped with the generation it was written in. Calling `clear` only bumps
+//! the current generation, so entries from previous generations become invisible without
+//! touching the backing storage. This is handy for puzzles running many independent
+//! memoized searches (one DFS per query), where reallocating or clearing a large map
+//! between queries dominates the runtime.
+//!
+//! ```
+//! use aoc_tools::cache::Cache;
+//! let mut cache = Cache::new();
+//! cache.insert("a", 1);
+//! assert_eq!(cache.get(&"a"), Some(&1));
+//! cache.clear();
+//! assert_eq!(cache.get(&"a"), None);
+//! ```
+//!
+//! `AnswerCache` persists the answers of solvers to a file, keyed by day, part and a hash of
+//! the input, so slow days are only solved again when their input changes.
+use crate::{
+    error::AocResult,
+    hash::{FastMap, fx_hash},
+};
+use std::{
+    collections::hash_map::Entry,
+    fmt::Display,
+    fs,
+    hash::Hash,
+    io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone)]
+pub struct Cache<K, V> {
+    entries: FastMap<K, (u32, V)>,
+    generation: u32,
+}
+
+impl<K, V> Default for Cache<K, V> {
+    fn default() -> Self {
+        Cache {
+            entries: FastMap::default(),
+            generation: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq, V> Cache<K, V> {
+    /// creates an empty cache
+    pub fn new() -> Cache<K, V> {
+        Cache::default()
+    }
+
+    /// creates an empty cache with space for at least `capacity` entries
+    pub fn with_capacity(capacity: usize) -> Cache<K, V> {
+        Cache {
+            entries: FastMap::with_capacity_and_hasher(capacity, Default::default()),
+            generation: 0,
+        }
+    }
+
+    /// returns the value stored for `key` in the current generation
+    pub fn get(&self, key: &K) -> Option<&V> {
+        match self.entries.get(key) {
+            Some((generation, value)) if *generation == self.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// returns true if `key` has a value in the current generation
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// stores `value` for `key` in the current generation, returning the previous value if
+    /// it was also written in the current generation.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let generation = self.generation;
+        match self.entries.insert(key, (generation, value)) {
+            Some((old_generation, old)) if old_generation == generation => Some(old),
+            _ => None,
+        }
+    }
+
+    /// returns the value for `key`, computing and storing it with `f` if it is missing or
+    /// stale.
+    pub fn get_or_insert_with<F>(&mut self, key: K, f: F) -> &V
+    where
+        F: FnOnce() -> V,
+    {
+        let generation = self.generation;
+        let entry = match self.entries.entry(key) {
+            Entry::Occupied(o) => {
+                let entry = o.into_mut();
+                if entry.0 != generation {
+                    *entry = (generation, f());
+                }
+                entry
+            }
+            Entry::Vacant(v) => v.insert((generation, f())),
+        };
+        &entry.1
+    }
+
+    /// invalidates every entry in constant time.
+    ///
+    /// Stale entries keep their memory until overwritten. If the generation counter ever
+    /// wraps around, the backing map is really cleared so old entries cannot resurface.
+    pub fn clear(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        if self.generation == 0 {
+            self.entries.clear();
+        }
+    }
+
+    /// the current generation, increased at each `clear`
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+//------------------------------
+// Answers cached on disk
+//------------------------------
+
+/// Answers of solvers stored in a text file, one `day part input-hash answer` line per
+/// entry (tab separated).
+///
+/// The input hash is an `fx_hash` of the input, stable across runs. A toolchain update may
+/// change it, in which case the answers are just computed (and cached) again.
+///
+/// ```no_run
+/// use aoc_tools::cache::AnswerCache;
+/// let input = std::fs::read_to_string("inputs/day11.txt").unwrap();
+/// let mut cache = AnswerCache::open(".answers").unwrap();
+/// let answer = cache.get_or_solve(11, 2, &input, || input.lines().count()).unwrap();
+/// println!("day 11 part 2: {answer}");
+/// ```
+#[derive(Debug, Clone)]
+pub struct AnswerCache {
+    path: PathBuf,
+    answers: FastMap<(u32, u32, u64), String>,
+}
+
+impl AnswerCache {
+    /// loads the answers cached in the file at `path`. A missing file is an empty cache, and
+    /// lines that cannot be read are ignored.
+    pub fn open(path: impl AsRef<Path>) -> AocResult<AnswerCache> {
+        let path = path.as_ref().to_path_buf();
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let answers = content
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(4, '\t');
+                let day = fields.next()?.parse().ok()?;
+                let part = fields.next()?.parse().ok()?;
+                let hash = u64::from_str_radix(fields.next()?, 16).ok()?;
+                Some(((day, part, hash), unescape(fields.next()?)))
+            })
+            .collect();
+        Ok(AnswerCache { path, answers })
+    }
+
+    /// the answer cached for this day, part and input
+    pub fn get(&self, day: u32, part: u32, input: impl AsRef<[u8]>) -> Option<&str> {
+        self.answers
+            .get(&(day, part, fx_hash(input.as_ref())))
+            .map(String::as_str)
+    }
+
+    /// returns the cached answer for this day, part and input, or runs `solve` and saves its
+    /// answer to the file. Answers are stored rendered with `Display`.
+    pub fn get_or_solve<T, F>(&mut self, day: u32, part: u32, input: impl AsRef<[u8]>, solve: F) -> AocResult<String>
+    where
+        T: Display,
+        F: FnOnce() -> T,
+    {
+        let key = (day, part, fx_hash(input.as_ref()));
+        if let Some(answer) = self.answers.get(&key) {
+            return Ok(answer.clone());
+        }
+        let answer = solve().to_string();
+        self.answers.insert(key, answer.clone());
+        self.save()?;
+        Ok(answer)
+    }
+
+    /// forgets every cached answer of `day`, e.g. after fixing a wrong solution
+    pub fn invalidate(&mut self, day: u32) -> AocResult<()> {
+        self.answers.retain(|&(d, _, _), _| d != day);
+        self.save()
+    }
+
+    /// writes every answer to the file
+    pub fn save(&self) -> AocResult<()> {
+        let mut entries: Vec<_> = self.answers.iter().collect();
+        entries.sort_unstable_by_key(|(key, _)| **key);
+        let content: String = entries
+            .into_iter()
+            .map(|((day, part, hash), answer)| format!("{day}\t{part}\t{hash:016x}\t{}\n", escape(answer)))
+            .collect();
+        fs::write(&self.path, content)?;
+        Ok(())
+    }
+}
+
+/// escapes the backslashes, tabs and new lines of multi-line answers (letters drawn on
+/// screens)
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => (),
+        }
+    }
+    out
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer_cache() {
+        let path = std::env::temp_dir().join(format!("aoc_tools_answers_{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut cache = AnswerCache::open(&path).unwrap();
+        let mut calls = 0;
+        let mut solve = |input: &str| {
+            calls += 1;
+            input.len()
+        };
+        assert_eq!(cache.get_or_solve(1, 1, "abc", || solve("abc")).unwrap(), "3");
+        assert_eq!(cache.get_or_solve(1, 1, "abc", || solve("abc")).unwrap(), "3");
+        assert_eq!(cache.get_or_solve(1, 1, "abcd", || solve("abcd")).unwrap(), "4");
+        assert_eq!(calls, 2);
+        cache.get_or_solve(2, 1, "x", || "#\\.\n.\t#").unwrap();
+
+        let reopened = AnswerCache::open(&path).unwrap();
+        assert_eq!(reopened.get(1, 1, "abc"), Some("3"));
+        assert_eq!(reopened.get(1, 2, "abc"), None);
+        assert_eq!(reopened.get(2, 1, "x"), Some("#\\.\n.\t#"));
+
+        cache.invalidate(1).unwrap();
+        assert_eq!(AnswerCache::open(&path).unwrap().get(1, 1, "abc"), None);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,146 @@
+//! A position and a heading walking over a grid.
+//!
+//! `Cursor` holds the state machine of walker simulations (viruses, carts, guards...): it
+//! only stores the grid bounds, so the grid itself stays free to be read or mutated while the
+//! cursor walks over it. What happens at the edges is set by an `EdgePolicy`.
+//!
+//! ```
+//! use aoc_tools::{cursor::{Cursor, EdgePolicy}, direction::Direction, grid::Grid, pair::Pair};
+//! let grid = Grid::new(3, 3, '.');
+//! let mut cursor = Cursor::new(&grid, Pair::new(0, 0), Direction::Right, EdgePolicy::Wrap).track_visited();
+//! for _ in 0..4 {
+//!     cursor.advance();
+//! }
+//! assert_eq!(cursor.pos, Pair::new(1, 0));
+//! assert_eq!(cursor.visited_count(), 3);
+//! ```
+use crate::{direction::Direction, grid::Grid, pair::Pair};
+
+/// What a cursor does when it steps past the edge of the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgePolicy {
+    /// reappear on the opposite side, as on a torus
+    Wrap,
+    /// stay in place, `advance` returns false
+    Clip,
+}
+
+#[derive(Debug, Clone)]
+pub struct Cursor {
+    pub pos: Pair<usize>,
+    pub dir: Direction,
+    pub policy: EdgePolicy,
+    len_x: usize,
+    len_y: usize,
+    visited: Option<Grid<bool>>,
+}
+
+impl Cursor {
+    /// creates a cursor bound to the size of `grid`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is out of the grid.
+    pub fn new<T>(grid: &Grid<T>, pos: Pair<usize>, dir: Direction, policy: EdgePolicy) -> Cursor {
+        assert!(pos.x < grid.len_x && pos.y < grid.len_y, "cursor out of the grid");
+        Cursor {
+            pos,
+            dir,
+            policy,
+            len_x: grid.len_x,
+            len_y: grid.len_y,
+            visited: None,
+        }
+    }
+
+    /// starts recording the visited positions, the current one included
+    pub fn track_visited(mut self) -> Cursor {
+        let mut visited = Grid::new(self.len_x, self.len_y, false);
+        *visited.get_mut(self.pos.x, self.pos.y) = true;
+        self.visited = Some(visited);
+        self
+    }
+
+    /// the position one step ahead, `None` if it is blocked by the edge
+    pub fn peek(&self) -> Option<Pair<usize>> {
+        let (dx, dy) = self.dir.delta();
+        match self.policy {
+            EdgePolicy::Wrap => Some(Pair::new(
+                (self.pos.x + self.len_x).wrapping_add_signed(dx) % self.len_x,
+                (self.pos.y + self.len_y).wrapping_add_signed(dy) % self.len_y,
+            )),
+            EdgePolicy::Clip => self
+                .pos
+                .checked_add_signed(Pair::new(dx, dy))
+                .filter(|p| p.x < self.len_x && p.y < self.len_y),
+        }
+    }
+
+    /// the value one step ahead in `grid`
+    pub fn ahead<'g, T>(&self, grid: &'g Grid<T>) -> Option<&'g T> {
+        self.peek().map(|p| grid.get(p.x, p.y))
+    }
+
+    /// moves one step forward, returning false if the edge blocked the move
+    pub fn advance(&mut self) -> bool {
+        match self.peek() {
+            Some(next) => {
+                self.pos = next;
+                if let Some(visited) = &mut self.visited {
+                    *visited.get_mut(next.x, next.y) = true;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn turn_left(&mut self) {
+        self.dir = self.dir.turn_left();
+    }
+
+    pub fn turn_right(&mut self) {
+        self.dir = self.dir.turn_right();
+    }
+
+    pub fn reverse(&mut self) {
+        self.dir = self.dir.reverse();
+    }
+
+    /// the visited positions, if tracking was enabled with `track_visited`
+    pub fn visited(&self) -> Option<&Grid<bool>> {
+        self.visited.as_ref()
+    }
+
+    /// number of distinct visited positions, 0 if tracking is disabled
+    pub fn visited_count(&self) -> usize {
+        self.visited.as_ref().map_or(0, |v| v.iter().filter(|&&b| b).count())
+    }
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edges() {
+        let grid = Grid::new(2, 3, 0u8);
+        let mut c = Cursor::new(&grid, Pair::new(0, 0), Direction::Up, EdgePolicy::Clip);
+        assert!(!c.advance());
+        assert_eq!(c.pos, Pair::new(0, 0));
+        c.turn_left();
+        assert_eq!(c.peek(), None);
+        c.reverse();
+        assert!(c.advance() && !c.advance());
+        assert_eq!(c.pos, Pair::new(1, 0));
+
+        c.policy = EdgePolicy::Wrap;
+        assert_eq!(c.peek(), Some(Pair::new(0, 0)));
+        c.turn_left();
+        assert_eq!(c.peek(), Some(Pair::new(1, 2)));
+    }
+}
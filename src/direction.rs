@@ -0,0 +1,61 @@
+//! The four cardinal directions on a grid.
+//!
+//! Directions follow the grid convention: `y` grows downwards, so `Up` decreases `y`.
+//!
+//! ```
+//! use aoc_tools::direction::Direction;
+//! let d = Direction::from_char('^').unwrap();
+//! assert_eq!(d.turn_right(), Direction::Right);
+//! assert_eq!(d.delta(), (0, -1));
+//! ```
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// all directions in clockwise order, starting from `Up`
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    /// parses `U/D/L/R`, `^/v/</>` or `N/S/W/E` (case insensitive for letters)
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c.to_ascii_uppercase() {
+            'U' | 'N' | '^' => Some(Direction::Up),
+            'R' | 'E' | '>' => Some(Direction::Right),
+            'D' | 'S' | 'V' => Some(Direction::Down),
+            'L' | 'W' | '<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    /// `(dx, dy)` of a step in this direction
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Right => (1, 0),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+        }
+    }
+
+    pub fn turn_right(self) -> Direction {
+        Direction::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Direction {
+        Direction::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Direction {
+        Direction::ALL[(self as usize + 2) % 4]
+    }
+
+    /// true for `Left` and `Right`
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Direction::Left | Direction::Right)
+    }
+}
@@ -0,0 +1,83 @@
+//! The error type shared by the fallible helpers of the crate.
+//!
+//! Reading, parsing and building grids all fail with an `AocError`, so a solution's `main`
+//! can use `?` on any of them (or return a `Box<dyn Error>`).
+//!
+//! ```
+//! use aoc_tools::{error::AocError, parse::{parse_all, ParseMode}};
+//! let err = parse_all::<u32, _>("1\nx".lines(), ParseMode::Strict).unwrap_err();
+//! assert!(matches!(err, AocError::Parse { line: Some(2), .. }));
+//! assert_eq!(err.to_string(), "line 2: invalid digit found in string (\"x\")");
+//! ```
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    io,
+};
+
+pub type AocResult<T> = Result<T, AocError>;
+
+#[derive(Debug)]
+pub enum AocError {
+    /// failure reading an input
+    Io(io::Error),
+    /// some text could not be parsed. `line` is the number of the line (or piece, for the
+    /// split helpers) starting at 1, when known.
+    Parse {
+        line: Option<usize>,
+        content: String,
+        message: String,
+    },
+    /// a value does not fit in the target type of a conversion
+    Conversion(String),
+    /// a line of a grid does not have the width of the first one
+    Dimensions { line: usize, expected: usize, found: usize },
+}
+
+impl AocError {
+    /// a parse error on `content`, with the message of the underlying error
+    pub fn parse(line: Option<usize>, content: &str, error: impl Display) -> AocError {
+        AocError::Parse {
+            line,
+            content: content.to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AocError::Io(e) => write!(f, "{e}"),
+            AocError::Parse {
+                line: Some(line),
+                content,
+                message,
+            } => write!(f, "line {line}: {message} (\"{content}\")"),
+            AocError::Parse {
+                line: None,
+                content,
+                message,
+            } => write!(f, "{message} (\"{content}\")"),
+            AocError::Conversion(message) => write!(f, "{message}"),
+            AocError::Dimensions { line, expected, found } => {
+                write!(f, "line {line} has width {found}, expected {expected}")
+            }
+        }
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AocError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AocError {
+    fn from(e: io::Error) -> Self {
+        AocError::Io(e)
+    }
+}
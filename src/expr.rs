@@ -0,0 +1,236 @@
+//! Infix expressions evaluated with a configurable operator table.
+//!
+//! Puzzles with "new math" (addition before multiplication, everything left to right) only
+//! differ in operator precedence. Describe the operators in an `OpTable`, and the shunting-yard
+//! algorithm converts the expression to reverse polish notation, which is then evaluated on
+//! a stack.
+//!
+//! ```
+//! use aoc_tools::expr::{eval, Assoc, OpTable};
+//! let standard = OpTable::arithmetic();
+//! assert_eq!(eval("1 + 2 * 3", &standard).unwrap(), 7);
+//!
+//! // addition binds tighter than multiplication
+//! let advanced = OpTable::new()
+//!     .with('+', 2, Assoc::Left, |a, b| a + b)
+//!     .with('*', 1, Assoc::Left, |a, b| a * b);
+//! assert_eq!(eval("2 * 3 + (4 * 5)", &advanced).unwrap(), 46);
+//! ```
+use crate::{
+    error::{AocError, AocResult},
+    hash::FastMap,
+};
+
+/// A piece of an expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    Num(i64),
+    Op(char),
+    Open,
+    Close,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assoc {
+    Left,
+    Right,
+}
+
+/// A binary operator: the higher the `precedence`, the tighter it binds.
+#[derive(Debug, Clone, Copy)]
+pub struct Operator {
+    pub precedence: u8,
+    pub assoc: Assoc,
+    pub apply: fn(i64, i64) -> i64,
+}
+
+/// The binary operators known to the parser, keyed by their symbol.
+#[derive(Debug, Clone, Default)]
+pub struct OpTable {
+    ops: FastMap<char, Operator>,
+}
+
+impl OpTable {
+    /// an empty table
+    pub fn new() -> OpTable {
+        OpTable::default()
+    }
+
+    /// `+`, `-`, `*` and `/` with the usual precedences, plus `^` for powers (right
+    /// associative) and no checks against overflow or division by zero
+    pub fn arithmetic() -> OpTable {
+        OpTable::new()
+            .with('+', 1, Assoc::Left, |a, b| a + b)
+            .with('-', 1, Assoc::Left, |a, b| a - b)
+            .with('*', 2, Assoc::Left, |a, b| a * b)
+            .with('/', 2, Assoc::Left, |a, b| a / b)
+            .with('^', 3, Assoc::Right, |a, b| a.pow(b as u32))
+    }
+
+    /// adds (or replaces) the operator `symbol`
+    pub fn with(mut self, symbol: char, precedence: u8, assoc: Assoc, apply: fn(i64, i64) -> i64) -> OpTable {
+        self.ops.insert(
+            symbol,
+            Operator {
+                precedence,
+                assoc,
+                apply,
+            },
+        );
+        self
+    }
+
+    pub fn get(&self, symbol: char) -> Option<&Operator> {
+        self.ops.get(&symbol)
+    }
+}
+
+/// splits `s` into numbers, the operators of `table` and parentheses. Whitespace is
+/// ignored.
+pub fn tokenize(s: &str, table: &OpTable) -> AocResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            _ if c.is_whitespace() => (),
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            _ if c.is_ascii_digit() => {
+                let mut end = i + 1;
+                while let Some(&(j, d)) = chars.peek() {
+                    if !d.is_ascii_digit() {
+                        break;
+                    }
+                    end = j + 1;
+                    chars.next();
+                }
+                let n = s[i..end].parse().map_err(|e| AocError::parse(None, s, e))?;
+                tokens.push(Token::Num(n));
+            }
+            _ if table.get(c).is_some() => tokens.push(Token::Op(c)),
+            _ => return Err(AocError::parse(None, s, format!("unexpected '{c}' at {i}"))),
+        }
+    }
+    Ok(tokens)
+}
+
+/// reorders infix `tokens` into reverse polish notation with the shunting-yard algorithm.
+/// The output holds no parentheses.
+pub fn to_rpn(tokens: &[Token], table: &OpTable) -> AocResult<Vec<Token>> {
+    let unknown = |c: char| AocError::parse(None, &c.to_string(), format!("unknown operator '{c}'"));
+    let unbalanced = || AocError::parse(None, "", "unbalanced parentheses");
+
+    let mut output = Vec::with_capacity(tokens.len());
+    let mut stack: Vec<Token> = Vec::new();
+    for &token in tokens {
+        match token {
+            Token::Num(_) => output.push(token),
+            Token::Op(c) => {
+                let op = table.get(c).ok_or_else(|| unknown(c))?;
+                while let Some(&Token::Op(top)) = stack.last() {
+                    let top_op = table.get(top).ok_or_else(|| unknown(top))?;
+                    let pops = match op.assoc {
+                        Assoc::Left => top_op.precedence >= op.precedence,
+                        Assoc::Right => top_op.precedence > op.precedence,
+                    };
+                    if !pops {
+                        break;
+                    }
+                    output.push(stack.pop().unwrap());
+                }
+                stack.push(token);
+            }
+            Token::Open => stack.push(token),
+            Token::Close => loop {
+                match stack.pop().ok_or_else(unbalanced)? {
+                    Token::Open => break,
+                    t => output.push(t),
+                }
+            },
+        }
+    }
+    while let Some(token) = stack.pop() {
+        if token == Token::Open {
+            return Err(unbalanced());
+        }
+        output.push(token);
+    }
+    Ok(output)
+}
+
+/// evaluates an expression in reverse polish notation
+pub fn eval_rpn(rpn: &[Token], table: &OpTable) -> AocResult<i64> {
+    let malformed = || AocError::parse(None, "", "malformed expression");
+
+    let mut stack = Vec::new();
+    for &token in rpn {
+        match token {
+            Token::Num(n) => stack.push(n),
+            Token::Op(c) => {
+                let op = table
+                    .get(c)
+                    .ok_or_else(|| AocError::parse(None, &c.to_string(), format!("unknown operator '{c}'")))?;
+                let b = stack.pop().ok_or_else(malformed)?;
+                let a = stack.pop().ok_or_else(malformed)?;
+                stack.push((op.apply)(a, b));
+            }
+            Token::Open | Token::Close => return Err(malformed()),
+        }
+    }
+    match stack[..] {
+        [value] => Ok(value),
+        _ => Err(malformed()),
+    }
+}
+
+/// tokenizes, converts and evaluates the infix expression `s`. Errors of every stage point
+/// at `s`.
+pub fn eval(s: &str, table: &OpTable) -> AocResult<i64> {
+    tokenize(s, table)
+        .and_then(|tokens| to_rpn(&tokens, table))
+        .and_then(|rpn| eval_rpn(&rpn, table))
+        .map_err(|e| match e {
+            AocError::Parse { message, .. } => AocError::parse(None, s, message),
+            e => e,
+        })
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precedence() {
+        let standard = OpTable::arithmetic();
+        assert_eq!(eval("10 - 4 - 3", &standard).unwrap(), 3);
+        assert_eq!(eval("2 ^ 3 ^ 2", &standard).unwrap(), 512);
+        assert_eq!(eval("((2 + 3) * 4) / 5", &standard).unwrap(), 4);
+
+        // every operator with the same precedence, evaluated left to right
+        let flat = OpTable::new()
+            .with('+', 0, Assoc::Left, |a, b| a + b)
+            .with('*', 0, Assoc::Left, |a, b| a * b);
+        assert_eq!(eval("1 + 2 * 3 + 4 * 5 + 6", &flat).unwrap(), 71);
+        let rpn = to_rpn(&tokenize("1 + 2 * 3", &flat).unwrap(), &flat).unwrap();
+        assert_eq!(
+            rpn,
+            vec![
+                Token::Num(1),
+                Token::Num(2),
+                Token::Op('+'),
+                Token::Num(3),
+                Token::Op('*')
+            ]
+        );
+
+        let err = eval("(1 + 2", &flat).unwrap_err();
+        assert_eq!(err.to_string(), "unbalanced parentheses (\"(1 + 2\")");
+        assert!(eval("1 + 2)", &flat).is_err());
+        assert!(eval("1 +", &flat).is_err());
+        assert!(eval("1 - 2", &flat).is_err());
+    }
+}
@@ -0,0 +1,402 @@
+//! Units falling through a grid until they come to rest, as sand pouring into a cave or
+//! water filling the reservoirs of clay under a spring.
+//!
+//! A unit dropped at a source tries its `moves` in order of preference (for sand: straight
+//! down, then down-left, then down-right) and takes the first one into a free cell. It comes
+//! to rest when every move is blocked, and escapes when its preferred free move leaves the
+//! grid. `pour` repeats this until a unit escapes or the source itself gets covered.
+//!
+//! Water (`flow`) falls straight down and spreads sideways over clay instead: it settles
+//! where clay holds it on both sides, and keeps flowing over the edges otherwise.
+//!
+//! ```
+//! use aoc_tools::{falling::{pour, Stop, SAND}, grid::Grid, pair::Pair};
+//! // a cup: rock on both sides and at the bottom
+//! let mut cave = Grid::from_vec(5, 4, "#...##...##...######".chars().collect());
+//! let poured = pour(&mut cave, Pair::new(2, 0), &SAND, |&c| c != '.', 'o');
+//! // two rows of three, then one unit on the source: the top corners stay empty
+//! assert_eq!(poured.rested, 7);
+//! assert_eq!(poured.stop, Stop::SourceBlocked);
+//! ```
+use crate::{grid::Grid, pair::Pair};
+
+//------------------------------
+// Sand
+//------------------------------
+
+/// Preference order of a falling sand unit: down, down-left and down-right.
+pub const SAND: [(isize, isize); 3] = [(0, 1), (-1, 1), (1, 1)];
+
+/// Where a single dropped unit ends up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fall {
+    Rest(Pair<usize>),
+    /// the unit left the grid
+    Escaped,
+    /// the source cell is itself blocked, so nothing can be dropped
+    SourceBlocked,
+}
+
+/// Why `pour` stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Escaped,
+    SourceBlocked,
+}
+
+/// Outcome of `pour`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Poured {
+    /// number of units that came to rest
+    pub rested: usize,
+    pub stop: Stop,
+}
+
+/// follows a single unit dropped at `source`, without modifying the grid. `blocked` tells
+/// the cells units cannot move into.
+pub fn drop_unit<T, F>(grid: &Grid<T>, source: Pair<usize>, moves: &[(isize, isize)], mut blocked: F) -> Fall
+where
+    F: FnMut(&T) -> bool,
+{
+    let mut idx = grid.flat_index(source.x, source.y);
+    if blocked(grid.get_flat(idx)) {
+        return Fall::SourceBlocked;
+    }
+    loop {
+        match next_move(grid, idx, moves, &mut blocked) {
+            Move::To(next) => idx = next,
+            Move::Out => return Fall::Escaped,
+            Move::Stuck => return Fall::Rest(Pair::from_tuple(grid.xy_index(idx))),
+        }
+    }
+}
+
+/// drops units at `source` one after the other, writing `settled` where each comes to rest,
+/// until a unit escapes the grid or the source is covered. `settled` must be `blocked`.
+///
+/// Each unit follows the path of the previous one, so it starts falling from the last cell
+/// of that path which is still free instead of from the source: pouring takes time linear
+/// in the number of units and the length of their paths, not their product.
+pub fn pour<T, F>(
+    grid: &mut Grid<T>,
+    source: Pair<usize>,
+    moves: &[(isize, isize)],
+    mut blocked: F,
+    settled: T,
+) -> Poured
+where
+    T: Clone,
+    F: FnMut(&T) -> bool,
+{
+    let mut rested = 0;
+    let mut path = vec![grid.flat_index(source.x, source.y)];
+    while let Some(&idx) = path.last() {
+        if blocked(grid.get_flat(idx)) {
+            path.pop();
+            continue;
+        }
+        match next_move(grid, idx, moves, &mut blocked) {
+            Move::To(next) => path.push(next),
+            Move::Out => {
+                return Poured {
+                    rested,
+                    stop: Stop::Escaped,
+                };
+            }
+            Move::Stuck => {
+                *grid.get_flat_mut(idx) = settled.clone();
+                rested += 1;
+                path.pop();
+            }
+        }
+    }
+    Poured {
+        rested,
+        stop: Stop::SourceBlocked,
+    }
+}
+
+enum Move {
+    To(usize),
+    Out,
+    Stuck,
+}
+
+/// the first of `moves` from `idx` into a free (or out of grid) cell
+fn next_move<T, F>(grid: &Grid<T>, idx: usize, moves: &[(isize, isize)], blocked: &mut F) -> Move
+where
+    F: FnMut(&T) -> bool,
+{
+    for &(dx, dy) in moves {
+        match grid.offset(idx, dx, dy) {
+            None => return Move::Out,
+            Some(next) if !blocked(grid.get_flat(next)) => return Move::To(next),
+            _ => (),
+        }
+    }
+    Move::Stuck
+}
+
+//------------------------------
+// Water
+//------------------------------
+
+/// Outcome of `flow`: the number of cells of each kind of water in the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Flowed {
+    /// cells water went through without settling
+    pub flowing: usize,
+    pub settled: usize,
+}
+
+/// lets water run from `source` until the grid is filled, marking the cells water went
+/// through `flowing` and the ones where it rests `settled`.
+///
+/// Water falls through free cells. Once on `clay` or settled water, it spreads both ways: if
+/// clay stops it on both sides the row settles and the level rises, else it falls again over
+/// each edge. Streams meeting water already flowing merge into it, and water leaving the grid
+/// is lost. Neither `flowing` nor `settled` may be `clay`.
+///
+/// ```
+/// use aoc_tools::{falling::flow, grid::Grid, pair::Pair};
+/// // a basin two wide under the spring: it fills up, then overflows on both sides
+/// let mut ground = Grid::from_vec(4, 3, "....#..#####".chars().collect());
+/// let flowed = flow(&mut ground, Pair::new(1, 0), |&c| c == '#', '|', '~');
+/// assert_eq!(ground.row(1), &['#', '~', '~', '#']);
+/// assert_eq!(ground.row(0), &['|'; 4]);
+/// assert_eq!((flowed.flowing, flowed.settled), (4, 2));
+/// ```
+pub fn flow<T, F>(grid: &mut Grid<T>, source: Pair<usize>, clay: F, flowing: T, settled: T) -> Flowed
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T) -> bool,
+{
+    let mut water = Reservoir {
+        grid,
+        clay,
+        flowing,
+        settled,
+    };
+    // column and top row of each stream falling, with the row it spreads along once landed.
+    // A stream waits on top of the ones spilling from its row, then spreads again.
+    let mut streams: Vec<(usize, usize, Option<usize>)> = vec![(source.x, source.y, None)];
+    while let Some(&(x, top, row)) = streams.last() {
+        let y = match row {
+            Some(y) => y,
+            None if water.cell(x, top) != Cell::Free => {
+                streams.pop();
+                continue;
+            }
+            None => match water.fall(x, top) {
+                Some(y) => y,
+                None => {
+                    streams.pop();
+                    continue;
+                }
+            },
+        };
+        let last = streams.len() - 1;
+        let (left, right) = (water.spread(x, y, -1), water.spread(x, y, 1));
+        if let (End::Wall(a), End::Wall(b)) = (left, right) {
+            (a..=b).for_each(|x| water.set(x, y, true));
+            if y == top {
+                streams.pop();
+            } else {
+                streams[last].2 = Some(y - 1);
+            }
+            continue;
+        }
+        (left.x()..=right.x()).for_each(|x| water.set(x, y, false));
+        streams[last].2 = Some(y);
+        let mut spilled = false;
+        for end in [left, right] {
+            match end {
+                End::Spill(x) if water.cell(x, y + 1) == Cell::Free => {
+                    streams.push((x, y + 1, None));
+                    spilled = true;
+                }
+                _ => (),
+            }
+        }
+        if !spilled {
+            streams.pop();
+        }
+    }
+
+    let count = |value: &T| water.grid.iter().filter(|&c| c == value).count();
+    Flowed {
+        flowing: count(&water.flowing),
+        settled: count(&water.settled),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Free,
+    Wet,
+    /// clay or settled water
+    Solid,
+}
+
+/// Where water spreading along a row stops, by column.
+#[derive(Debug, Clone, Copy)]
+enum End {
+    /// against clay
+    Wall(usize),
+    /// over a cell it falls into
+    Spill(usize),
+    /// at the side of the grid
+    Edge(usize),
+}
+
+impl End {
+    fn x(self) -> usize {
+        match self {
+            End::Wall(x) | End::Spill(x) | End::Edge(x) => x,
+        }
+    }
+}
+
+struct Reservoir<'a, T, F> {
+    grid: &'a mut Grid<T>,
+    clay: F,
+    flowing: T,
+    settled: T,
+}
+
+impl<T, F> Reservoir<'_, T, F>
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T) -> bool,
+{
+    fn cell(&mut self, x: usize, y: usize) -> Cell {
+        let c = self.grid.get(x, y);
+        if *c == self.settled || (self.clay)(c) {
+            Cell::Solid
+        } else if *c == self.flowing {
+            Cell::Wet
+        } else {
+            Cell::Free
+        }
+    }
+
+    fn set(&mut self, x: usize, y: usize, settle: bool) {
+        let value = if settle { &self.settled } else { &self.flowing };
+        *self.grid.get_mut(x, y) = value.clone();
+    }
+
+    /// wets the cells below x,top down to the first solid one, returning the row just above
+    /// it, or `None` if the water leaves the grid or joins a stream already flowing
+    fn fall(&mut self, x: usize, top: usize) -> Option<usize> {
+        let mut y = top;
+        loop {
+            self.set(x, y, false);
+            if y + 1 == self.grid.len_y {
+                return None;
+            }
+            match self.cell(x, y + 1) {
+                Cell::Free => y += 1,
+                Cell::Wet => return None,
+                Cell::Solid => return Some(y),
+            }
+        }
+    }
+
+    /// where water spreading from x along row y, which lies on solid cells at x, stops going
+    /// in direction `dx`
+    fn spread(&mut self, mut x: usize, y: usize, dx: isize) -> End {
+        loop {
+            if self.cell(x, y + 1) != Cell::Solid {
+                return End::Spill(x);
+            }
+            match x.checked_add_signed(dx).filter(|&next| next < self.grid.len_x) {
+                None => return End::Edge(x),
+                Some(next) if self.cell(next, y) == Cell::Solid => return End::Wall(x),
+                Some(next) => x = next,
+            }
+        }
+    }
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sand() {
+        // the example of the regolith reservoir, shifted so the source is at (6, 0)
+        let rows = [
+            "..........",
+            "..........",
+            "..........",
+            "..........",
+            "....#...##",
+            "....#...#.",
+            "..###...#.",
+            "........#.",
+            "........#.",
+            "#########.",
+        ];
+        let mut cave = Grid::from_vec(10, rows.len(), rows.concat().chars().collect());
+        let source = Pair::new(6, 0);
+        assert_eq!(
+            drop_unit(&cave, source, &SAND, |&c| c != '.'),
+            Fall::Rest(Pair::new(6, 8))
+        );
+        let poured = pour(&mut cave, source, &SAND, |&c| c != '.', 'o');
+        assert_eq!(
+            poured,
+            Poured {
+                rested: 24,
+                stop: Stop::Escaped
+            }
+        );
+        assert_eq!(cave.iter().filter(|&&c| c == 'o').count(), 24);
+
+        // with a floor wide enough, the sand piles up to the source
+        let mut floored = Grid::new(25, 12, '.');
+        (0..25).for_each(|x| *floored.get_mut(x, 11) = '#');
+        let poured = pour(&mut floored, Pair::new(12, 0), &SAND, |&c| c != '.', 'o');
+        assert_eq!(poured.stop, Stop::SourceBlocked);
+        assert_eq!(poured.rested, 11 * 11);
+        assert_eq!(
+            drop_unit(&floored, Pair::new(12, 0), &SAND, |&c| c != '.'),
+            Fall::SourceBlocked
+        );
+    }
+
+    #[test]
+    fn test_water() {
+        // the example of the reservoir research, x from 494 and the spring at (6, 0)
+        let rows = [
+            "..............",
+            "............#.",
+            ".#..#.......#.",
+            ".#..#..#......",
+            ".#..#..#......",
+            ".#.....#......",
+            ".#.....#......",
+            ".#######......",
+            "..............",
+            "..............",
+            "....#.....#...",
+            "....#.....#...",
+            "....#.....#...",
+            "....#######...",
+        ];
+        let mut ground = Grid::from_vec(14, rows.len(), rows.concat().chars().collect());
+        let flowed = flow(&mut ground, Pair::new(6, 0), |&c| c == '#', '|', '~');
+        // the spring row is above the clay, so it is not counted by the puzzle
+        assert_eq!(flowed.flowing + flowed.settled - 1, 57);
+        assert_eq!(flowed.settled, 29);
+        assert_eq!(ground.row(9).iter().collect::<String>(), "...|||||||||..");
+        assert_eq!(ground.row(5).iter().collect::<String>(), ".#~~~~~#|.....");
+
+        // a second run finds everything already wet
+        assert_eq!(flow(&mut ground, Pair::new(6, 0), |&c| c == '#', '|', '~'), flowed);
+    }
+}
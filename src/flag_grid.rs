@@ -0,0 +1,119 @@
+//! A grid holding a small set of flags per cell, packed as the bits of an integer.
+//!
+//! Flags are bit indices, e.g. `Direction as u32` to record the directions a beam or a
+//! guard went through each cell. Setting a flag tells whether it was already set, which is
+//! the test for walking around a loop, without the cost of a `HashSet<(position, dir)>`.
+//!
+//! ```
+//! use aoc_tools::{direction::Direction, flag_grid::FlagGrid};
+//! let mut beams = FlagGrid::<u8>::new(4, 3);
+//! assert!(beams.set_flag(1, 1, Direction::Right as u32));
+//! assert!(beams.set_flag(1, 1, Direction::Down as u32));
+//! // a beam already went right through (1, 1): it would loop
+//! assert!(!beams.set_flag(1, 1, Direction::Right as u32));
+//! assert!(beams.has_flag(1, 1, Direction::Down as u32));
+//! assert_eq!(beams.count_nonempty(), 1);
+//! assert_eq!(beams.count_cells_with(Direction::Up as u32), 0);
+//! ```
+use crate::grid::Grid;
+use std::{
+    fmt::Debug,
+    ops::{BitAnd, BitOr, Not, Shl},
+};
+
+/// Unsigned integers used as the flag sets of a `FlagGrid`.
+pub trait Flags:
+    Copy + Eq + Debug + BitAnd<Output = Self> + BitOr<Output = Self> + Not<Output = Self> + Shl<u32, Output = Self>
+{
+    const BITS: u32;
+    const EMPTY: Self;
+    const ONE: Self;
+
+    fn count(self) -> u32;
+}
+
+macro_rules! impl_flags {
+    ($($t:ty),*) => {
+        $(
+            impl Flags for $t {
+                const BITS: u32 = <$t>::BITS;
+                const EMPTY: $t = 0;
+                const ONE: $t = 1;
+
+                fn count(self) -> u32 {
+                    self.count_ones()
+                }
+            }
+        )*
+    };
+}
+
+impl_flags!(u8, u16, u32, u64);
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FlagGrid<F: Flags> {
+    pub cells: Grid<F>,
+}
+
+impl<F: Flags> FlagGrid<F> {
+    /// creates a grid with no flags set
+    pub fn new(len_x: usize, len_y: usize) -> FlagGrid<F> {
+        FlagGrid {
+            cells: Grid::new(len_x, len_y, F::EMPTY),
+        }
+    }
+
+    /// sets `flag` on cell x,y, returning false if it was already set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds or `flag` is not below the number of bits
+    /// of `F`.
+    pub fn set_flag(&mut self, x: usize, y: usize, flag: u32) -> bool {
+        let bit = Self::bit(flag);
+        let cell = self.cells.get_mut(x, y);
+        let was_set = *cell & bit != F::EMPTY;
+        *cell = *cell | bit;
+        !was_set
+    }
+
+    pub fn clear_flag(&mut self, x: usize, y: usize, flag: u32) {
+        let cell = self.cells.get_mut(x, y);
+        *cell = *cell & !Self::bit(flag);
+    }
+
+    pub fn has_flag(&self, x: usize, y: usize, flag: u32) -> bool {
+        *self.cells.get(x, y) & Self::bit(flag) != F::EMPTY
+    }
+
+    /// the set of flags of cell x,y
+    pub fn flags(&self, x: usize, y: usize) -> F {
+        *self.cells.get(x, y)
+    }
+
+    /// number of flags set on cell x,y
+    pub fn count_flags(&self, x: usize, y: usize) -> u32 {
+        self.flags(x, y).count()
+    }
+
+    /// number of cells with `flag` set
+    pub fn count_cells_with(&self, flag: u32) -> usize {
+        let bit = Self::bit(flag);
+        self.cells.iter().filter(|&&c| c & bit != F::EMPTY).count()
+    }
+
+    /// number of cells with at least one flag, e.g. the energized tiles
+    pub fn count_nonempty(&self) -> usize {
+        self.cells.iter().filter(|&&c| c != F::EMPTY).count()
+    }
+
+    /// clears every flag of every cell
+    pub fn reset(&mut self) {
+        self.cells.iter_mut().for_each(|c| *c = F::EMPTY);
+    }
+
+    fn bit(flag: u32) -> F {
+        assert!(flag < F::BITS, "flag {flag} does not fit in {} bits", F::BITS);
+        F::ONE << flag
+    }
+}
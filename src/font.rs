@@ -0,0 +1,110 @@
+//! The 4x6 letter font of the Advent of Code screens.
+//!
+//! `render` draws a string as a `Grid<bool>`, one blank column after each letter as on the
+//! puzzle screens, and `read` goes the other way. Rendering is handy to build fixtures and
+//! to eyeball an image against the expected answer.
+//!
+//! ```
+//! use aoc_tools::font::{read, render};
+//! let screen = render("HI").unwrap();
+//! assert_eq!((screen.len_x, screen.len_y), (10, 6));
+//! assert_eq!(read(&screen).as_deref(), Some("HI"));
+//! ```
+use crate::grid::Grid;
+
+/// glyph height in cells
+pub const HEIGHT: usize = 6;
+
+/// known letters and their glyphs, one string per row. Only the letters that have shown up in
+/// puzzles are known; `Y` is the only one 5 cells wide.
+const GLYPHS: [(char, [&str; HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+fn glyph(c: char) -> Option<&'static [&'static str; HEIGHT]> {
+    GLYPHS.iter().find(|(g, _)| *g == c).map(|(_, rows)| rows)
+}
+
+/// draws `text` with the puzzle font, lit cells being `true`. Returns `None` if `text` has
+/// a char missing from the font.
+pub fn render(text: &str) -> Option<Grid<bool>> {
+    let glyphs: Vec<_> = text.chars().map(glyph).collect::<Option<_>>()?;
+    let len_x = glyphs.iter().map(|g| g[0].len() + 1).sum();
+    let mut grid = Grid::new(len_x, HEIGHT, false);
+    let mut x0 = 0;
+    for g in glyphs {
+        for (y, row) in g.iter().enumerate() {
+            for (dx, c) in row.chars().enumerate() {
+                *grid.get_mut(x0 + dx, y) = c == '#';
+            }
+        }
+        x0 += g[0].len() + 1;
+    }
+    Some(grid)
+}
+
+/// reads the letters drawn on a screen `HEIGHT` cells tall, skipping the blank columns
+/// between them. Returns `None` if the screen has another height or some glyph is unknown.
+pub fn read(screen: &Grid<bool>) -> Option<String> {
+    if screen.len_y != HEIGHT {
+        return None;
+    }
+    let blank_col = |x: usize| screen.iter_col(x).all(|&lit| !lit);
+    let matches = |x0: usize, rows: &[&str; HEIGHT]| {
+        let width = rows[0].len();
+        x0 + width <= screen.len_x
+            && (x0 + width == screen.len_x || blank_col(x0 + width))
+            && rows.iter().enumerate().all(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .all(|(dx, c)| *screen.get(x0 + dx, y) == (c == '#'))
+            })
+    };
+    let mut text = String::new();
+    let mut x = 0;
+    while x < screen.len_x {
+        // glyphs are tried before skipping blank columns, as `I` and `J` start with some
+        if let Some((c, rows)) = GLYPHS.iter().find(|(_, rows)| matches(x, rows)) {
+            text.push(*c);
+            x += rows[0].len();
+        } else if blank_col(x) {
+            x += 1;
+        } else {
+            return None;
+        }
+    }
+    Some(text)
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_every_glyph() {
+        let all: String = GLYPHS.iter().map(|(c, _)| c).collect();
+        assert_eq!(read(&render(&all).unwrap()), Some(all));
+        assert!(render("a").is_none());
+    }
+}
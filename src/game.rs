@@ -0,0 +1,241 @@
+//! Searches over the states of turn-based games.
+//!
+//! States are any `Hash + Eq + Clone` value holding everything that matters for the rest of
+//! the game (positions, hit points, whose turn it is...), and games are described with
+//! closures generating the moves and telling when a game is over:
+//!
+//! - `min_cost_to_win`: the cheapest sequence of moves reaching a win, e.g. the mana spent
+//!   in the wizard simulator;
+//! - `count_wins`: in how many universes each player wins, memoized on the states, e.g.
+//!   Dirac dice;
+//! - `outcomes`: every final state with the number of ways it is reached;
+//! - `minimax`: the score of a state when both sides play their best.
+//!
+//! ```
+//! use aoc_tools::game::count_wins;
+//! // race to 3: each turn the player moves 1 or 2 squares, in two universes
+//! #[derive(Clone, Hash, PartialEq, Eq)]
+//! struct Race { pos: [u8; 2], turn: usize }
+//! let moves = |s: &Race| {
+//!     [1, 2].map(|step| {
+//!         let mut next = s.clone();
+//!         next.pos[s.turn] += step;
+//!         next.turn = 1 - s.turn;
+//!         (next, 1)
+//!     })
+//! };
+//! let winner = |s: &Race| s.pos.iter().position(|&p| p >= 3);
+//! let wins = count_wins(Race { pos: [0, 0], turn: 0 }, moves, winner);
+//! assert_eq!(wins, [8, 3]);
+//! ```
+use crate::{
+    hash::FastMap,
+    pathfinding::{PathResult, dijkstra},
+};
+use std::{hash::Hash, ops::Add};
+
+/// State of a game from the point of view of the player searching for a win.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Ongoing,
+}
+
+/// cheapest way of reaching a winning state from `start`, `moves` yielding the states
+/// reachable in one turn with the (non-negative) cost of the move. Lost states are not
+/// explored further. Returns `None` if no win can be reached.
+///
+/// This is Dijkstra's algorithm, so the path of states to the win is available from the
+/// result.
+///
+/// ```
+/// use aoc_tools::game::{min_cost_to_win, Outcome};
+/// // (boss hit points, player hit points): a spell costs 5 and deals 2, or costs 8 and
+/// // deals 4; the boss hits back for 3 after each spell
+/// let moves = |&(boss, player): &(i32, i32)| [(5, 2), (8, 4)].map(|(cost, dmg)| ((boss - dmg, player - 3), cost));
+/// let outcome = |&(boss, player): &(i32, i32)| match (boss <= 0, player <= 0) {
+///     (true, _) => Outcome::Win,
+///     (_, true) => Outcome::Loss,
+///     _ => Outcome::Ongoing,
+/// };
+/// let best = min_cost_to_win((8, 7), moves, outcome).unwrap();
+/// assert_eq!(best.cost, 16);
+/// ```
+pub fn min_cost_to_win<S, C, FM, IM, FO>(start: S, mut moves: FM, outcome: FO) -> Option<PathResult<S, C>>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    FM: FnMut(&S) -> IM,
+    IM: IntoIterator<Item = (S, C)>,
+    FO: Fn(&S) -> Outcome,
+{
+    dijkstra(
+        start,
+        |s| {
+            let ongoing = outcome(s) == Outcome::Ongoing;
+            moves(s).into_iter().filter(move |_| ongoing)
+        },
+        |s| outcome(s) == Outcome::Win,
+    )
+}
+
+/// counts the games won by each of the `P` players, `moves` yielding the states reachable
+/// in one turn with the number of universes (or ways) leading to each, and `winner` telling
+/// which player won a finished game. Results are memoized on the states, so the games only
+/// need a small number of distinct states, not of histories.
+///
+/// # Panics
+///
+/// Panics if `winner` returns a player index not below `P`.
+pub fn count_wins<S, FM, IM, FW, const P: usize>(start: S, moves: FM, winner: FW) -> [u64; P]
+where
+    S: Hash + Eq + Clone,
+    FM: Fn(&S) -> IM,
+    IM: IntoIterator<Item = (S, u64)>,
+    FW: Fn(&S) -> Option<usize>,
+{
+    fn count<S, FM, IM, FW, const P: usize>(
+        state: &S,
+        moves: &FM,
+        winner: &FW,
+        memo: &mut FastMap<S, [u64; P]>,
+    ) -> [u64; P]
+    where
+        S: Hash + Eq + Clone,
+        FM: Fn(&S) -> IM,
+        IM: IntoIterator<Item = (S, u64)>,
+        FW: Fn(&S) -> Option<usize>,
+    {
+        if let Some(&wins) = memo.get(state) {
+            return wins;
+        }
+        let mut wins = [0; P];
+        match winner(state) {
+            Some(player) => wins[player] = 1,
+            None => {
+                for (next, ways) in moves(state) {
+                    let sub = count(&next, moves, winner, memo);
+                    wins.iter_mut().zip(sub).for_each(|(w, s)| *w += ways * s);
+                }
+            }
+        }
+        memo.insert(state.clone(), wins);
+        wins
+    }
+
+    count(&start, &moves, &winner, &mut FastMap::default())
+}
+
+/// every final state reachable from `start`, with the number of ways to reach it (the
+/// product of the weights yielded by `moves` along the way, summed over all the paths).
+///
+/// States are expanded one turn at a time and equal states merged, so a game where many
+/// histories lead to the same states stays cheap. The game must end: a state that is not
+/// `is_final` and has no moves is dropped.
+pub fn outcomes<S, FM, IM, FF>(start: S, mut moves: FM, mut is_final: FF) -> FastMap<S, u64>
+where
+    S: Hash + Eq + Clone,
+    FM: FnMut(&S) -> IM,
+    IM: IntoIterator<Item = (S, u64)>,
+    FF: FnMut(&S) -> bool,
+{
+    let mut finals = FastMap::default();
+    let mut frontier = FastMap::from_iter([(start, 1u64)]);
+    while !frontier.is_empty() {
+        let mut next_frontier = FastMap::default();
+        for (state, count) in frontier {
+            if is_final(&state) {
+                *finals.entry(state).or_default() += count;
+                continue;
+            }
+            for (next, ways) in moves(&state) {
+                *next_frontier.entry(next).or_default() += count * ways;
+            }
+        }
+        frontier = next_frontier;
+    }
+    finals
+}
+
+/// score of `state` with both players at their best: `score` gives the value of finished
+/// games (`None` while the game goes on), which the player for whom `maximizing` returns
+/// true tries to maximize and the other one to minimize. Memoized on the states.
+///
+/// ```
+/// use aoc_tools::game::minimax;
+/// // take 1 or 2 sticks, the player taking the last one wins (+1 for the first player)
+/// let moves = |&(sticks, first): &(u32, bool)| (1..=2.min(sticks)).map(move |n| (sticks - n, !first));
+/// // the game ends when no sticks are left, the winner being who moved last
+/// let score = |&(sticks, first): &(u32, bool)| (sticks == 0).then_some(if first { -1 } else { 1 });
+/// assert_eq!(minimax((4, true), moves, score, |s| s.1), 1);
+/// assert_eq!(minimax((3, true), moves, score, |s| s.1), -1);
+/// ```
+pub fn minimax<S, FM, IM, FS, FP>(state: S, moves: FM, score: FS, maximizing: FP) -> i64
+where
+    S: Hash + Eq + Clone,
+    FM: Fn(&S) -> IM,
+    IM: IntoIterator<Item = S>,
+    FS: Fn(&S) -> Option<i64>,
+    FP: Fn(&S) -> bool,
+{
+    struct Game<FM, FS, FP> {
+        moves: FM,
+        score: FS,
+        maximizing: FP,
+    }
+
+    fn eval<S, FM, IM, FS, FP>(state: &S, game: &Game<FM, FS, FP>, memo: &mut FastMap<S, i64>) -> i64
+    where
+        S: Hash + Eq + Clone,
+        FM: Fn(&S) -> IM,
+        IM: IntoIterator<Item = S>,
+        FS: Fn(&S) -> Option<i64>,
+        FP: Fn(&S) -> bool,
+    {
+        if let Some(&value) = memo.get(state) {
+            return value;
+        }
+        let value = (game.score)(state).unwrap_or_else(|| {
+            let scores = (game.moves)(state).into_iter().map(|next| eval(&next, game, memo));
+            let best = if (game.maximizing)(state) {
+                scores.max()
+            } else {
+                scores.min()
+            };
+            best.expect("an unfinished game must have moves")
+        });
+        memo.insert(state.clone(), value);
+        value
+    }
+
+    let game = Game {
+        moves,
+        score,
+        maximizing,
+    };
+    eval(&state, &game, &mut FastMap::default())
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outcomes_match_counts() {
+        // three rolls of a 3 sided die: sums 3..=9 with the trinomial counts
+        let moves = |&(rolls, sum): &(u8, u8)| (1..=3).map(move |d| ((rolls + 1, sum + d), 1));
+        let finals = outcomes((0u8, 0u8), moves, |&(rolls, _)| rolls == 3);
+        let mut by_sum: Vec<(u8, u64)> = finals.into_iter().map(|((_, sum), n)| (sum, n)).collect();
+        by_sum.sort_unstable();
+        assert_eq!(by_sum, vec![(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)]);
+
+        // the same game as a race: player 0 wins if the sum is even
+        let winner = |&(rolls, sum): &(u8, u8)| (rolls == 3).then_some((sum % 2) as usize);
+        assert_eq!(count_wins((0u8, 0u8), moves, winner), [3 + 7 + 3, 1 + 6 + 6 + 1]);
+    }
+}
@@ -0,0 +1,343 @@
+//! Simple 2d geometry shapes.
+//!
+//! - `Rect`: an axis aligned rectangle given by its origin and size, parseable from the
+//!   fabric claims format ("#1 @ 1,3: 4x4");
+//! - `rasterize` and `count_overlaps` paint many rectangles onto a `Grid` of coverage
+//!   counts;
+//! - `points_in` iterates over every point of a pair of coordinate ranges;
+//! - `Polygon`: a simple polygon over integer vertices, with its area by the shoelace
+//!   formula and lattice point counts by Pick's theorem.
+//!
+//! ```
+//! use aoc_tools::geometry::{count_overlaps, Claim};
+//! let claims: Vec<Claim> = ["#1 @ 1,3: 4x4", "#2 @ 3,1: 4x4", "#3 @ 5,5: 2x2"]
+//!     .iter()
+//!     .map(|s| s.parse().unwrap())
+//!     .collect();
+//! assert_eq!(count_overlaps(claims.iter().map(|c| &c.rect)), 4);
+//! assert_eq!(claims[0].rect.intersect(&claims[2].rect), None);
+//! ```
+use crate::{direction::Direction, error::AocError, grid::Grid, pair::Pair, sparse_grid::SparseGrid};
+use std::{error::Error, ops::Range, str::FromStr};
+
+/// An axis aligned rectangle covering the cells from `origin` (inclusive) to
+/// `origin + size` (exclusive).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub origin: Pair<usize>,
+    pub size: Pair<usize>,
+}
+
+impl Rect {
+    pub fn new(origin: Pair<usize>, size: Pair<usize>) -> Rect {
+        Rect { origin, size }
+    }
+
+    /// number of cells covered
+    pub fn area(&self) -> usize {
+        self.size.x * self.size.y
+    }
+
+    /// the corner opposite to the origin, exclusive
+    pub fn end(&self) -> Pair<usize> {
+        self.origin + self.size
+    }
+
+    pub fn contains(&self, p: Pair<usize>) -> bool {
+        let end = self.end();
+        (self.origin.x..end.x).contains(&p.x) && (self.origin.y..end.y).contains(&p.y)
+    }
+
+    /// returns the overlapping region of both rectangles, if it is not empty
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let (end, other_end) = (self.end(), other.end());
+        let origin = Pair::new(self.origin.x.max(other.origin.x), self.origin.y.max(other.origin.y));
+        let end = Pair::new(end.x.min(other_end.x), end.y.min(other_end.y));
+        if origin.x >= end.x || origin.y >= end.y {
+            return None;
+        }
+        Some(Rect::new(origin, end - origin))
+    }
+
+    /// iterates over the covered cells in row major order
+    pub fn points(&self) -> impl Iterator<Item = Pair<usize>> {
+        self.iter_points(Order::RowMajor)
+    }
+
+    /// iterates over the covered cells in the given order
+    pub fn iter_points(&self, order: Order) -> Points<Range<usize>> {
+        let (origin, end) = (self.origin, self.end());
+        points_in(origin.x..end.x, origin.y..end.y, order)
+    }
+}
+
+impl FromStr for Rect {
+    type Err = AocError;
+
+    /// parses "x,y: wxh", optionally preceded by a claim id as in "#1 @ 1,3: 4x4". Blank
+    /// spaces around the separators are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_rect(s).map_err(|e| AocError::parse(None, s, e))
+    }
+}
+
+fn parse_rect(s: &str) -> Result<Rect, Box<dyn Error>> {
+    let s = s.split_once('@').map_or(s, |(_, rect)| rect);
+    let (origin, size) = s.split_once(':').ok_or("Missing ':' between origin and size.")?;
+    let (x, y) = origin.split_once(',').ok_or("Missing ',' in the origin.")?;
+    let (w, h) = size.split_once('x').ok_or("Missing 'x' in the size.")?;
+    Ok(Rect::new(
+        Pair::new(x.trim().parse()?, y.trim().parse()?),
+        Pair::new(w.trim().parse()?, h.trim().parse()?),
+    ))
+}
+
+/// A rectangle with an id, as in the "#1 @ 1,3: 4x4" fabric claims.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Claim {
+    pub id: u32,
+    pub rect: Rect,
+}
+
+impl FromStr for Claim {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_claim(s).map_err(|e| AocError::parse(None, s, e))
+    }
+}
+
+fn parse_claim(s: &str) -> Result<Claim, Box<dyn Error>> {
+    let (id, rect) = s.split_once('@').ok_or("Missing '@' after the claim id.")?;
+    let id = id.trim().strip_prefix('#').ok_or("Claim id must start with '#'.")?;
+    Ok(Claim {
+        id: id.parse()?,
+        rect: rect.parse()?,
+    })
+}
+
+//------------------------------
+// Polygons
+//------------------------------
+
+/// A simple polygon (its edges do not cross) given by its vertices in order, either
+/// clockwise or counterclockwise. The last vertex connects back to the first one.
+///
+/// ```
+/// use aoc_tools::{direction::Direction::*, geometry::Polygon, pair::Pair};
+/// // a 3x3 block of cells dug around by the trench R2, D2, L2, U2
+/// let plan = Polygon::from_moves(Pair::new(0, 0), [(Right, 2), (Down, 2), (Left, 2), (Up, 2)]);
+/// assert_eq!(plan.area(), 4.0);
+/// assert_eq!(plan.boundary_points(), 8);
+/// assert_eq!(plan.interior_points(), 1);
+/// assert!(plan.contains(Pair::new(2, 1)));
+/// assert_eq!(plan.rasterize().len(), 9);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    pub vertices: Vec<Pair<i64>>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Pair<i64>>) -> Polygon {
+        Polygon { vertices }
+    }
+
+    /// builds a polygon by walking from `start`, as in the dig plan puzzles. The walk is
+    /// expected to come back to `start`.
+    pub fn from_moves<I>(start: Pair<i64>, moves: I) -> Polygon
+    where
+        I: IntoIterator<Item = (Direction, u32)>,
+    {
+        let mut p = start;
+        let mut vertices = vec![start];
+        for (dir, n) in moves {
+            let (dx, dy) = dir.delta();
+            p += Pair::new(dx as i64, dy as i64) * n as i64;
+            vertices.push(p);
+        }
+        if vertices.len() > 1 && vertices.last() == Some(&start) {
+            vertices.pop();
+        }
+        Polygon::new(vertices)
+    }
+
+    /// the edges as pairs of consecutive vertices, the closing edge included
+    pub fn edges(&self) -> impl Iterator<Item = (Pair<i64>, Pair<i64>)> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| (self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    /// twice the enclosed area, which is always an integer
+    pub fn double_area(&self) -> i64 {
+        self.edges().map(|(a, b)| a.x * b.y - b.x * a.y).sum::<i64>().abs()
+    }
+
+    /// enclosed area, by the shoelace formula
+    pub fn area(&self) -> f64 {
+        self.double_area() as f64 / 2.0
+    }
+
+    /// length of the boundary
+    pub fn perimeter(&self) -> f64 {
+        self.edges()
+            .map(|(a, b)| Pair::new(a.x as f64, a.y as f64).distance(&Pair::new(b.x as f64, b.y as f64)))
+            .sum()
+    }
+
+    /// number of lattice points on the boundary
+    pub fn boundary_points(&self) -> i64 {
+        self.edges()
+            .map(|(a, b)| gcd((b.x - a.x).abs(), (b.y - a.y).abs()))
+            .sum()
+    }
+
+    /// number of lattice points strictly inside, by Pick's theorem
+    pub fn interior_points(&self) -> i64 {
+        (self.double_area() - self.boundary_points() + 2) / 2
+    }
+
+    /// returns true if `p` is inside the polygon or on its boundary
+    pub fn contains(&self, p: Pair<i64>) -> bool {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let cross = (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x);
+            let within = p.x >= a.x.min(b.x) && p.x <= a.x.max(b.x) && p.y >= a.y.min(b.y) && p.y <= a.y.max(b.y);
+            if cross == 0 && within {
+                return true;
+            }
+            // even-odd rule on a ray going right from `p`
+            if (a.y > p.y) != (b.y > p.y) {
+                let x_cross = a.x as f64 + (p.y - a.y) as f64 * (b.x - a.x) as f64 / (b.y - a.y) as f64;
+                if (p.x as f64) < x_cross {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+
+    /// the lattice points inside or on the boundary. Every point of the bounding box is
+    /// tested, so prefer the analytic counts for big polygons.
+    pub fn rasterize(&self) -> SparseGrid<bool> {
+        let (Some(min_x), Some(max_x)) = (
+            self.vertices.iter().map(|v| v.x).min(),
+            self.vertices.iter().map(|v| v.x).max(),
+        ) else {
+            return SparseGrid::new();
+        };
+        let min_y = self.vertices.iter().map(|v| v.y).min().unwrap();
+        let max_y = self.vertices.iter().map(|v| v.y).max().unwrap();
+        points_in(min_x..=max_x, min_y..=max_y, Order::RowMajor)
+            .filter(|&p| self.contains(p))
+            .map(|p| (p, true))
+            .collect()
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+//------------------------------
+// Point iteration
+//------------------------------
+
+/// Traversal order of `points_in`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// `x` varies fastest: the whole first row, then the second...
+    RowMajor,
+    /// `y` varies fastest: the whole first column, then the second...
+    ColumnMajor,
+}
+
+/// Iterator returned by `points_in`.
+#[derive(Debug, Clone)]
+pub struct Points<R: Iterator> {
+    outer: R,
+    template: R,
+    inner: R,
+    current: Option<R::Item>,
+    order: Order,
+}
+
+impl<R> Iterator for Points<R>
+where
+    R: Iterator + Clone,
+    R::Item: Copy,
+{
+    type Item = Pair<R::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let (Some(o), Some(i)) = (self.current, self.inner.next()) {
+                return Some(match self.order {
+                    Order::RowMajor => Pair::new(i, o),
+                    Order::ColumnMajor => Pair::new(o, i),
+                });
+            }
+            self.current = Some(self.outer.next()?);
+            self.inner = self.template.clone();
+        }
+    }
+}
+
+/// iterates over every point with `x` in `xs` and `y` in `ys`, in the given order. Any
+/// cloneable iterator works as a range, e.g. exclusive and inclusive ranges or stepped
+/// ranges, as long as both have the same type.
+///
+/// ```
+/// use aoc_tools::{geometry::{points_in, Order}, pair::Pair};
+/// let row_major: Vec<_> = points_in(0..=1, 5..=6, Order::RowMajor).map(|p| p.tuple()).collect();
+/// assert_eq!(row_major, vec![(0, 5), (1, 5), (0, 6), (1, 6)]);
+/// let column_major: Vec<_> = points_in(0..=1, 5..=6, Order::ColumnMajor).map(|p| p.tuple()).collect();
+/// assert_eq!(column_major, vec![(0, 5), (0, 6), (1, 5), (1, 6)]);
+/// assert_eq!(points_in(-1..2, -1..2, Order::RowMajor).filter(|&p| p != Pair::new(0, 0)).count(), 8);
+/// ```
+pub fn points_in<R>(xs: R, ys: R, order: Order) -> Points<R>
+where
+    R: Iterator + Clone,
+{
+    let (outer, template) = match order {
+        Order::RowMajor => (ys, xs),
+        Order::ColumnMajor => (xs, ys),
+    };
+    Points {
+        outer,
+        inner: template.clone(),
+        template,
+        current: None,
+        order,
+    }
+}
+
+//------------------------------
+// Rasterization
+//------------------------------
+
+/// paints the rectangles onto a grid where each cell counts how many rectangles cover it.
+/// The grid is just large enough to hold every rectangle.
+pub fn rasterize<'a, I>(rects: I) -> Grid<u32>
+where
+    I: IntoIterator<Item = &'a Rect>,
+{
+    let rects: Vec<&Rect> = rects.into_iter().collect();
+    let len_x = rects.iter().map(|r| r.end().x).max().unwrap_or(0);
+    let len_y = rects.iter().map(|r| r.end().y).max().unwrap_or(0);
+    let mut grid = Grid::new(len_x, len_y, 0);
+    for rect in rects {
+        for p in rect.points() {
+            grid[p] += 1;
+        }
+    }
+    grid
+}
+
+/// number of cells covered by two or more rectangles
+pub fn count_overlaps<'a, I>(rects: I) -> usize
+where
+    I: IntoIterator<Item = &'a Rect>,
+{
+    rasterize(rects).iter().filter(|&&c| c > 1).count()
+}
@@ -0,0 +1,194 @@
+//! 3d geometry: axis aligned cuboids and their algebra.
+//!
+//! A `Cuboid` covers the integer points from `min` (inclusive) to `max` (exclusive) on each
+//! axis. Subtracting a cuboid from another splits the remainder into disjoint cuboids, which
+//! is what `Reactor` relies on to keep the volume of a union exact under on/off steps.
+//!
+//! ```
+//! use aoc_tools::geometry3::{Cuboid, Reactor};
+//! let mut reactor = Reactor::new();
+//! reactor.apply(true, "x=10..12,y=10..12,z=10..12".parse().unwrap());
+//! reactor.apply(true, "x=11..13,y=11..13,z=11..13".parse().unwrap());
+//! reactor.apply(false, "x=9..11,y=9..11,z=9..11".parse().unwrap());
+//! reactor.apply(true, "x=10..10,y=10..10,z=10..10".parse().unwrap());
+//! assert_eq!(reactor.volume(), 39);
+//! ```
+use crate::error::AocError;
+use std::{error::Error, str::FromStr};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    pub min: [i64; 3],
+    pub max: [i64; 3],
+}
+
+impl Cuboid {
+    /// cuboid from `min` (inclusive) to `max` (exclusive)
+    pub fn new(min: [i64; 3], max: [i64; 3]) -> Cuboid {
+        Cuboid { min, max }
+    }
+
+    /// cuboid from `min` to `max`, both inclusive
+    pub fn from_inclusive(min: [i64; 3], max: [i64; 3]) -> Cuboid {
+        Cuboid::new(min, max.map(|m| m + 1))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..3).any(|i| self.min[i] >= self.max[i])
+    }
+
+    /// number of integer points covered
+    pub fn volume(&self) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+        (0..3).map(|i| (self.max[i] - self.min[i]) as u64).product()
+    }
+
+    pub fn contains(&self, p: [i64; 3]) -> bool {
+        (0..3).all(|i| (self.min[i]..self.max[i]).contains(&p[i]))
+    }
+
+    /// returns the overlapping region of both cuboids, if it is not empty
+    pub fn intersect(&self, other: &Cuboid) -> Option<Cuboid> {
+        let c = Cuboid::new(
+            [0, 1, 2].map(|i| self.min[i].max(other.min[i])),
+            [0, 1, 2].map(|i| self.max[i].min(other.max[i])),
+        );
+        (!c.is_empty()).then_some(c)
+    }
+
+    /// splits the part of `self` not covered by `other` into at most 6 disjoint cuboids
+    pub fn subtract(&self, other: &Cuboid) -> Vec<Cuboid> {
+        let Some(hole) = self.intersect(other) else {
+            return vec![*self];
+        };
+        let mut pieces = Vec::new();
+        // slab by slab: cut what lies below and above the hole on one axis, then shrink the
+        // remainder to the hole on that axis and go on with the next one
+        let mut rest = *self;
+        for axis in 0..3 {
+            if rest.min[axis] < hole.min[axis] {
+                let mut below = rest;
+                below.max[axis] = hole.min[axis];
+                pieces.push(below);
+            }
+            if hole.max[axis] < rest.max[axis] {
+                let mut above = rest;
+                above.min[axis] = hole.max[axis];
+                pieces.push(above);
+            }
+            rest.min[axis] = hole.min[axis];
+            rest.max[axis] = hole.max[axis];
+        }
+        pieces
+    }
+}
+
+impl FromStr for Cuboid {
+    type Err = AocError;
+
+    /// parses inclusive ranges as in "x=-20..26,y=-36..17,z=-47..7"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_cuboid(s).map_err(|e| AocError::parse(None, s, e))
+    }
+}
+
+fn parse_cuboid(s: &str) -> Result<Cuboid, Box<dyn Error>> {
+    let mut min = [0; 3];
+    let mut max = [0; 3];
+    let mut axes = s.trim().split(',');
+    for (i, name) in ["x=", "y=", "z="].iter().enumerate() {
+        let range = axes.next().ok_or("Missing axis range.")?;
+        let range = range.trim().strip_prefix(name).ok_or("Unexpected axis name.")?;
+        let (lo, hi) = range.split_once("..").ok_or("Missing '..' in the range.")?;
+        min[i] = lo.parse()?;
+        max[i] = hi.parse::<i64>()? + 1;
+    }
+    if axes.next().is_some() {
+        return Err("Too many axes.".into());
+    }
+    Ok(Cuboid::new(min, max))
+}
+
+//------------------------------
+// Unions of cuboids
+//------------------------------
+
+/// A set of points kept as disjoint cuboids, switched on and off one cuboid at a time.
+#[derive(Debug, Clone, Default)]
+pub struct Reactor {
+    cuboids: Vec<Cuboid>,
+}
+
+impl Reactor {
+    pub fn new() -> Reactor {
+        Reactor::default()
+    }
+
+    /// turns the points of `cuboid` on or off
+    pub fn apply(&mut self, on: bool, cuboid: Cuboid) {
+        self.cuboids = self.cuboids.iter().flat_map(|c| c.subtract(&cuboid)).collect();
+        if on && !cuboid.is_empty() {
+            self.cuboids.push(cuboid);
+        }
+    }
+
+    /// number of points turned on
+    pub fn volume(&self) -> u64 {
+        self.cuboids.iter().map(Cuboid::volume).sum()
+    }
+
+    /// number of points turned on inside `region`
+    pub fn volume_in(&self, region: &Cuboid) -> u64 {
+        self.cuboids
+            .iter()
+            .filter_map(|c| c.intersect(region))
+            .map(|c| c.volume())
+            .sum()
+    }
+
+    /// the disjoint cuboids making up the set
+    pub fn cuboids(&self) -> &[Cuboid] {
+        &self.cuboids
+    }
+}
+
+/// total number of points turned on after applying every `(on, cuboid)` step in order
+pub fn volume_after_steps<I>(steps: I) -> u64
+where
+    I: IntoIterator<Item = (bool, Cuboid)>,
+{
+    let mut reactor = Reactor::new();
+    steps.into_iter().for_each(|(on, c)| reactor.apply(on, c));
+    reactor.volume()
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subtract_is_disjoint_and_exact() {
+        let a = Cuboid::new([0, 0, 0], [4, 5, 6]);
+        for b in [
+            Cuboid::new([1, 1, 1], [2, 2, 2]),
+            Cuboid::new([-3, 2, 2], [2, 9, 3]),
+            Cuboid::new([10, 10, 10], [11, 11, 11]),
+            a,
+        ] {
+            let pieces = a.subtract(&b);
+            let overlap = a.intersect(&b).map_or(0, |c| c.volume());
+            assert_eq!(pieces.iter().map(Cuboid::volume).sum::<u64>(), a.volume() - overlap);
+            for (i, p) in pieces.iter().enumerate() {
+                assert!(p.intersect(&b).is_none());
+                assert!(pieces[i + 1..].iter().all(|q| p.intersect(q).is_none()));
+            }
+        }
+        assert!(a.subtract(&a).is_empty());
+    }
+}
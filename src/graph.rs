@@ -0,0 +1,379 @@
+//! Graph algorithms over vertices identified by `usize` indices.
+//!
+//! Flow networks:
+//! - `FlowNetwork::max_flow` computes a maximum flow with Dinic's algorithm;
+//! - `FlowNetwork::min_cut` computes a minimum s-t cut, reporting both the vertices on the
+//!   source side and the saturated edges crossing the cut.
+//!
+//! For the common "find the 3 wires to cut" style puzzle, build the graph with
+//! `add_undirected_edge(u, v, 1)`, fix any source and try sinks until the cut value is the
+//! expected one:
+//!
+//! ```
+//! use aoc_tools::graph::FlowNetwork;
+//! // two triangles joined by a single bridge (2-3)
+//! let mut net = FlowNetwork::new(6);
+//! for (u, v) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)] {
+//!     net.add_undirected_edge(u, v, 1);
+//! }
+//! let cut = net.min_cut(0, 5);
+//! assert_eq!(cut.value, 1);
+//! assert_eq!(cut.edges, vec![(2, 3)]);
+//! assert_eq!(cut.source_side.iter().filter(|&&s| s).count(), 3);
+//! ```
+//!
+//! Reachability:
+//! - `transitive_closure` precomputes which vertices can reach which, to answer many
+//!   queries in constant time.
+//!
+//! Vertices given by name, e.g. parsed with `parse::edges`, are indexed with
+//! `adjacency_from_edges`.
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone)]
+struct Edge {
+    to: usize,
+    cap: i64,
+    /// capacity the edge was created with, used to tell original edges from residual ones
+    original: i64,
+}
+
+/// A directed graph with edge capacities. Each added edge is stored alongside its residual
+/// reverse edge, so edge `e` and `e ^ 1` are always a pair.
+#[derive(Debug, Clone)]
+pub struct FlowNetwork {
+    edges: Vec<Edge>,
+    adj: Vec<Vec<usize>>,
+}
+
+/// Result of `FlowNetwork::min_cut`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinCut {
+    /// total capacity of the cut, which equals the maximum flow
+    pub value: i64,
+    /// `source_side[v]` is true if `v` is on the side of the source
+    pub source_side: Vec<bool>,
+    /// edges `(from, to)` crossing the cut, from the source side to the sink side
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl FlowNetwork {
+    /// creates a network with `n` vertices and no edges
+    pub fn new(n: usize) -> FlowNetwork {
+        FlowNetwork {
+            edges: Vec::new(),
+            adj: vec![Vec::new(); n],
+        }
+    }
+
+    /// number of vertices
+    pub fn len(&self) -> usize {
+        self.adj.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.adj.is_empty()
+    }
+
+    /// adds a directed edge from `u` to `v`
+    pub fn add_edge(&mut self, u: usize, v: usize, cap: i64) {
+        self.push_pair(u, v, cap, 0);
+    }
+
+    /// adds an edge that can carry up to `cap` in either direction
+    pub fn add_undirected_edge(&mut self, u: usize, v: usize, cap: i64) {
+        self.push_pair(u, v, cap, cap);
+    }
+
+    /// computes the maximum flow from `s` to `t`.
+    ///
+    /// The network keeps the residual capacities afterwards, call `reset` before running
+    /// another query on the same network.
+    pub fn max_flow(&mut self, s: usize, t: usize) -> i64 {
+        if s == t {
+            return 0;
+        }
+        let n = self.len();
+        let mut flow = 0;
+        let mut level = vec![usize::MAX; n];
+        let mut next_edge = vec![0; n];
+        while self.bfs_levels(s, t, &mut level) {
+            next_edge.iter_mut().for_each(|e| *e = 0);
+            loop {
+                let pushed = self.dfs_push(s, t, i64::MAX, &level, &mut next_edge);
+                if pushed == 0 {
+                    break;
+                }
+                flow += pushed;
+            }
+        }
+        flow
+    }
+
+    /// computes a minimum cut separating `s` from `t`, resetting the residual capacities
+    /// before running.
+    pub fn min_cut(&mut self, s: usize, t: usize) -> MinCut {
+        self.reset();
+        let value = self.max_flow(s, t);
+
+        // the source side is everything still reachable in the residual graph
+        let mut source_side = vec![false; self.len()];
+        let mut queue = VecDeque::from([s]);
+        source_side[s] = true;
+        while let Some(u) = queue.pop_front() {
+            for &e in &self.adj[u] {
+                let Edge { to, cap, .. } = self.edges[e];
+                if cap > 0 && !source_side[to] {
+                    source_side[to] = true;
+                    queue.push_back(to);
+                }
+            }
+        }
+
+        let mut edges = Vec::new();
+        for (u, adj) in self.adj.iter().enumerate() {
+            for &e in adj {
+                let edge = &self.edges[e];
+                if source_side[u] && !source_side[edge.to] && edge.original > 0 {
+                    edges.push((u, edge.to));
+                }
+            }
+        }
+
+        MinCut {
+            value,
+            source_side,
+            edges,
+        }
+    }
+
+    /// restores every edge to its original capacity
+    pub fn reset(&mut self) {
+        self.edges.iter_mut().for_each(|e| e.cap = e.original);
+    }
+
+    //------------------------------
+    // Private
+    //------------------------------
+
+    fn push_pair(&mut self, u: usize, v: usize, cap: i64, reverse_cap: i64) {
+        self.adj[u].push(self.edges.len());
+        self.edges.push(Edge {
+            to: v,
+            cap,
+            original: cap,
+        });
+        self.adj[v].push(self.edges.len());
+        self.edges.push(Edge {
+            to: u,
+            cap: reverse_cap,
+            original: reverse_cap,
+        });
+    }
+
+    /// builds the level graph, returns false if `t` is unreachable
+    fn bfs_levels(&self, s: usize, t: usize, level: &mut [usize]) -> bool {
+        level.iter_mut().for_each(|l| *l = usize::MAX);
+        level[s] = 0;
+        let mut queue = VecDeque::from([s]);
+        while let Some(u) = queue.pop_front() {
+            for &e in &self.adj[u] {
+                let Edge { to, cap, .. } = self.edges[e];
+                if cap > 0 && level[to] == usize::MAX {
+                    level[to] = level[u] + 1;
+                    queue.push_back(to);
+                }
+            }
+        }
+        level[t] != usize::MAX
+    }
+
+    /// pushes a blocking flow along the level graph
+    fn dfs_push(&mut self, u: usize, t: usize, limit: i64, level: &[usize], next_edge: &mut [usize]) -> i64 {
+        if u == t {
+            return limit;
+        }
+        while next_edge[u] < self.adj[u].len() {
+            let e = self.adj[u][next_edge[u]];
+            let Edge { to, cap, .. } = self.edges[e];
+            if cap > 0 && level[to] == level[u] + 1 {
+                let pushed = self.dfs_push(to, t, limit.min(cap), level, next_edge);
+                if pushed > 0 {
+                    self.edges[e].cap -= pushed;
+                    self.edges[e ^ 1].cap += pushed;
+                    return pushed;
+                }
+            }
+            next_edge[u] += 1;
+        }
+        0
+    }
+}
+
+//------------------------------
+// Reachability
+//------------------------------
+
+/// Transitive closure of a directed graph, one bitset row per vertex. Built by
+/// `transitive_closure`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reachability {
+    n: usize,
+    /// number of `u64` words of each row
+    words: usize,
+    rows: Vec<u64>,
+}
+
+impl Reachability {
+    /// number of vertices
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// true if there is a path of at least one edge from `u` to `v`
+    pub fn reaches(&self, u: usize, v: usize) -> bool {
+        self.row(u)[v / 64] >> (v % 64) & 1 == 1
+    }
+
+    /// vertices reachable from `u`, in increasing order
+    pub fn reachable_from(&self, u: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.n).filter(move |&v| self.reaches(u, v))
+    }
+
+    /// number of vertices reachable from `u`
+    pub fn count_from(&self, u: usize) -> usize {
+        self.row(u).iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    fn row(&self, u: usize) -> &[u64] {
+        &self.rows[u * self.words..(u + 1) * self.words]
+    }
+}
+
+/// computes which vertices can reach which, `adjacency[u]` listing the successors of `u`.
+/// Runs Warshall's algorithm on bitset rows, in O(n³ / 64), after which each query is a
+/// single bit test.
+///
+/// A vertex reaches itself only if it lies on a cycle.
+///
+/// ```
+/// use aoc_tools::graph::transitive_closure;
+/// // 0 -> 1 -> 2 -> 1, 3 isolated
+/// let closure = transitive_closure(&[vec![1], vec![2], vec![1], vec![]]);
+/// assert!(closure.reaches(0, 2));
+/// assert!(closure.reaches(2, 2));
+/// assert!(!closure.reaches(0, 0));
+/// assert!(!closure.reaches(1, 0));
+/// assert_eq!(closure.reachable_from(0).collect::<Vec<_>>(), vec![1, 2]);
+/// assert_eq!(closure.count_from(3), 0);
+/// ```
+pub fn transitive_closure(adjacency: &[Vec<usize>]) -> Reachability {
+    let n = adjacency.len();
+    let words = n.div_ceil(64);
+    let mut rows = vec![0u64; n * words];
+    for (u, succ) in adjacency.iter().enumerate() {
+        for &v in succ {
+            rows[u * words + v / 64] |= 1 << (v % 64);
+        }
+    }
+
+    for k in 0..n {
+        for i in 0..n {
+            if i != k && rows[i * words + k / 64] >> (k % 64) & 1 == 1 {
+                for w in 0..words {
+                    rows[i * words + w] |= rows[k * words + w];
+                }
+            }
+        }
+    }
+    Reachability { n, words, rows }
+}
+
+//------------------------------
+// Named vertices
+//------------------------------
+
+/// indexes the vertices of directed `edges` between named vertices, e.g. as extracted by
+/// `parse::edges`. Returns the names, sorted so that breaking ties on vertex indices breaks
+/// them alphabetically, and the adjacency lists (sorted, without duplicates).
+pub fn adjacency_from_edges<S: AsRef<str>>(edges: &[(S, S)]) -> (Vec<String>, Vec<Vec<usize>>) {
+    let mut names: Vec<String> = edges
+        .iter()
+        .flat_map(|(u, v)| [u.as_ref().to_string(), v.as_ref().to_string()])
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    let index = |name: &str| names.binary_search_by(|n| n.as_str().cmp(name)).unwrap();
+
+    let mut adjacency = vec![Vec::new(); names.len()];
+    for (u, v) in edges {
+        adjacency[index(u.as_ref())].push(index(v.as_ref()));
+    }
+    for succ in &mut adjacency {
+        succ.sort_unstable();
+        succ.dedup();
+    }
+    (names, adjacency)
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_flow() {
+        // CLRS figure 26.1, max flow of 23
+        let mut net = FlowNetwork::new(6);
+        for (u, v, c) in [
+            (0, 1, 16),
+            (0, 2, 13),
+            (2, 1, 4),
+            (1, 3, 12),
+            (3, 2, 9),
+            (2, 4, 14),
+            (4, 3, 7),
+            (3, 5, 20),
+            (4, 5, 4),
+        ] {
+            net.add_edge(u, v, c);
+        }
+        assert_eq!(net.max_flow(0, 5), 23);
+        net.reset();
+        assert_eq!(net.max_flow(0, 5), 23);
+
+        let cut = net.min_cut(0, 5);
+        assert_eq!(cut.value, 23);
+        assert_eq!(cut.edges, vec![(1, 3), (4, 3), (4, 5)]);
+    }
+
+    #[test]
+    fn test_transitive_closure() {
+        // a chain longer than one bitset word, closed into a cycle at the end
+        let n = 130;
+        let mut adjacency: Vec<Vec<usize>> = (0..n).map(|u| vec![u + 1]).collect();
+        adjacency[n - 1] = vec![];
+        let chain = transitive_closure(&adjacency);
+        assert!(chain.reaches(0, n - 1));
+        assert!(!chain.reaches(n - 1, 0));
+        assert_eq!(chain.count_from(64), n - 65);
+
+        adjacency[n - 1] = vec![0];
+        let cycle = transitive_closure(&adjacency);
+        assert!((0..n).all(|u| cycle.count_from(u) == n));
+    }
+
+    #[test]
+    fn test_disconnected() {
+        let mut net = FlowNetwork::new(3);
+        net.add_edge(0, 1, 5);
+        assert_eq!(net.max_flow(0, 2), 0);
+    }
+}
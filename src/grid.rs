@@ -23,7 +23,7 @@
 //! let mut grid = Grid::new(5, 5, 0u8);
 //! let v = grid.get_mut(2, 2);
 //! *v = 100;
-//! assert_eq!(grid[(2,2)], 100);
+//! assert_eq!(grid[(2usize, 2usize)], 100);
 //! ```
 //!
 //! ## Beware
@@ -38,9 +38,18 @@
 
 // use super::base2d::Base2d;
 
+use crate::direction::Direction;
+use crate::hash::{FastMap, FxHasher};
 use crate::pair::Pair;
+use crate::rand::Rng;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::{Skip, StepBy};
 use std::ops::{Index, IndexMut};
+use std::slice;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Grid<T> {
@@ -118,6 +127,59 @@ impl<T: Clone> Grid<T> {
 
         Grid::from_vec(len, flat.len() / len, flat)
     }
+
+    /// creates a grid of cells drawn by `cell` from `rng`, in row major order, e.g. random
+    /// inputs for stress testing or benchmarking. The same seed gives the same grid.
+    ///
+    /// ```
+    /// use aoc_tools::{grid::Grid, rand::Rng};
+    /// // a maze with about 30% walls
+    /// let maze = Grid::random(50, 40, &mut Rng::new(1), |rng| if rng.gen_bool(0.3) { '#' } else { '.' });
+    /// assert_eq!(maze, Grid::random(50, 40, &mut Rng::new(1), |rng| if rng.gen_bool(0.3) { '#' } else { '.' }));
+    ///
+    /// // weighted terrain
+    /// let terrain = [('.', 6), ('~', 3), ('^', 1)];
+    /// let map = Grid::random(10, 10, &mut Rng::new(2), |rng| *rng.choose_weighted(&terrain).unwrap());
+    /// assert!(map.iter().all(|c| ".~^".contains(*c)));
+    /// ```
+    pub fn random<F>(len_x: usize, len_y: usize, rng: &mut Rng, mut cell: F) -> Grid<T>
+    where
+        F: FnMut(&mut Rng) -> T,
+    {
+        let flat = (0..len_x * len_y).map(|_| cell(rng)).collect();
+        Grid::from_vec(len_x, len_y, flat)
+    }
+
+    /// creates the smallest grid holding every point of `points`, set to `mark` over a
+    /// `fill` background. Returns the grid and its origin, the coordinates of its top left
+    /// cell, so point `p` lands on cell `p - origin`. No points give an empty grid.
+    ///
+    /// ```
+    /// use aoc_tools::{grid::Grid, pair::Pair};
+    /// let points = [Pair::new(-1, 2), Pair::new(1, 3), Pair::new(0, 2)];
+    /// let (grid, origin) = Grid::from_points(points, '#', '.');
+    /// assert_eq!(origin, Pair::new(-1, 2));
+    /// assert_eq!((grid.len_x, grid.len_y), (3, 2));
+    /// assert_eq!(grid.row(0), &['#', '#', '.']);
+    /// assert_eq!(grid.row(1), &['.', '.', '#']);
+    /// ```
+    pub fn from_points<I>(points: I, mark: T, fill: T) -> (Grid<T>, Pair<i64>)
+    where
+        I: IntoIterator<Item = Pair<i64>>,
+    {
+        let points: Vec<Pair<i64>> = points.into_iter().collect();
+        let Some(&first) = points.first() else {
+            return (Grid::from_vec(0, 0, Vec::new()), Pair::new(0, 0));
+        };
+        let (min, max) = points.iter().fold((first, first), |(min, max), p| {
+            (min.min_components(p), max.max_components(p))
+        });
+        let mut grid = Grid::new((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize, fill);
+        for p in points {
+            *grid.get_mut((p.x - min.x) as usize, (p.y - min.y) as usize) = mark.clone();
+        }
+        (grid, min)
+    }
 }
 
 impl<T> Grid<T> {
@@ -131,12 +193,12 @@ impl<T> Grid<T> {
     ///
     /// Panics if either index is out of bounds.
     pub fn get(&self, x: usize, y: usize) -> &T {
-        // &self.flat[self.index(x, y)]
-        self.flat.get(self.index(x, y)).unwrap()
+        // &self.flat[self.flat_index(x, y)]
+        self.flat.get(self.flat_index(x, y)).unwrap()
     }
 
     pub fn get_mut(&mut self, x: usize, y: usize) -> &mut T {
-        let i = self.index(x, y); // must have an aux variable coz mutable borrow
+        let i = self.flat_index(x, y); // must have an aux variable coz mutable borrow
         &mut self.flat[i]
     }
 
@@ -155,6 +217,12 @@ impl<T> Grid<T> {
         &mut self.flat[idx]
     }
 
+    /// swaps the values at positions `a` and `b`
+    pub fn swap(&mut self, a: Pair<usize>, b: Pair<usize>) {
+        let (a, b) = (self.flat_index(a.x, a.y), self.flat_index(b.x, b.y));
+        self.flat.swap(a, b);
+    }
+
     //------------------------------
     // Getters for multiple elements
     //------------------------------
@@ -167,23 +235,82 @@ impl<T> Grid<T> {
         self.flat.iter_mut()
     }
 
-    // returns an array slice for a line of the grid
+    // returns an array slice for a line of the grid, empty if the grid has no columns
     pub fn row(&self, y: usize) -> &[T] {
-        &self.flat[self.index(0, y)..=self.index(self.len_x - 1, y)]
+        if self.len_x == 0 {
+            return &[];
+        }
+        &self.flat[self.flat_index(0, y)..=self.flat_index(self.len_x - 1, y)]
     }
 
     pub fn row_mut(&mut self, y: usize) -> &mut [T] {
-        let idx0 = self.index(0, y);
-        let idx1 = self.index(self.len_x - 1, y);
+        if self.len_x == 0 {
+            return &mut [];
+        }
+        let idx0 = self.flat_index(0, y);
+        let idx1 = self.flat_index(self.len_x - 1, y);
         &mut self.flat[idx0..=idx1]
     }
 
     pub fn iter_col(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
-        self.flat.iter().skip(x).step_by(self.len_x)
+        // a grid without columns has no cells, so any step yields nothing
+        self.flat.iter().skip(x).step_by(self.len_x.max(1))
     }
 
     pub fn iter_col_mut(&mut self, x: usize) -> impl DoubleEndedIterator<Item = &mut T> + ExactSizeIterator {
-        self.flat.iter_mut().skip(x).step_by(self.len_x)
+        self.flat.iter_mut().skip(x).step_by(self.len_x.max(1))
+    }
+
+    /// the first row, empty if the grid is
+    pub fn top_row(&self) -> &[T] {
+        if self.flat.is_empty() { &[] } else { self.row(0) }
+    }
+
+    /// the last row, empty if the grid is
+    pub fn bottom_row(&self) -> &[T] {
+        if self.flat.is_empty() {
+            &[]
+        } else {
+            self.row(self.len_y - 1)
+        }
+    }
+
+    /// the first column, empty if the grid is
+    pub fn left_col(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.iter_col(0)
+    }
+
+    /// the last column, empty if the grid is
+    pub fn right_col(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.iter_col(self.len_x.saturating_sub(1))
+    }
+
+    /// the four sides of the grid, in the order of `Direction::ALL` (top, right, bottom,
+    /// left) so `edges()[dir as usize]` is the side facing `dir`.
+    ///
+    /// Sides are read clockwise: the top from left to right, the right side from top to
+    /// bottom, the bottom from right to left and the left side from bottom to top. Hence
+    /// rotating the grid only rotates the array, and two tiles match along a side when one
+    /// edge is the reverse of the other.
+    ///
+    /// ```
+    /// use aoc_tools::{direction::Direction, grid::Grid};
+    /// let tile = Grid::from_vec(3, 3, (1..=9).collect());
+    /// let [top, right, bottom, left] = tile.edges();
+    /// assert_eq!((top, right), (vec![1, 2, 3], vec![3, 6, 9]));
+    /// assert_eq!((bottom, left), (vec![9, 8, 7], vec![7, 4, 1]));
+    /// assert_eq!(tile.edges()[Direction::Right as usize], vec![3, 6, 9]);
+    /// ```
+    pub fn edges(&self) -> [Vec<T>; 4]
+    where
+        T: Clone,
+    {
+        [
+            self.top_row().to_vec(),
+            self.right_col().cloned().collect(),
+            self.bottom_row().iter().rev().cloned().collect(),
+            self.left_col().rev().cloned().collect(),
+        ]
     }
 
     //------------------------------
@@ -195,16 +322,26 @@ impl<T> Grid<T> {
         self.flat.len()
     }
 
-    //------------------------------
-    // Private
-    //------------------------------
-
-    /// returns the index for acessing the `flat` array from the coordinates `x`
-    /// and `y`.
-    fn index(&self, x: usize, y: usize) -> usize {
+    /// returns the index for acessing the flat array (see `get_flat`) from the coordinates
+    /// `x` and `y`.
+    pub fn flat_index(&self, x: usize, y: usize) -> usize {
         self.len_x * y + x
     }
 
+    /// moves the flat index `idx` by (dx, dy), returning `None` if the result is out of the
+    /// grid. Lets hot loops walk the flat array without going through coordinates.
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// let grid = Grid::new(3, 2, 0);
+    /// assert_eq!(grid.offset(2, 0, 1), Some(5));
+    /// assert_eq!(grid.offset(2, 1, 0), None);
+    /// ```
+    pub fn offset(&self, idx: usize, dx: isize, dy: isize) -> Option<usize> {
+        let (x, y) = self.xy_index(idx);
+        self.offset_xy(x, y, dx, dy).map(|(x, y)| self.flat_index(x, y))
+    }
+
     pub fn xy_index(&self, idx: usize) -> (usize, usize) {
         (idx % self.len_x, idx / self.len_x)
     }
@@ -217,6 +354,1186 @@ impl<T: PartialEq> Grid<T> {
     }
 }
 
+impl<T> Grid<T> {
+    /// returns the `n` cells with the largest keys, in descending order of key. Ties are
+    /// resolved in favour of the cell that comes first in row major order.
+    ///
+    /// Uses a bounded heap, so it runs in O(size * log n) instead of sorting the whole grid.
+    ///
+    /// ```
+    /// use aoc_tools::{grid::Grid, pair::Pair};
+    /// let grid = Grid::from_vec(2, 2, vec![3, 9, 9, 1]);
+    /// let top = grid.top_n_by_key(2, |&v| v);
+    /// assert_eq!(top, vec![(Pair::new(1, 0), &9), (Pair::new(0, 1), &9)]);
+    /// ```
+    pub fn top_n_by_key<K, F>(&self, n: usize, mut key_fn: F) -> Vec<(Pair<usize>, &T)>
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        if n == 0 {
+            return Vec::new();
+        }
+
+        // min-heap on the key, so the smallest of the current top `n` is popped first
+        let mut heap = BinaryHeap::with_capacity(n + 1);
+        for (idx, v) in self.iter().enumerate() {
+            heap.push(Reverse((key_fn(v), Reverse(idx))));
+            if heap.len() > n {
+                heap.pop();
+            }
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((_, Reverse(idx)))| (Pair::from_tuple(self.xy_index(idx)), self.get_flat(idx)))
+            .collect()
+    }
+
+    /// yields every unordered pair of distinct cells whose value matches `pred`, each pair
+    /// once and in row major order of its first then second cell.
+    ///
+    /// ```
+    /// use aoc_tools::{grid::Grid, pair::Pair};
+    /// let grid = Grid::from_vec(3, 2, "#..#.#".chars().collect());
+    /// let pairs: Vec<_> = grid.pairs_where(|&c| c == '#').collect();
+    /// assert_eq!(pairs.len(), 3);
+    /// assert_eq!(pairs[0], (Pair::new(0, 0), Pair::new(0, 1)));
+    /// let total: usize = pairs.iter().map(|(a, b)| a.distance_manhattan(b)).sum();
+    /// assert_eq!(total, 1 + 3 + 2);
+    /// ```
+    pub fn pairs_where<F>(&self, mut pred: F) -> impl Iterator<Item = (Pair<usize>, Pair<usize>)>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let cells: Vec<Pair<usize>> = self
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| pred(v))
+            .map(|(idx, _)| Pair::from_tuple(self.xy_index(idx)))
+            .collect();
+        let (mut i, mut j) = (0, 1);
+        std::iter::from_fn(move || {
+            if j >= cells.len() {
+                i += 1;
+                j = i + 1;
+            }
+            let pair = (*cells.get(i)?, *cells.get(j)?);
+            j += 1;
+            Some(pair)
+        })
+    }
+}
+
+//------------------------------
+// Transformations
+//------------------------------
+
+impl<T> Grid<T> {
+    /// creates a new grid of the same dimensions applying `f` to every cell
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            flat: self.flat.iter().map(f).collect(),
+            len_x: self.len_x,
+            len_y: self.len_y,
+        }
+    }
+
+    /// creates a new grid of the same dimensions applying `f(x, y, value)` to every cell
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// let grid = Grid::new(3, 2, 10);
+    /// let shifted = grid.map_indexed(|x, y, &v| v + x + 10 * y);
+    /// assert_eq!(shifted.row(1), &[20, 21, 22]);
+    /// ```
+    pub fn map_indexed<U, F>(&self, mut f: F) -> Grid<U>
+    where
+        F: FnMut(usize, usize, &T) -> U,
+    {
+        Grid {
+            flat: self
+                .flat
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    let (x, y) = self.xy_index(i);
+                    f(x, y, v)
+                })
+                .collect(),
+            len_x: self.len_x,
+            len_y: self.len_y,
+        }
+    }
+}
+
+impl<T: Hash + Eq> Grid<T> {
+    /// replaces each value by the one it maps to in `map`. Use `map` for a conversion
+    /// computed by a closure.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some value of the grid is not a key of `map`.
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// use std::collections::HashMap;
+    /// let grid = Grid::from_vec(3, 1, vec!['#', '.', '#']);
+    /// let map = HashMap::from([('#', true), ('.', false)]);
+    /// assert_eq!(grid.relabel(&map).row(0), &[true, false, true]);
+    /// ```
+    #[track_caller]
+    pub fn relabel<U, S>(&self, map: &HashMap<T, U, S>) -> Grid<U>
+    where
+        U: Clone,
+        S: BuildHasher,
+    {
+        self.map(|v| map.get(v).expect("value missing from the relabeling map").clone())
+    }
+
+    /// numbers the distinct values of the grid in order of first appearance (row major),
+    /// returning the grid of numbers and the value of each number. Handy to turn a map of
+    /// chars into a compact alphabet for `PackedGrid`s or lookup tables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grid holds more than 256 distinct values.
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// let grid = Grid::from_vec(4, 1, vec!['#', '.', 'O', '.']);
+    /// let (labels, alphabet) = grid.compact_alphabet();
+    /// assert_eq!(labels.row(0), &[0, 1, 2, 1]);
+    /// assert_eq!(alphabet, vec!['#', '.', 'O']);
+    /// ```
+    pub fn compact_alphabet(&self) -> (Grid<u8>, Vec<T>)
+    where
+        T: Clone,
+    {
+        let mut labels: FastMap<&T, u8> = FastMap::default();
+        let mut alphabet = Vec::new();
+        let flat = self
+            .flat
+            .iter()
+            .map(|v| {
+                *labels.entry(v).or_insert_with(|| {
+                    alphabet.push(v.clone());
+                    u8::try_from(alphabet.len() - 1).expect("more than 256 distinct values")
+                })
+            })
+            .collect();
+        let grid = Grid {
+            flat,
+            len_x: self.len_x,
+            len_y: self.len_y,
+        };
+        (grid, alphabet)
+    }
+}
+
+//------------------------------
+// Scans and reductions
+//------------------------------
+
+impl<T> Grid<T> {
+    /// running aggregate along each row, from left to right: each cell of the result holds
+    /// `op` folded from `init` over the cells of its row up to itself (included)
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// let grid = Grid::from_vec(3, 2, vec![1, 2, 3, 4, 5, 6]);
+    /// let sums = grid.scan_rows(0, |acc, &v| acc + v);
+    /// assert_eq!(sums.row(1), &[4, 9, 15]);
+    /// ```
+    pub fn scan_rows<U, F>(&self, init: U, op: F) -> Grid<U>
+    where
+        U: Clone,
+        F: FnMut(&U, &T) -> U,
+    {
+        self.scan(Direction::Right, init, op)
+    }
+
+    /// running aggregate along each column, from top to bottom
+    pub fn scan_cols<U, F>(&self, init: U, op: F) -> Grid<U>
+    where
+        U: Clone,
+        F: FnMut(&U, &T) -> U,
+    {
+        self.scan(Direction::Down, init, op)
+    }
+
+    /// running aggregate along each row or column, moving towards `dir`: `Direction::Left`
+    /// scans the rows from right to left, `Direction::Up` the columns from bottom to top...
+    /// Scanning in the four directions gives, e.g., the tallest tree seen from each edge.
+    ///
+    /// ```
+    /// use aoc_tools::{direction::Direction, grid::Grid};
+    /// let trees = Grid::from_vec(4, 1, vec![3, 0, 5, 1]);
+    /// let from_right = trees.scan(Direction::Left, 0, |&max, &h| max.max(h));
+    /// assert_eq!(from_right.row(0), &[5, 5, 5, 1]);
+    /// ```
+    pub fn scan<U, F>(&self, dir: Direction, init: U, mut op: F) -> Grid<U>
+    where
+        U: Clone,
+        F: FnMut(&U, &T) -> U,
+    {
+        let mut out: Vec<Option<U>> = (0..self.size()).map(|_| None).collect();
+        let (lines, len) = if dir.is_horizontal() {
+            (self.len_y, self.len_x)
+        } else {
+            (self.len_x, self.len_y)
+        };
+        for line in 0..lines {
+            let mut acc = init.clone();
+            for step in 0..len {
+                let (x, y) = match dir {
+                    Direction::Right => (step, line),
+                    Direction::Left => (len - 1 - step, line),
+                    Direction::Down => (line, step),
+                    Direction::Up => (line, len - 1 - step),
+                };
+                let i = self.flat_index(x, y);
+                acc = op(&acc, &self.flat[i]);
+                out[i] = Some(acc.clone());
+            }
+        }
+        Grid {
+            flat: out.into_iter().flatten().collect(),
+            len_x: self.len_x,
+            len_y: self.len_y,
+        }
+    }
+
+    /// reduces each row to a single value, e.g. its sum or the count of some cell. The
+    /// result is indexed by `y`.
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// let grid = Grid::from_vec(3, 2, vec![1, 2, 3, 4, 5, 6]);
+    /// assert_eq!(grid.reduce_rows(|row| row.iter().sum::<i32>()), vec![6, 15]);
+    /// assert_eq!(grid.reduce_cols(|col| col.max().copied()), vec![Some(4), Some(5), Some(6)]);
+    /// ```
+    pub fn reduce_rows<U, F>(&self, f: F) -> Vec<U>
+    where
+        F: FnMut(&[T]) -> U,
+    {
+        (0..self.len_y).map(|y| self.row(y)).map(f).collect()
+    }
+
+    /// reduces each column to a single value, `f` receiving the iterator of `iter_col`. The
+    /// result is indexed by `x`.
+    pub fn reduce_cols<U, F>(&self, f: F) -> Vec<U>
+    where
+        F: FnMut(StepBy<Skip<slice::Iter<'_, T>>>) -> U,
+    {
+        (0..self.len_x)
+            .map(|x| self.flat.iter().skip(x).step_by(self.len_x.max(1)))
+            .map(f)
+            .collect()
+    }
+}
+
+//------------------------------
+// Optional cells
+//------------------------------
+
+/// Helpers for partial maps, where `None` marks the cells not known (or not computed) yet.
+impl<T> Grid<Option<T>> {
+    /// stores `value` at (x, y), returning the previous value if there was one
+    pub fn set_some(&mut self, x: usize, y: usize, value: T) -> Option<T> {
+        self.get_mut(x, y).replace(value)
+    }
+
+    /// number of cells holding a value
+    pub fn count_some(&self) -> usize {
+        self.iter().filter(|v| v.is_some()).count()
+    }
+
+    /// iterates over the cells holding a value, in row major order
+    ///
+    /// ```
+    /// use aoc_tools::{grid::Grid, pair::Pair};
+    /// let mut costs: Grid<Option<u32>> = Grid::new(3, 2, None);
+    /// costs.set_some(2, 0, 7);
+    /// costs.set_some(0, 1, 4);
+    /// assert_eq!(costs.count_some(), 2);
+    /// let present: Vec<_> = costs.iter_present().collect();
+    /// assert_eq!(present, vec![(Pair::new(2, 0), &7), (Pair::new(0, 1), &4)]);
+    /// assert_eq!(costs.to_sentinel(u32::MAX).row(1), &[4, u32::MAX, u32::MAX]);
+    /// ```
+    pub fn iter_present(&self) -> impl Iterator<Item = (Pair<usize>, &T)> {
+        self.iter()
+            .enumerate()
+            .filter_map(|(i, v)| Some((Pair::from_tuple(self.xy_index(i)), v.as_ref()?)))
+    }
+
+    /// replaces the missing cells with `sentinel`
+    pub fn to_sentinel(&self, sentinel: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.map(|v| v.clone().unwrap_or_else(|| sentinel.clone()))
+    }
+
+    /// converts a grid where `sentinel` marks the missing cells
+    pub fn from_sentinel(grid: &Grid<T>, sentinel: &T) -> Grid<Option<T>>
+    where
+        T: Clone + PartialEq,
+    {
+        grid.map(|v| (v != sentinel).then(|| v.clone()))
+    }
+
+    /// unwraps every cell, or returns `None` if some cell is still missing
+    pub fn into_complete(self) -> Option<Grid<T>> {
+        let (len_x, len_y) = (self.len_x, self.len_y);
+        let flat = self.flat.into_iter().collect::<Option<Vec<T>>>()?;
+        Some(Grid { flat, len_x, len_y })
+    }
+}
+
+//------------------------------
+// Byte grids
+//------------------------------
+
+const LOW_BITS: u64 = 0x7f7f_7f7f_7f7f_7f7f;
+
+/// high bit of each byte of `word` equal to `value`, the other bits cleared
+#[inline]
+fn matching_bytes(word: u64, value: u8) -> u64 {
+    let x = word ^ (value as u64 * 0x0101_0101_0101_0101);
+    // the high bit of each byte is set if the byte is not zero, without carries between
+    // bytes, so there are no false positives
+    !(((x & LOW_BITS) + LOW_BITS) | x | LOW_BITS)
+}
+
+/// Scans of byte grids reading 8 cells at a time (SWAR), several times faster than
+/// comparing cell by cell on big grids. Equality needs no special version: comparing two
+/// `Grid<u8>` already compares the byte slices with `memcmp`.
+impl Grid<u8> {
+    /// number of cells equal to `value`
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// let grid = Grid::from_vec(5, 2, b"#..#.##.#.".to_vec());
+    /// assert_eq!(grid.count_byte(b'#'), 5);
+    /// assert_eq!(grid.find_byte(b'#'), Some((0, 0)));
+    /// assert_eq!(grid.find_byte(b'x'), None);
+    /// ```
+    pub fn count_byte(&self, value: u8) -> usize {
+        let mut chunks = self.flat.chunks_exact(8);
+        let mut count: usize = (&mut chunks)
+            .map(|c| matching_bytes(u64::from_le_bytes(c.try_into().unwrap()), value).count_ones() as usize)
+            .sum();
+        count += chunks.remainder().iter().filter(|&&b| b == value).count();
+        count
+    }
+
+    /// position of the first cell equal to `value`, in row major order
+    pub fn find_byte(&self, value: u8) -> Option<(usize, usize)> {
+        let mut chunks = self.flat.chunks_exact(8);
+        for (i, c) in (&mut chunks).enumerate() {
+            let matches = matching_bytes(u64::from_le_bytes(c.try_into().unwrap()), value);
+            if matches != 0 {
+                return Some(self.xy_index(i * 8 + matches.trailing_zeros() as usize / 8));
+            }
+        }
+        let offset = self.flat.len() - chunks.remainder().len();
+        let i = chunks.remainder().iter().position(|&b| b == value)?;
+        Some(self.xy_index(offset + i))
+    }
+}
+
+//------------------------------
+// Floating point grids
+//------------------------------
+
+/// Probability and heat maps. The generic helpers (`map`, `scan`, `reduce_rows`...) also
+/// work on floats, these add what needs care with rounding.
+impl Grid<f64> {
+    /// sum of the cells, with Neumaier's compensated summation so adding millions of tiny
+    /// probabilities does not lose them to rounding
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// let mut heat = Grid::from_vec(2, 2, vec![1.0, 3.0, 0.0, 4.0]);
+    /// assert_eq!(heat.sum(), 8.0);
+    /// assert_eq!(heat.normalize(), 8.0);
+    /// assert_eq!(heat.row(0), &[0.125, 0.375]);
+    /// // running totals are floats as well
+    /// let cumulative = heat.scan_rows(0.0, |acc, &p| acc + p);
+    /// assert_eq!(cumulative.row(1), &[0.0, 0.5]);
+    /// ```
+    pub fn sum(&self) -> f64 {
+        let (mut sum, mut compensation) = (0.0f64, 0.0f64);
+        for &v in self.iter() {
+            let t = sum + v;
+            compensation += if sum.abs() >= v.abs() {
+                (sum - t) + v
+            } else {
+                (v - t) + sum
+            };
+            sum = t;
+        }
+        sum + compensation
+    }
+
+    /// scales the cells so they add up to 1, returning the previous sum. A grid summing to
+    /// 0 is left untouched.
+    pub fn normalize(&mut self) -> f64 {
+        let total = self.sum();
+        if total != 0.0 {
+            self.iter_mut().for_each(|v| *v /= total);
+        }
+        total
+    }
+}
+
+//------------------------------
+// Nearest features
+//------------------------------
+
+/// Distance functions between two cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// |dx| + |dy|, moving orthogonally
+    Manhattan,
+    /// max(|dx|, |dy|), moving like a chess king
+    Chebyshev,
+    /// dx² + dy², the square of the euclidean distance (same ordering, no floats)
+    EuclideanSquared,
+}
+
+impl Metric {
+    pub fn distance(&self, a: Pair<usize>, b: Pair<usize>) -> usize {
+        let (dx, dy) = (a.x.abs_diff(b.x), a.y.abs_diff(b.y));
+        match self {
+            Metric::Manhattan => dx + dy,
+            Metric::Chebyshev => dx.max(dy),
+            Metric::EuclideanSquared => dx * dx + dy * dy,
+        }
+    }
+}
+
+/// A cell of the grid returned by `Grid::nearest_map`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Nearest {
+    /// distance to the nearest target
+    pub distance: usize,
+    /// index of the nearest target in the targets list, `None` if several targets are tied
+    pub target: Option<usize>,
+}
+
+impl<T> Grid<T> {
+    /// finds, for every cell, the nearest cell matching `is_target` according to `metric`.
+    ///
+    /// Returns the targets in row major order and a grid where each cell holds the distance
+    /// to its nearest target and that target's index in the list, or `None` on ties. With
+    /// no targets, every cell holds `usize::MAX` as the distance.
+    ///
+    /// Every cell is compared against every target, so this is O(size * targets).
+    ///
+    /// ```
+    /// use aoc_tools::grid::{Grid, Metric};
+    /// use aoc_tools::pair::Pair;
+    /// let grid = Grid::from_vec(3, 1, vec![true, false, true]);
+    /// let (targets, nearest) = grid.nearest_map(|&t| t, Metric::Manhattan);
+    /// assert_eq!(targets, vec![Pair::new(0, 0), Pair::new(2, 0)]);
+    /// assert_eq!(nearest.get(0, 0).target, Some(0));
+    /// assert_eq!(nearest.get(1, 0).target, None); // tied
+    /// assert_eq!(nearest.get(1, 0).distance, 1);
+    /// ```
+    pub fn nearest_map<F>(&self, mut is_target: F, metric: Metric) -> (Vec<Pair<usize>>, Grid<Nearest>)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let targets: Vec<Pair<usize>> = self
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| is_target(v))
+            .map(|(i, _)| Pair::from_tuple(self.xy_index(i)))
+            .collect();
+
+        let nearest = self.map_indexed(|x, y, _| {
+            let cell = Pair::new(x, y);
+            let mut best = Nearest {
+                distance: usize::MAX,
+                target: None,
+            };
+            for (i, &t) in targets.iter().enumerate() {
+                let d = metric.distance(cell, t);
+                if d < best.distance {
+                    best = Nearest {
+                        distance: d,
+                        target: Some(i),
+                    };
+                } else if d == best.distance {
+                    best.target = None;
+                }
+            }
+            best
+        });
+        (targets, nearest)
+    }
+}
+
+//------------------------------
+// In place orientation changes and shifts
+//------------------------------
+
+impl<T> Grid<T> {
+    /// rotates a square grid 90 degrees clockwise without allocating. The cell at (x, y)
+    /// moves to (len - 1 - y, x).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grid is not square.
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// let mut grid = Grid::from_vec(3, 3, (1..=9).collect());
+    /// grid.rotate_cw_in_place();
+    /// assert_eq!(grid.row(0), &[7, 4, 1]);
+    /// assert_eq!(grid.row(2), &[9, 6, 3]);
+    /// ```
+    pub fn rotate_cw_in_place(&mut self) {
+        assert_eq!(self.len_x, self.len_y, "in place rotation requires a square grid");
+        let n = self.len_x;
+        for y in 0..n / 2 {
+            for x in y..n - 1 - y {
+                // cycle of four cells, one per side of the current layer
+                let a = self.flat_index(x, y);
+                let b = self.flat_index(n - 1 - y, x);
+                let c = self.flat_index(n - 1 - x, n - 1 - y);
+                let d = self.flat_index(y, n - 1 - x);
+                self.flat.swap(a, b);
+                self.flat.swap(a, c);
+                self.flat.swap(a, d);
+            }
+        }
+    }
+
+    /// mirrors a square grid over its main diagonal without allocating, so the cell at (x, y)
+    /// moves to (y, x).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grid is not square.
+    pub fn transpose_in_place(&mut self) {
+        assert_eq!(self.len_x, self.len_y, "in place transposition requires a square grid");
+        for y in 0..self.len_y {
+            for x in y + 1..self.len_x {
+                let (a, b) = (self.flat_index(x, y), self.flat_index(y, x));
+                self.flat.swap(a, b);
+            }
+        }
+    }
+
+    /// shifts row `y` cyclically `n` cells to the right, the cells falling off the right side
+    /// coming back on the left.
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// let mut screen = Grid::from_vec(7, 2, "#.#......#....".chars().collect());
+    /// screen.rotate_row(0, 4);
+    /// assert_eq!(screen.row(0), &['.', '.', '.', '.', '#', '.', '#']);
+    /// screen.rotate_col(2, 1);
+    /// assert_eq!(screen.iter_col(2).collect::<String>(), "#.");
+    /// ```
+    pub fn rotate_row(&mut self, y: usize, n: usize) {
+        let len = self.len_x;
+        if len > 0 {
+            self.row_mut(y).rotate_right(n % len);
+        }
+    }
+
+    /// shifts column `x` cyclically `n` cells down, the cells falling off the bottom coming
+    /// back at the top. Done with three reversals of the strided column, so each cell is
+    /// swapped at most twice and nothing is allocated.
+    pub fn rotate_col(&mut self, x: usize, n: usize) {
+        assert!(x < self.len_x, "index out of bounds");
+        let len = self.len_y;
+        if len == 0 {
+            return;
+        }
+        let n = n % len;
+        self.reverse_col(x, 0, len);
+        self.reverse_col(x, 0, n);
+        self.reverse_col(x, n, len);
+    }
+
+    /// reverses the cells of column `x` in rows `from..to`
+    fn reverse_col(&mut self, x: usize, from: usize, to: usize) {
+        let (mut lo, mut hi) = (from, to);
+        while lo + 1 < hi {
+            hi -= 1;
+            let (a, b) = (self.flat_index(x, lo), self.flat_index(x, hi));
+            self.flat.swap(a, b);
+            lo += 1;
+        }
+    }
+}
+
+//------------------------------
+// Resampling
+//------------------------------
+
+impl<T> Grid<T> {
+    /// shrinks the grid by a factor `k`, each `k`x`k` block becoming the cell
+    /// `reduce(block)`, the cells of the block given in row major order. The blocks of the
+    /// last column and row are smaller if the sides are not multiples of `k`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is 0.
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// let grid = Grid::from_vec(5, 2, (0..10).collect());
+    /// let sums = grid.downsample(2, |block| block.iter().copied().sum::<u32>());
+    /// assert_eq!((sums.len_x, sums.len_y), (3, 1));
+    /// assert_eq!(sums.row(0), &[0 + 1 + 5 + 6, 2 + 3 + 7 + 8, 4 + 9]);
+    /// ```
+    pub fn downsample<U, F>(&self, k: usize, mut reduce: F) -> Grid<U>
+    where
+        F: FnMut(&[&T]) -> U,
+    {
+        assert!(k > 0, "the downsampling factor must be positive");
+        let (len_x, len_y) = (self.len_x.div_ceil(k), self.len_y.div_ceil(k));
+        let mut block = Vec::with_capacity(k * k);
+        let mut flat = Vec::with_capacity(len_x * len_y);
+        for by in 0..len_y {
+            for bx in 0..len_x {
+                block.clear();
+                for y in by * k..((by + 1) * k).min(self.len_y) {
+                    block.extend(&self.row(y)[bx * k..((bx + 1) * k).min(self.len_x)]);
+                }
+                flat.push(reduce(&block));
+            }
+        }
+        Grid { flat, len_x, len_y }
+    }
+
+    /// grows the grid by a factor `k`, each cell repeated over a `k`x`k` block
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// let grid = Grid::from_vec(2, 1, vec!['#', '.']);
+    /// let big = grid.upscale(2);
+    /// assert_eq!(big.row(1), &['#', '#', '.', '.']);
+    /// assert_eq!(big.downsample(2, |b| *b[0]), grid);
+    /// ```
+    pub fn upscale(&self, k: usize) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let len_x = self.len_x * k;
+        let mut flat = Vec::with_capacity(len_x * self.len_y * k);
+        for y in 0..self.len_y {
+            let start = flat.len();
+            for cell in self.row(y) {
+                flat.extend(std::iter::repeat_n(cell, k).cloned());
+            }
+            for _ in 1..k {
+                flat.extend_from_within(start..start + len_x);
+            }
+        }
+        Grid {
+            flat,
+            len_x,
+            len_y: self.len_y * k,
+        }
+    }
+}
+
+//------------------------------
+// Symmetries
+//------------------------------
+
+impl<T> Grid<T> {
+    /// cells of the grid seen in one of the 8 orientations (rotations and reflections),
+    /// along with the dimensions of the oriented grid. Bit 2 of `o` transposes, bits 0 and 1
+    /// mirror the columns and rows of the result.
+    fn oriented(&self, o: u8) -> ((usize, usize), impl Iterator<Item = &T>) {
+        let transpose = o & 4 != 0;
+        let (w, h) = if transpose {
+            (self.len_y, self.len_x)
+        } else {
+            (self.len_x, self.len_y)
+        };
+        let cells = (0..h)
+            .flat_map(move |j| (0..w).map(move |i| (i, j)))
+            .map(move |(i, j)| {
+                let a = if o & 1 != 0 { w - 1 - i } else { i };
+                let b = if o & 2 != 0 { h - 1 - j } else { j };
+                let (x, y) = if transpose { (b, a) } else { (a, b) };
+                &self.flat[self.flat_index(x, y)]
+            });
+        ((w, h), cells)
+    }
+
+    /// number of the 8 rotations and reflections (identity included) that leave the grid
+    /// unchanged: 1 for an asymmetric grid, 8 for a grid with all the symmetries of a square
+    pub fn symmetry_count(&self) -> usize
+    where
+        T: PartialEq,
+    {
+        let (dims, _) = self.oriented(0);
+        (0..8)
+            .filter(|&o| {
+                let (d, cells) = self.oriented(o);
+                d == dims && cells.eq(self.iter())
+            })
+            .count()
+    }
+
+    /// index of the smallest orientation, comparing dimensions and then the cells in row
+    /// major order
+    fn canonical_orientation(&self) -> u8
+    where
+        T: Ord,
+    {
+        (1..8).fold(0, |best, o| {
+            let (dims, cells) = self.oriented(o);
+            let (best_dims, best_cells) = self.oriented(best);
+            if dims.cmp(&best_dims).then_with(|| cells.cmp(best_cells)).is_lt() {
+                o
+            } else {
+                best
+            }
+        })
+    }
+
+    /// copy of the grid in its canonical orientation: every rotation or reflection of a
+    /// grid has the same canonical grid
+    pub fn canonical(&self) -> Grid<T>
+    where
+        T: Ord + Clone,
+    {
+        let ((len_x, len_y), cells) = self.oriented(self.canonical_orientation());
+        Grid::from_vec(len_x, len_y, cells.cloned().collect())
+    }
+
+    /// hash of the canonical orientation, equal for grids that are equivalent up to rotation
+    /// and reflection. Use it as the key of seen-sets to skip symmetric states of a search.
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// let grid = Grid::from_vec(3, 2, vec![1, 2, 3, 4, 5, 6]);
+    /// let rotated = Grid::from_vec(2, 3, vec![4, 1, 5, 2, 6, 3]);
+    /// assert_eq!(grid.symmetry_class_key(), rotated.symmetry_class_key());
+    /// assert_eq!(grid.canonical(), rotated.canonical());
+    /// let other = Grid::from_vec(3, 2, vec![1, 2, 3, 4, 6, 5]);
+    /// assert_ne!(grid.symmetry_class_key(), other.symmetry_class_key());
+    /// ```
+    pub fn symmetry_class_key(&self) -> u64
+    where
+        T: Ord + Hash,
+    {
+        let mut hasher = FxHasher::default();
+        let (dims, cells) = self.oriented(self.canonical_orientation());
+        dims.hash(&mut hasher);
+        cells.for_each(|c| c.hash(&mut hasher));
+        hasher.finish()
+    }
+}
+
+//------------------------------
+// Cellular automata
+//------------------------------
+
+impl<T: Clone> Grid<T> {
+    /// computes the next generation of an infinite image, as in the trench map puzzle.
+    ///
+    /// Each 3x3 window, centered in a cell, is turned into an index by `encode` (the window
+    /// is given in row major order) and the new cell value is looked up in `rule`. Every cell
+    /// outside the grid is assumed to hold `background`.
+    ///
+    /// As the image is infinite, the result grows by one cell in each direction. Returns the
+    /// new grid together with the new background: if the rule maps an all-background window
+    /// to a different value, the whole infinite background flips.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `encode` returns an index out of `rule`.
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// // a rule that lights up every cell with at least one lit neighbour
+    /// let rule: Vec<bool> = (0..512).map(|i| i != 0).collect();
+    /// let encode = |w: &[&bool; 9]| w.iter().fold(0, |acc, &&lit| acc << 1 | lit as usize);
+    /// let grid = Grid::new(1, 1, true);
+    /// let (next, background) = grid.enhance(&rule, &false, encode);
+    /// assert_eq!((next.len_x, next.len_y), (3, 3));
+    /// assert!(next.iter().all(|&lit| lit));
+    /// assert!(!background);
+    /// ```
+    pub fn enhance<F>(&self, rule: &[T], background: &T, encode: F) -> (Grid<T>, T)
+    where
+        F: Fn(&[&T; 9]) -> usize,
+    {
+        let (len_x, len_y) = (self.len_x + 2, self.len_y + 2);
+        // cell of the output grid (x, y) is centered on (x - 1, y - 1) of `self`
+        let value_at = |x: isize, y: isize| -> &T {
+            if x < 0 || y < 0 || x as usize >= self.len_x || y as usize >= self.len_y {
+                background
+            } else {
+                self.get(x as usize, y as usize)
+            }
+        };
+
+        let mut flat = Vec::with_capacity(len_x * len_y);
+        for y in 0..len_y as isize {
+            for x in 0..len_x as isize {
+                let window: [&T; 9] = std::array::from_fn(|i| {
+                    let (dx, dy) = ((i % 3) as isize - 1, (i / 3) as isize - 1);
+                    value_at(x - 1 + dx, y - 1 + dy)
+                });
+                flat.push(rule[encode(&window)].clone());
+            }
+        }
+
+        let new_background = rule[encode(&[background; 9])].clone();
+        (Grid::from_vec(len_x, len_y, flat), new_background)
+    }
+}
+
+impl<T: Clone + PartialEq> Grid<T> {
+    /// keeps at least `thickness` cells of `background` around the values that differ from
+    /// it. Each side where a non background value is closer than `thickness` to the edge
+    /// grows by `thickness` cells, so a spreading automaton only reallocates every
+    /// `thickness` steps.
+    ///
+    /// Returns how much the existing cells moved, to translate coordinates kept outside of
+    /// the grid: what was at (x, y) is now at (x, y) + offset.
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// use aoc_tools::pair::Pair;
+    /// let mut grid = Grid::from_vec(3, 2, vec![0, 0, 0, 0, 1, 0]);
+    /// let offset = grid.ensure_border(&0, 1);
+    /// assert_eq!(offset, Pair::new(0, 0));
+    /// assert_eq!((grid.len_x, grid.len_y), (3, 3));
+    /// assert_eq!(grid.ensure_border(&0, 1), Pair::new(0, 0));
+    /// ```
+    pub fn ensure_border(&mut self, background: &T, thickness: usize) -> Pair<usize> {
+        let (mut left, mut right, mut top, mut bottom) = (0, 0, 0, 0);
+        for (i, v) in self.flat.iter().enumerate() {
+            if v == background {
+                continue;
+            }
+            let (x, y) = self.xy_index(i);
+            let grow = |dist: usize| if dist < thickness { thickness } else { 0 };
+            left = left.max(grow(x));
+            right = right.max(grow(self.len_x - 1 - x));
+            top = top.max(grow(y));
+            bottom = bottom.max(grow(self.len_y - 1 - y));
+        }
+        if left + right + top + bottom == 0 {
+            return Pair::new(0, 0);
+        }
+        let len_x = self.len_x + left + right;
+        let len_y = self.len_y + top + bottom;
+        let mut grown = Grid::new(len_x, len_y, background.clone());
+        for y in 0..self.len_y {
+            grown.row_mut(y + top)[left..left + self.len_x].clone_from_slice(self.row(y));
+        }
+        *self = grown;
+        Pair::new(left, top)
+    }
+}
+
+//------------------------------
+// Flood fill
+//------------------------------
+
+/// Which cells are considered adjacent when moving across the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity<'a> {
+    /// orthogonal neighbours: up, right, down, left
+    Four,
+    /// orthogonal and diagonal neighbours
+    Eight,
+    /// arbitrary `(dx, dy)` offsets
+    Custom(&'a [(isize, isize)]),
+}
+
+impl Connectivity<'_> {
+    pub const FOUR: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+    pub const EIGHT: [(isize, isize); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+    /// the `(dx, dy)` offsets to the adjacent cells
+    pub fn offsets(&self) -> &[(isize, isize)] {
+        match self {
+            Connectivity::Four => &Self::FOUR,
+            Connectivity::Eight => &Self::EIGHT,
+            Connectivity::Custom(offsets) => offsets,
+        }
+    }
+}
+
+/// Result of `Grid::flood_fill_region`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FloodRegion {
+    /// filled cells in the order they were visited, the start included
+    pub interior: Vec<Pair<usize>>,
+    /// cells next to the region that the fill was not allowed to move into
+    pub boundary: Vec<Pair<usize>>,
+    /// number of refused moves from a filled cell, into a boundary cell or out of the grid,
+    /// i.e. the perimeter or surface area of the region. Exact when `can_move` only depends
+    /// on the target cell.
+    pub faces: usize,
+    /// true if some filled cell has a neighbour outside of the grid
+    pub touches_edge: bool,
+}
+
+impl<T> Grid<T> {
+    /// fills the region reachable from `start`, returning the reached cells in the order they
+    /// were visited (breadth first, `start` included).
+    ///
+    /// Cells are adjacent according to `connectivity`, and moving from a cell to an adjacent
+    /// one is only allowed if `can_move(from_value, to_value)` holds, so rules between cells
+    /// (e.g. climbing at most one unit of height) are expressible, not just rules on cells.
+    ///
+    /// ```
+    /// use aoc_tools::grid::{Connectivity, Grid};
+    /// use aoc_tools::pair::Pair;
+    /// let heights = Grid::from_vec(3, 1, vec![0, 1, 3]);
+    /// let climb = |a: &i32, b: &i32| *b <= *a + 1;
+    /// let reached = heights.flood_fill(Pair::new(0, 0), Connectivity::Four, climb);
+    /// assert_eq!(reached, vec![Pair::new(0, 0), Pair::new(1, 0)]);
+    /// ```
+    pub fn flood_fill<F>(&self, start: Pair<usize>, connectivity: Connectivity, can_move: F) -> Vec<Pair<usize>>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        self.flood_fill_region(start, connectivity, can_move).interior
+    }
+
+    /// same as `flood_fill`, but also reports where the fill stopped: the cells it could not
+    /// move into, the number of refused moves (faces of the region, the moves off the grid
+    /// included, e.g. its surface area in a voxel slice) and whether the region reaches the
+    /// edge of the grid.
+    ///
+    /// ```
+    /// use aoc_tools::grid::{Connectivity, Grid};
+    /// use aoc_tools::pair::Pair;
+    /// // a hole enclosed by walls
+    /// let grid = Grid::from_vec(3, 3, "####.####".chars().collect());
+    /// let region = grid.flood_fill_region(Pair::new(1, 1), Connectivity::Four, |_, &b| b == '.');
+    /// assert_eq!(region.interior, vec![Pair::new(1, 1)]);
+    /// assert_eq!(region.boundary.len(), 4);
+    /// assert_eq!(region.faces, 4);
+    /// assert!(!region.touches_edge);
+    /// ```
+    pub fn flood_fill_region<F>(&self, start: Pair<usize>, connectivity: Connectivity, mut can_move: F) -> FloodRegion
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut seen = vec![false; self.size()];
+        let mut is_boundary = vec![false; self.size()];
+        seen[self.flat_index(start.x, start.y)] = true;
+        let mut region = FloodRegion {
+            interior: vec![start],
+            boundary: Vec::new(),
+            faces: 0,
+            touches_edge: false,
+        };
+        let mut next = 0;
+        while let Some(&p) = region.interior.get(next) {
+            next += 1;
+            let from = self.get(p.x, p.y);
+            for &(dx, dy) in connectivity.offsets() {
+                let Some((x, y)) = self.offset_xy(p.x, p.y, dx, dy) else {
+                    region.faces += 1;
+                    region.touches_edge = true;
+                    continue;
+                };
+                let i = self.flat_index(x, y);
+                if seen[i] {
+                    continue;
+                }
+                if can_move(from, &self.flat[i]) {
+                    seen[i] = true;
+                    region.interior.push(Pair::new(x, y));
+                } else {
+                    region.faces += 1;
+                    if !is_boundary[i] {
+                        is_boundary[i] = true;
+                        region.boundary.push(Pair::new(x, y));
+                    }
+                }
+            }
+        }
+        // a cell refused from one side may have been filled from another one later
+        region.boundary.retain(|p| !seen[self.flat_index(p.x, p.y)]);
+        region
+    }
+
+    /// iterates over the cells at the given `(dx, dy)` offsets from (x, y), skipping those
+    /// that fall outside the grid. Any movement table works: orthogonal or diagonal
+    /// neighbours (see `Connectivity`), knight moves, custom jumps...
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// let grid = Grid::new(3, 3, 0);
+    /// let knight = [(1, 2), (2, 1), (-1, 2), (-2, 1), (1, -2), (2, -1), (-1, -2), (-2, -1)];
+    /// let moves: Vec<_> = grid.neighbors_by(&knight, 0, 0).collect();
+    /// assert_eq!(moves, vec![(1, 2), (2, 1)]);
+    /// ```
+    pub fn neighbors_by<'a>(
+        &'a self,
+        offsets: &'a [(isize, isize)],
+        x: usize,
+        y: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.iter().filter_map(move |&(dx, dy)| self.offset_xy(x, y, dx, dy))
+    }
+
+    /// returns the values of the four orthogonal neighbours of (x, y), in the order of
+    /// `Connectivity::FOUR` (up, right, down, left), with `default` standing in for the ones
+    /// out of the grid.
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// let grid = Grid::from_vec(2, 2, vec![1, 2, 3, 4]);
+    /// assert_eq!(grid.neighbors4_padded(0, 0, &0), [&0, &2, &3, &0]);
+    /// ```
+    pub fn neighbors4_padded<'a>(&'a self, x: usize, y: usize, default: &'a T) -> [&'a T; 4] {
+        Connectivity::FOUR.map(|(dx, dy)| self.offset_xy(x, y, dx, dy).map_or(default, |(x, y)| self.get(x, y)))
+    }
+
+    /// like `neighbors_by`, but always yields one value per offset, `default` standing in for
+    /// the cells out of the grid
+    pub fn neighbors_padded<'a>(
+        &'a self,
+        offsets: &'a [(isize, isize)],
+        x: usize,
+        y: usize,
+        default: &'a T,
+    ) -> impl Iterator<Item = &'a T> + 'a {
+        offsets
+            .iter()
+            .map(move |&(dx, dy)| self.offset_xy(x, y, dx, dy).map_or(default, |(x, y)| self.get(x, y)))
+    }
+
+    /// returns where an object starting at `pos` and moving towards `dir` stops: the last
+    /// cell before a cell for which `blocked` holds, or before the edge of the grid. The
+    /// start cell itself is not tested.
+    ///
+    /// ```
+    /// use aoc_tools::{direction::Direction, grid::Grid, pair::Pair};
+    /// let grid = Grid::from_vec(5, 1, "O..#.".chars().collect());
+    /// assert_eq!(grid.slide_from(Pair::new(0, 0), Direction::Right, |&c| c != '.'), Pair::new(2, 0));
+    /// assert_eq!(grid.slide_from(Pair::new(4, 0), Direction::Right, |&c| c != '.'), Pair::new(4, 0));
+    /// ```
+    pub fn slide_from<F>(&self, pos: Pair<usize>, dir: Direction, mut blocked: F) -> Pair<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let (dx, dy) = dir.delta();
+        let (mut x, mut y) = (pos.x, pos.y);
+        while let Some((nx, ny)) = self.offset_xy(x, y, dx, dy) {
+            if blocked(self.get(nx, ny)) {
+                break;
+            }
+            (x, y) = (nx, ny);
+        }
+        Pair::new(x, y)
+    }
+
+    /// moves (x, y) by (dx, dy), returning `None` if the result is out of the grid
+    fn offset_xy(&self, x: usize, y: usize, dx: isize, dy: isize) -> Option<(usize, usize)> {
+        let x = x.checked_add_signed(dx).filter(|&x| x < self.len_x)?;
+        let y = y.checked_add_signed(dy).filter(|&y| y < self.len_y)?;
+        Some((x, y))
+    }
+}
+
+//------------------------------
+// Test helpers
+//------------------------------
+
+impl<T: PartialEq + Debug> Grid<T> {
+    /// compares `self` against `expected`, returning `None` if they are equal or a rendered
+    /// report otherwise.
+    ///
+    /// The report shows both grids side by side (actual on the left, expected on the right)
+    /// with mismatched cells wrapped in square brackets, followed by the coordinates of the
+    /// first mismatches.
+    ///
+    /// ```
+    /// use aoc_tools::grid::Grid;
+    /// let actual = Grid::from_vec(2, 1, vec![1, 2]);
+    /// let expected = Grid::from_vec(2, 1, vec![1, 3]);
+    /// let report = actual.diff_report(&expected).unwrap();
+    /// assert!(report.contains(" 1 [2] |  1 [3]"));
+    /// assert!(actual.diff_report(&actual).is_none());
+    /// ```
+    pub fn diff_report(&self, expected: &Grid<T>) -> Option<String> {
+        if self.len_x != expected.len_x || self.len_y != expected.len_y {
+            return Some(format!(
+                "grid dimensions differ: actual is {}x{}, expected is {}x{}",
+                self.len_x, self.len_y, expected.len_x, expected.len_y
+            ));
+        }
+        if self == expected {
+            return None;
+        }
+
+        let render = |g: &Grid<T>| g.iter().map(|v| format!("{v:?}")).collect::<Vec<_>>();
+        let (actual_cells, expected_cells) = (render(self), render(expected));
+        let width = actual_cells
+            .iter()
+            .chain(&expected_cells)
+            .map(|s| s.len())
+            .max()
+            .unwrap_or(0);
+        let cell = |s: &str, mismatch: bool| {
+            if mismatch {
+                format!("[{s:>width$}]")
+            } else {
+                format!(" {s:>width$} ")
+            }
+        };
+
+        let mut report = String::from("grids differ (actual | expected):\n");
+        let mut mismatches = Vec::new();
+        for y in 0..self.len_y {
+            let (mut left, mut right) = (String::new(), String::new());
+            for x in 0..self.len_x {
+                let i = self.flat_index(x, y);
+                let mismatch = self.flat[i] != expected.flat[i];
+                if mismatch {
+                    mismatches.push((x, y));
+                }
+                left += &cell(&actual_cells[i], mismatch);
+                right += &cell(&expected_cells[i], mismatch);
+            }
+            report += &format!("{left} | {right}\n");
+        }
+
+        const SHOWN: usize = 10;
+        report += &format!("{} mismatched cells", mismatches.len());
+        let listed = mismatches.iter().take(SHOWN).map(|p| format!("{p:?}"));
+        report += &format!(", at (x, y): {}", listed.collect::<Vec<_>>().join(", "));
+        if mismatches.len() > SHOWN {
+            report += ", ...";
+        }
+        Some(report)
+    }
+
+    /// panics with the `diff_report` if `self` and `expected` differ.
+    #[track_caller]
+    pub fn assert_eq_pretty(&self, expected: &Grid<T>) {
+        if let Some(report) = self.diff_report(expected) {
+            panic!("{report}");
+        }
+    }
+}
+
 //------------------------------
 // Indexing
 //------------------------------
@@ -262,3 +1579,169 @@ where
         self.get_mut(index.x.into(), index.y.into())
     }
 }
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_edges() {
+        for empty in [Grid::new(0, 0, 1), Grid::new(3, 0, 1), Grid::new(0, 3, 1)] {
+            assert!(empty.top_row().is_empty() && empty.bottom_row().is_empty());
+            assert_eq!((empty.left_col().count(), empty.right_col().count()), (0, 0));
+            assert_eq!(empty.clone().iter_col_mut(0).count(), 0);
+            assert_eq!(empty.reduce_cols(|c| c.count()), vec![0; empty.len_x]);
+            assert_eq!(empty.reduce_rows(<[i32]>::len), vec![0; empty.len_y]);
+            assert!(empty.edges().iter().all(Vec::is_empty));
+        }
+    }
+
+    #[test]
+    fn test_region_on_border() {
+        // a 2x2 plot in the corner: half of its perimeter is the edge of the grid
+        let grid = Grid::from_vec(3, 3, "..#..####".chars().collect());
+        let region = grid.flood_fill_region(Pair::new(0, 0), Connectivity::Four, |_, &b| b == '.');
+        assert_eq!(region.interior.len(), 4);
+        assert_eq!((region.boundary.len(), region.faces), (4, 8));
+        assert!(region.touches_edge);
+
+        let whole = Grid::new(3, 2, '.').flood_fill_region(Pair::new(1, 1), Connectivity::Four, |_, _| true);
+        assert_eq!((whole.boundary.len(), whole.faces), (0, 10));
+    }
+
+    #[test]
+    fn test_resampling() {
+        let grid = Grid::from_vec(3, 2, (0..6).collect::<Vec<u32>>());
+        let big = grid.upscale(3);
+        assert_eq!((big.len_x, big.len_y), (9, 6));
+        assert_eq!(big.iter_col(4).copied().collect::<Vec<_>>(), vec![1, 1, 1, 4, 4, 4]);
+        assert_eq!(big.downsample(3, |b| *b[4]), grid);
+
+        // a grid without columns keeps its rows, scaled
+        let thin = Grid::new(0, 2, 0u32).upscale(3);
+        assert_eq!((thin.len_x, thin.len_y), (0, 6));
+        // partial blocks on the right and bottom sides
+        let counts = big.downsample(4, |b| b.len());
+        assert_eq!(counts.row(0), &[16, 16, 4]);
+        assert_eq!(counts.row(1), &[8, 8, 2]);
+    }
+
+    #[test]
+    fn test_rotate_row_col() {
+        let mut grid = Grid::from_vec(3, 5, (0..15).collect());
+        grid.rotate_col(1, 7);
+        assert_eq!(grid.iter_col(1).copied().collect::<Vec<_>>(), vec![10, 13, 1, 4, 7]);
+        grid.rotate_col(1, 3);
+        assert_eq!(grid.iter_col(1).copied().collect::<Vec<_>>(), vec![1, 4, 7, 10, 13]);
+        grid.rotate_row(4, 3);
+        assert_eq!(grid.row(4), &[12, 13, 14]);
+        grid.rotate_row(4, 2);
+        assert_eq!(grid.row(4), &[13, 14, 12]);
+        assert_eq!(grid.iter_col(0).copied().collect::<Vec<_>>(), vec![0, 3, 6, 9, 13]);
+    }
+
+    #[test]
+    fn test_rotate_in_place() {
+        let original = Grid::from_vec(4, 4, (0..16).collect::<Vec<u8>>());
+        let mut grid = original.clone();
+        grid.rotate_cw_in_place();
+        // clockwise rotation is a transposition followed by mirroring each row
+        let mut expected = original.clone();
+        expected.transpose_in_place();
+        (0..4).for_each(|y| expected.row_mut(y).reverse());
+        assert_eq!(grid, expected);
+
+        (0..3).for_each(|_| grid.rotate_cw_in_place());
+        assert_eq!(grid, original);
+    }
+
+    #[test]
+    fn test_symmetry_class_key() {
+        let mut grid = Grid::from_vec(3, 3, vec![1, 1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(grid.symmetry_count(), 1);
+        let key = grid.symmetry_class_key();
+        assert_eq!(
+            Grid::from_vec(3, 3, vec![1, 2, 1, 0, 0, 0, 0, 0, 0]).symmetry_count(),
+            2
+        );
+        for _ in 0..4 {
+            grid.rotate_cw_in_place();
+            assert_eq!(grid.symmetry_class_key(), key);
+            grid.transpose_in_place();
+            assert_eq!(grid.symmetry_class_key(), key);
+            grid.transpose_in_place();
+        }
+        let other = Grid::from_vec(3, 3, vec![1, 0, 1, 0, 0, 0, 0, 0, 0]);
+        assert_ne!(other.symmetry_class_key(), key);
+        assert_eq!(Grid::new(4, 4, 0).symmetry_count(), 8);
+        assert_eq!(Grid::new(4, 2, 0).symmetry_count(), 4);
+    }
+
+    #[test]
+    fn test_sentinel_round_trip() {
+        let raw = Grid::from_vec(2, 2, vec![3, -1, -1, 5]);
+        let mut partial = Grid::from_sentinel(&raw, &-1);
+        assert_eq!(partial.count_some(), 2);
+        assert_eq!(partial.to_sentinel(-1), raw);
+        assert!(partial.clone().into_complete().is_none());
+        assert_eq!(partial.set_some(1, 0, 4), None);
+        assert_eq!(partial.set_some(0, 1, 4), None);
+        assert_eq!(partial.into_complete().unwrap().row(1), &[4, 5]);
+    }
+
+    #[test]
+    fn test_byte_scans() {
+        // every byte value, at every position of the 8 byte words and in the remainder
+        let bytes: Vec<u8> = (0..=255u8).chain([0x80, 0x7f, 0x01, 0xff, 0x00]).collect();
+        let grid = Grid::from_vec(bytes.len(), 1, bytes.clone());
+        for value in 0..=255u8 {
+            let expected = bytes.iter().filter(|&&b| b == value).count();
+            assert_eq!(grid.count_byte(value), expected, "value {value}");
+            let first = bytes.iter().position(|&b| b == value).map(|x| (x, 0));
+            assert_eq!(grid.find_byte(value), first, "value {value}");
+        }
+        let tail = Grid::from_vec(3, 3, vec![0, 0, 0, 0, 0, 0, 0, 0, 9]);
+        assert_eq!(tail.find_byte(9), Some((2, 2)));
+    }
+
+    #[test]
+    fn test_float_sum() {
+        // a naive sum loses every small value next to the large one
+        let mut grid = Grid::new(1001, 1, 1e-16);
+        *grid.get_mut(0, 0) = 1.0;
+        assert_eq!(grid.iter().sum::<f64>(), 1.0);
+        assert_eq!(grid.sum(), 1.0 + 1000.0 * 1e-16);
+        assert_eq!(Grid::new(2, 2, 0.0).normalize(), 0.0);
+    }
+
+    #[test]
+    fn test_enhance_background_flip() {
+        // empty windows light up, full windows go dark, otherwise the center is kept
+        let rule: Vec<bool> = (0..512)
+            .map(|i| match i {
+                0 => true,
+                511 => false,
+                _ => i & 0b000_010_000 != 0,
+            })
+            .collect();
+        let encode = |w: &[&bool; 9]| w.iter().fold(0, |acc, &&lit| acc << 1 | lit as usize);
+
+        let grid = Grid::from_vec(2, 1, vec![true, false]);
+        let (step1, background) = grid.enhance(&rule, &false, encode);
+        assert!(background);
+        // only the cell right of the image sees an empty window
+        assert_eq!(step1.row(0), &[false, false, false, true]);
+        assert_eq!(step1.row(1), &[false, true, false, true]);
+
+        let (step2, background) = step1.enhance(&rule, &background, encode);
+        assert!(!background);
+        assert_eq!((step2.len_x, step2.len_y), (6, 5));
+        // the corner sees a dark cell of step1, so it keeps the lit background of its center
+        assert!(*step2.get(0, 0));
+        assert!(*step2.get(2, 2));
+    }
+}
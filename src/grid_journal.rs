@@ -0,0 +1,95 @@
+//! A grid recording the previous value of every cell it sets, to undo mutations.
+//!
+//! Backtracking searches over a grid (placing pieces, filling cells) would otherwise clone
+//! the grid at each choice. Take a `checkpoint` before trying a choice and `rollback` to it
+//! afterwards: undoing costs as much as the changes made since the checkpoint.
+//!
+//! ```
+//! use aoc_tools::{grid::Grid, grid_journal::GridJournal};
+//! let mut board = GridJournal::new(Grid::new(3, 3, '.'));
+//! board.set(0, 0, '#');
+//! let before = board.checkpoint();
+//! board.set(1, 1, '#');
+//! let nested = board.checkpoint();
+//! board.set(2, 2, '#');
+//! board.set(1, 1, 'o');
+//! board.rollback(nested);
+//! assert_eq!(*board.get(1, 1), '#');
+//! assert_eq!(*board.get(2, 2), '.');
+//! board.rollback(before);
+//! assert_eq!(board.grid().iter().filter(|&&c| c == '#').count(), 1);
+//! ```
+use crate::grid::Grid;
+use std::mem;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GridJournal<T> {
+    grid: Grid<T>,
+    /// flat index and previous value of each set, oldest first
+    log: Vec<(usize, T)>,
+}
+
+/// A point of the history of a `GridJournal` to roll back to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Checkpoint(usize);
+
+impl<T> GridJournal<T> {
+    /// wraps `grid` with an empty history
+    pub fn new(grid: Grid<T>) -> GridJournal<T> {
+        GridJournal { grid, log: Vec::new() }
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    /// unwraps the grid, keeping every change
+    pub fn into_inner(self) -> Grid<T> {
+        self.grid
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> &T {
+        self.grid.get(x, y)
+    }
+
+    /// sets the cell at x,y to `value`, recording its previous value
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        self.set_flat(self.grid.flat_index(x, y), value);
+    }
+
+    pub fn set_flat(&mut self, idx: usize, value: T) {
+        let old = mem::replace(self.grid.get_flat_mut(idx), value);
+        self.log.push((idx, old));
+    }
+
+    /// the current point of the history
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.log.len())
+    }
+
+    /// undoes every set made since `checkpoint`, most recent first
+    ///
+    /// # Panics
+    ///
+    /// Panics if the history was already rolled back (or forgotten) past `checkpoint`.
+    pub fn rollback(&mut self, checkpoint: Checkpoint) {
+        assert!(checkpoint.0 <= self.log.len(), "checkpoint was already rolled back");
+        for (idx, old) in self.log.drain(checkpoint.0..).rev() {
+            *self.grid.get_flat_mut(idx) = old;
+        }
+    }
+
+    /// number of sets recorded
+    pub fn changes(&self) -> usize {
+        self.log.len()
+    }
+
+    /// clears the history, keeping the current values: earlier checkpoints become invalid
+    pub fn forget(&mut self) {
+        self.log.clear();
+    }
+}
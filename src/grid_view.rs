@@ -0,0 +1,246 @@
+//! Rectangular windows into a `Grid`.
+//!
+//! `GridView` reads a sub-region with coordinates relative to its origin, while
+//! `GridViewMut` writes through to the underlying grid, which suits the screen puzzles that
+//! fill or rotate rectangles of pixels.
+//!
+//! ```
+//! use aoc_tools::{geometry::Rect, grid::Grid, pair::Pair};
+//! let mut screen = Grid::new(7, 3, '.');
+//! screen.view_mut(Rect::new(Pair::new(0, 0), Pair::new(3, 2))).fill('#');
+//! screen.view_mut(Rect::new(Pair::new(0, 0), Pair::new(7, 3))).rotate_col(1, 1);
+//! assert_eq!(screen.row(0).iter().collect::<String>(), "#.#....");
+//! assert_eq!(screen.row(2).iter().collect::<String>(), ".#.....");
+//! ```
+use crate::{geometry::Rect, grid::Grid, pair::Pair};
+
+/// A read-only window into a grid.
+#[derive(Debug)]
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    rect: Rect,
+}
+
+// derived impls would require `T: Copy`
+impl<T> Clone for GridView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for GridView<'_, T> {}
+
+/// A mutable window into a grid.
+#[derive(Debug)]
+pub struct GridViewMut<'a, T> {
+    grid: &'a mut Grid<T>,
+    rect: Rect,
+}
+
+impl<T> Grid<T> {
+    /// returns a read-only view of `rect`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rect` does not fit in the grid.
+    pub fn view(&self, rect: Rect) -> GridView<'_, T> {
+        check_fits(self, &rect);
+        GridView { grid: self, rect }
+    }
+
+    /// returns a mutable view of `rect`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rect` does not fit in the grid.
+    pub fn view_mut(&mut self, rect: Rect) -> GridViewMut<'_, T> {
+        check_fits(self, &rect);
+        GridViewMut { grid: self, rect }
+    }
+}
+
+fn check_fits<T>(grid: &Grid<T>, rect: &Rect) {
+    let end = rect.end();
+    assert!(end.x <= grid.len_x && end.y <= grid.len_y, "view out of the grid");
+}
+
+impl<'a, T> GridView<'a, T> {
+    pub fn len_x(&self) -> usize {
+        self.rect.size.x
+    }
+
+    pub fn len_y(&self) -> usize {
+        self.rect.size.y
+    }
+
+    /// the region of the grid covered by the view
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// returns the value at x,y relative to the origin of the view
+    pub fn get(&self, x: usize, y: usize) -> &'a T {
+        assert!(x < self.len_x() && y < self.len_y(), "index out of the view");
+        self.grid.get(self.rect.origin.x + x, self.rect.origin.y + y)
+    }
+
+    pub fn row(&self, y: usize) -> &'a [T] {
+        assert!(y < self.len_y(), "index out of the view");
+        let x0 = self.rect.origin.x;
+        &self.grid.row(self.rect.origin.y + y)[x0..x0 + self.len_x()]
+    }
+
+    /// iterates over the values in row major order
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a {
+        let view = *self;
+        (0..self.len_y()).flat_map(move |y| view.row(y).iter())
+    }
+}
+
+impl<T: Clone> GridView<'_, T> {
+    /// copies the view into its own grid
+    pub fn to_grid(&self) -> Grid<T> {
+        Grid::from_iter(self.len_x(), self.len_y(), self.iter().cloned())
+    }
+}
+
+impl<T> GridViewMut<'_, T> {
+    pub fn len_x(&self) -> usize {
+        self.rect.size.x
+    }
+
+    pub fn len_y(&self) -> usize {
+        self.rect.size.y
+    }
+
+    pub fn as_view(&self) -> GridView<'_, T> {
+        GridView {
+            grid: self.grid,
+            rect: self.rect,
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> &T {
+        self.as_view().get(x, y)
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> &mut T {
+        assert!(x < self.len_x() && y < self.len_y(), "index out of the view");
+        self.grid.get_mut(self.rect.origin.x + x, self.rect.origin.y + y)
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        assert!(y < self.len_y(), "index out of the view");
+        let (x0, len_x) = (self.rect.origin.x, self.len_x());
+        &mut self.grid.row_mut(self.rect.origin.y + y)[x0..x0 + len_x]
+    }
+
+    /// replaces every value `v` of the view with `f(v)`
+    pub fn map_in_place<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> T,
+    {
+        for y in 0..self.len_y() {
+            self.row_mut(y).iter_mut().for_each(|v| *v = f(v));
+        }
+    }
+
+    /// rotates row `y` of the view `n` cells to the right, wrapping around
+    pub fn rotate_row(&mut self, y: usize, n: usize) {
+        let len_x = self.len_x();
+        self.row_mut(y).rotate_right(n % len_x.max(1));
+    }
+
+    /// rotates column `x` of the view `n` cells down, wrapping around
+    pub fn rotate_col(&mut self, x: usize, n: usize) {
+        let len_y = self.len_y();
+        if len_y == 0 {
+            return;
+        }
+        // rotation by three reversals, as `slice::rotate_right`
+        let n = n % len_y;
+        self.reverse_col(x, 0, len_y);
+        self.reverse_col(x, 0, n);
+        self.reverse_col(x, n, len_y);
+    }
+
+    /// rotates a square view 90 degrees clockwise
+    ///
+    /// # Panics
+    ///
+    /// Panics if the view is not square.
+    pub fn rotate_cw(&mut self) {
+        assert_eq!(self.len_x(), self.len_y(), "in place rotation requires a square view");
+        let origin = self.rect.origin;
+        for y in 0..self.len_y() {
+            for x in y + 1..self.len_x() {
+                self.grid.swap(origin + Pair::new(x, y), origin + Pair::new(y, x));
+            }
+        }
+        (0..self.len_y()).for_each(|y| self.row_mut(y).reverse());
+    }
+
+    /// reverses the cells `from..to` of column `x`
+    fn reverse_col(&mut self, x: usize, from: usize, to: usize) {
+        let origin = self.rect.origin;
+        let (mut a, mut b) = (from, to);
+        while a + 1 < b {
+            b -= 1;
+            self.grid.swap(origin + Pair::new(x, a), origin + Pair::new(x, b));
+            a += 1;
+        }
+    }
+}
+
+impl<T: Clone> GridViewMut<'_, T> {
+    pub fn fill(&mut self, value: T) {
+        for y in 0..self.len_y() {
+            self.row_mut(y).fill(value.clone());
+        }
+    }
+
+    /// copies `src` into the view
+    ///
+    /// # Panics
+    ///
+    /// Panics if both views have different sizes.
+    pub fn copy_from(&mut self, src: &GridView<T>) {
+        assert_eq!(src.rect().size, self.rect.size, "views of different sizes");
+        for y in 0..self.len_y() {
+            self.row_mut(y).clone_from_slice(src.row(y));
+        }
+    }
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sub_region_ops() {
+        // 0 1 2 3
+        // 4 5 6 7
+        // 8 9 10 11
+        let mut grid = Grid::from_vec(4, 3, (0..12).collect());
+        let square = Rect::new(Pair::new(1, 0), Pair::new(2, 2));
+        assert_eq!(grid.view(square).iter().copied().collect::<Vec<_>>(), vec![1, 2, 5, 6]);
+
+        grid.view_mut(square).rotate_cw();
+        assert_eq!(grid.row(0), &[0, 5, 1, 3]);
+        assert_eq!(grid.row(1), &[4, 6, 2, 7]);
+
+        let mut view = grid.view_mut(Rect::new(Pair::new(3, 0), Pair::new(1, 3)));
+        view.rotate_col(0, 4);
+        view.map_in_place(|v| v * 10);
+        assert_eq!(grid.iter_col(3).copied().collect::<Vec<_>>(), vec![110, 30, 70]);
+
+        let copy = grid.view(Rect::new(Pair::new(0, 0), Pair::new(2, 1))).to_grid();
+        grid.view_mut(Rect::new(Pair::new(2, 2), Pair::new(2, 1)))
+            .copy_from(&copy.view(Rect::new(Pair::new(0, 0), Pair::new(2, 1))));
+        assert_eq!(grid.row(2), &[8, 9, 0, 5]);
+    }
+}
@@ -2,12 +2,55 @@
 //!
 //! Notes:
 //! - All file helpers open files under the "inputs/" directory. Pass filenames relative
-//!   to that directory (e.g. "day01.txt");
+//!   to that directory (e.g. "day01.txt"). Any `AsRef<Path>` is accepted, so `PathBuf`s and
+//!   nested paths (e.g. `Path::new("2021").join("day01.txt")`) work as well;
 //! - Iterators returned from these helpers may hide I/O or parse errors depending on the
-//!   function. See each function's doc for the exact semantics.
+//!   function. See each function's doc for the exact semantics. The parsing helpers skip
+//!   parse failures unless they take a `ParseMode` (see `parse::ParseMode`);
 //! - Aside for the `lines` iterator, all other iterators use dynamic dispatch for the
 //!   return type;
-use std::{fs::File, io, io::prelude::*, str::FromStr};
+//! - Errors are reported as an `AocError` (see the `error` module), I/O failures included;
+//! - Contents are normalized (no BOM, LF line endings, no final newline) the same way by all
+//!   the helpers but `bytes`, see `Normalization`;
+//! - `aoc_input!` embeds an input file at compile time instead of reading it at runtime.
+use crate::{
+    error::{AocError, AocResult},
+    grid::Grid,
+    parse::{self, ParseMode},
+    runner::{span, spanned},
+};
+use std::{
+    convert::Infallible,
+    fmt::Display,
+    fs::File,
+    io,
+    io::prelude::*,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+//------------------------------
+// Embedded inputs
+//------------------------------
+
+/// Embeds `inputs/<file>` of the calling crate in the binary as a `&'static str`, with
+/// `include_str!`. The file is read at compile time, so the solution needs no file I/O at
+/// runtime (benchmarks, WASM builds); parse the text with the `parse` helpers.
+///
+/// `file` must be a string literal, named the same way as for the other helpers of this
+/// module (e.g. "day01.txt").
+///
+/// ```
+/// let text: &str = aoc_tools::aoc_input!("test_grid");
+/// let numbers = aoc_tools::parse::lines_into_vec::<u32>(text);
+/// assert_eq!(numbers, vec![123, 4567, 8901]);
+/// ```
+#[macro_export]
+macro_rules! aoc_input {
+    ($file:literal) => {
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/", $file))
+    };
+}
 
 //------------------------------
 // Read whole content into memory
@@ -19,15 +62,24 @@ use std::{fs::File, io, io::prelude::*, str::FromStr};
 /// line.
 ///
 /// Parsing failures are skipped in the current implementation.
-pub fn lines_to_vec<T>(filename: &str) -> io::Result<Vec<T>>
+pub fn lines_to_vec<T>(filename: impl AsRef<Path>) -> AocResult<Vec<T>>
+where
+    T: FromStr,
+{
+    let lines = buf_reader(filename)?.lines().map_while(Result::ok);
+    let _span = span("parse");
+    Ok(lines.flat_map(|s| parse::parse_piece(&s)).collect())
+}
+
+/// Like `lines_to_vec`, but with `ParseMode::Strict` a line that fails to parse makes the
+/// whole read fail with an `AocError::Parse` naming the line.
+pub fn lines_to_vec_with<T>(filename: impl AsRef<Path>, mode: ParseMode) -> AocResult<Vec<T>>
 where
     T: FromStr,
+    T::Err: Display,
 {
-    Ok(buf_reader(filename)?
-        .lines()
-        .map_while(Result::ok)
-        .flat_map(|s| s.parse())
-        .collect())
+    let lines = buf_reader(filename)?.lines().collect::<io::Result<Vec<String>>>()?;
+    parse::parse_all(lines.iter().map(String::as_str), mode)
 }
 
 /// Splits the contents of the file at `split_bit` and parses each section into `T`,
@@ -36,18 +88,76 @@ where
 /// Invalid UTF-8 chunks and parse failures are currently skipped.
 ///
 /// Notes:
-/// - Newline, CRLF and other special control bytes are preserved.
-pub fn split_to_vec<T>(filename: &str, split_bit: u8) -> io::Result<Vec<T>>
+/// - The content is normalized first (see `Normalization`): CRLF line endings become LF
+///   and a final newline is dropped. Other control bytes are preserved.
+pub fn split_to_vec<T>(filename: impl AsRef<Path>, split_bit: u8) -> AocResult<Vec<T>>
+where
+    T: FromStr,
+{
+    let pieces = split(filename, split_bit)?;
+    let _span = span("parse");
+    Ok(pieces.flat_map(|s| parse::parse_piece(&s)).collect())
+}
+
+/// Like `split_to_vec`, but the splitting behaviour is configured by `options` (see
+/// `SplitOptions`) and, with `ParseMode::Strict`, a piece that fails to parse makes the
+/// whole read fail with an `AocError::Parse` naming the piece.
+pub fn split_to_vec_with<T>(
+    filename: impl AsRef<Path>,
+    split_bit: u8,
+    options: SplitOptions,
+    mode: ParseMode,
+) -> AocResult<Vec<T>>
 where
     T: FromStr,
+    T::Err: Display,
 {
-    Ok(buf_reader(filename)?
-        .split(split_bit)
-        .flatten()
-        .filter(|v| !v.is_empty())
-        .flat_map(String::from_utf8)
-        .flat_map(|s| s.parse())
-        .collect())
+    split_parsed_with(filename, split_bit, options, mode)?.collect()
+}
+
+/// Reads the whole file as raw bytes, without any UTF-8 validation nor normalization.
+pub fn bytes(filename: impl AsRef<Path>) -> AocResult<Vec<u8>> {
+    let _span = span("read input");
+    let mut bytes = Vec::new();
+    open_file(filename)?.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Reads the whole file into a `String`, normalized with the default `Normalization` (no
+/// BOM, LF line endings, no final newline). Feed it to the `parse` helpers, e.g.
+/// `parse::blocks_to_structs` which splits blocks at "\n\n".
+///
+/// An invalid UTF-8 file is reported as an `AocError::Io` error of kind `InvalidData`.
+pub fn text(filename: impl AsRef<Path>) -> AocResult<String> {
+    text_with(filename, Normalization::default())
+}
+
+/// Like `text`, normalizing according to `normalization`.
+pub fn text_with(filename: impl AsRef<Path>, normalization: Normalization) -> AocResult<String> {
+    let bytes = normalization.apply(bytes(filename)?);
+    String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e).into())
+}
+
+//------------------------------
+// Grids
+//------------------------------
+
+/// Reads the file into a `Grid<char>`, each line being a row.
+///
+/// Trailing empty lines are ignored. Returns an `AocError::Dimensions` if the lines do not
+/// all have the same width, or an `AocError::Parse` if a non empty line follows an empty one,
+/// as in files holding several blocks.
+pub fn grid(filename: impl AsRef<Path>) -> AocResult<Grid<char>> {
+    grid_with(filename, Ok::<char, Infallible>)
+}
+
+/// Reads a file of decimal digits into a `Grid<u8>`, each line being a row.
+///
+/// Trailing empty lines are ignored. Returns an `AocError::Dimensions` if the lines do not
+/// all have the same width, or an `AocError::Parse` if some character is not a digit or a
+/// non empty line follows an empty one.
+pub fn digit_grid(filename: impl AsRef<Path>) -> AocResult<Grid<u8>> {
+    grid_with(filename, |c| c.to_digit(10).map(|d| d as u8).ok_or("not a digit"))
 }
 
 //------------------------------
@@ -58,55 +168,130 @@ where
 ///
 /// The iterator yields `io::Result<String>` for each line. Each `String` produced does
 /// not include the trailing newline byte(s) (LF or CRLF).
-pub fn lines(filename: &str) -> io::Result<io::Lines<io::BufReader<File>>> {
+pub fn lines(filename: impl AsRef<Path>) -> AocResult<io::Lines<io::BufReader<File>>> {
     Ok(buf_reader(filename)?.lines())
 }
 
+/// Returns an Iterator over the lines of a file as raw bytes, skipping UTF-8 conversion.
+///
+/// The iterator yields `io::Result<Vec<u8>>` for each line. As with `lines`, the trailing
+/// newline byte(s) (LF or CRLF) are not included.
+pub fn byte_lines(filename: impl AsRef<Path>) -> AocResult<impl Iterator<Item = io::Result<Vec<u8>>>> {
+    Ok(buf_reader(filename)?.split(b'\n').map(|line| {
+        let mut line = line?;
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        Ok(line)
+    }))
+}
+
 /// Returns an Iterator over lines parsed into type `T`.
 ///
 /// Behaviour:
-/// - The outer `io::Result` represents only the result of opening the file.
+/// - The outer `AocResult` represents only the result of opening the file.
 /// - The returned iterator currently ignores I/O errors encountered while iterating
 ///   (stops iteration) and also ignores parse failures (skips lines that fail
-///   `T::from_str`). Consider using `lines_parsed_explicit` if you need parse error
-///   information.
-pub fn lines_parsed<T>(filename: &str) -> io::Result<impl Iterator<Item = T>>
+///   `T::from_str`). Consider using `lines_parsed_explicit` or `lines_parsed_with` if you
+///   need parse error information.
+pub fn lines_parsed<T>(filename: impl AsRef<Path>) -> AocResult<impl Iterator<Item = T>>
 where
     T: FromStr,
 {
-    Ok(buf_reader(filename)?
-        .lines()
-        .map_while(Result::ok)
-        .flat_map(|s| s.parse::<T>()))
+    let lines = buf_reader(filename)?.lines().map_while(Result::ok);
+    Ok(spanned("parse", lines.flat_map(|s| parse::parse_piece::<T>(&s))))
+}
+
+/// Returns an Iterator over the lines of a file together with their line number, starting
+/// at 1.
+pub fn lines_numbered(filename: impl AsRef<Path>) -> AocResult<impl Iterator<Item = (usize, io::Result<String>)>> {
+    Ok(lines(filename)?.enumerate().map(|(i, line)| (i + 1, line)))
 }
 
 /// Variant of `lines_parsed` that exposes parse failures.
 ///
-/// Returns an iterator yielding `Result<T, <T as FromStr>::Err>` so callers can also
-/// handle parse errors explicitly.
-pub fn lines_parsed_explicit<T>(filename: &str) -> io::Result<impl Iterator<Item = Result<T, <T as FromStr>::Err>>>
+/// Returns an iterator yielding `AocResult<T>` so callers can also handle parse errors
+/// explicitly, the `AocError::Parse` errors telling which line failed.
+pub fn lines_parsed_explicit<T>(filename: impl AsRef<Path>) -> AocResult<impl Iterator<Item = AocResult<T>>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    lines_parsed_with(filename, ParseMode::Strict)
+}
+
+/// Like `lines_parsed`, with parse failures handled according to `mode`: skipped, or
+/// yielded as `AocError::Parse` errors as `lines_parsed_explicit` does. I/O errors still end
+/// the iteration.
+pub fn lines_parsed_with<T>(
+    filename: impl AsRef<Path>,
+    mode: ParseMode,
+) -> AocResult<impl Iterator<Item = AocResult<T>>>
 where
     T: FromStr,
+    T::Err: Display,
 {
-    Ok(buf_reader(filename)?
-        .lines()
-        .map_while(Result::ok)
-        .map(|s| s.parse::<T>()))
+    Ok(parse::parse_each(
+        buf_reader(filename)?.lines().map_while(Result::ok),
+        mode,
+    ))
 }
 
 /// Returns an Iterator over substrings of a file, using `split_bit` as the delimiter. The
-/// file is read as raw bytes and split on the given byte.
+/// file is read as bytes, normalized, and split on the given byte.
 ///
 /// Behaviour:
 /// - Each split chunk is converted with `String::from_utf8`. Chunks that are not valid
 ///   UTF-8 are currently skipped (they are filtered out).
 /// - Empty chunks are filtered out.
-pub fn split(filename: &str, split_bit: u8) -> io::Result<impl Iterator<Item = String>> {
-    Ok(buf_reader(filename)?
-        .split(split_bit)
-        .flatten()
-        .filter(|v| !v.is_empty())
-        .flat_map(String::from_utf8))
+pub fn split(filename: impl AsRef<Path>, split_bit: u8) -> AocResult<impl Iterator<Item = String>> {
+    split_with(filename, split_bit, SplitOptions::default())
+}
+
+/// Options for the `*_with` splitting helpers. The default values reproduce the behaviour of
+/// `split` and `split_to_vec`.
+///
+/// ```
+/// use aoc_tools::input::SplitOptions;
+/// let options = SplitOptions { trim: true, ..Default::default() };
+/// assert!(!options.keep_empty);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SplitOptions {
+    /// trims leading and trailing whitespace (including newlines) of each chunk
+    pub trim: bool,
+    /// keeps empty chunks, after trimming if `trim` is set
+    pub keep_empty: bool,
+    /// splits at most this many times, the last chunk holds the rest of the file
+    pub max_splits: Option<usize>,
+    /// how the content is cleaned up before splitting. `Normalization::NONE` splits the
+    /// raw bytes.
+    pub normalization: Normalization,
+}
+
+/// Like `split`, but the splitting behaviour is configured by `options`. See
+/// `SplitOptions`.
+///
+/// Invalid UTF-8 chunks are skipped regardless of the options.
+pub fn split_with(
+    filename: impl AsRef<Path>,
+    split_bit: u8,
+    options: SplitOptions,
+) -> AocResult<impl Iterator<Item = String>> {
+    let bytes = options.normalization.apply(bytes(filename)?);
+    let is_delimiter = |b: &u8| *b == split_bit;
+    let chunks: Vec<&[u8]> = match options.max_splits {
+        Some(n) => bytes.splitn(n + 1, is_delimiter).collect(),
+        None => bytes.split(is_delimiter).collect(),
+    };
+    let chunks: Vec<String> = chunks
+        .into_iter()
+        .flat_map(std::str::from_utf8)
+        .map(|s| if options.trim { s.trim() } else { s })
+        .filter(|s| options.keep_empty || !s.is_empty())
+        .map(String::from)
+        .collect();
+    Ok(chunks.into_iter())
 }
 
 /// Returns an Iterator over a file, where the content is split at `split_bit` and each
@@ -114,39 +299,231 @@ pub fn split(filename: &str, split_bit: u8) -> io::Result<impl Iterator<Item = S
 ///
 /// Behaviour:
 /// - Parsing failures are currently skipped (pieces that fail `T::from_str` are ignored).
-///   If you need parse errors surfaced, use `split_parsed_explicit`.
-pub fn split_parsed<T>(filename: &str, split_bit: u8) -> io::Result<impl Iterator<Item = T>>
+///   If you need parse errors surfaced, use `split_parsed_explicit` or `split_parsed_with`.
+pub fn split_parsed<T>(filename: impl AsRef<Path>, split_bit: u8) -> AocResult<impl Iterator<Item = T>>
 where
     T: FromStr,
 {
-    Ok(split(filename, split_bit)?.flat_map(|s| s.parse()))
+    Ok(spanned(
+        "parse",
+        split(filename, split_bit)?.flat_map(|s| parse::parse_piece(&s)),
+    ))
 }
 
-/// Like `split_parsed` but yields `Result<T, <T as FromStr>::Err>` so callers can handle
-/// parse failures explicitly.
+/// Like `split_parsed` but yields `AocResult<T>` so callers can handle parse failures
+/// explicitly. The `line` of the errors is the number of the piece, starting at 1.
 pub fn split_parsed_explicit<T>(
-    filename: &str,
+    filename: impl AsRef<Path>,
+    split_bit: u8,
+) -> AocResult<impl Iterator<Item = AocResult<T>>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    split_parsed_with(filename, split_bit, SplitOptions::default(), ParseMode::Strict)
+}
+
+/// Like `split_parsed`, but the splitting behaviour is configured by `options` and parse
+/// failures are handled according to `mode`, the `line` of the errors being the number of
+/// the piece.
+pub fn split_parsed_with<T>(
+    filename: impl AsRef<Path>,
     split_bit: u8,
-) -> io::Result<impl Iterator<Item = Result<T, <T as FromStr>::Err>>>
+    options: SplitOptions,
+    mode: ParseMode,
+) -> AocResult<impl Iterator<Item = AocResult<T>>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    Ok(parse::parse_each(split_with(filename, split_bit, options)?, mode))
+}
+
+/// Returns an Iterator over the pieces of a file split at any of the `delims` characters.
+///
+/// Behaviour:
+/// - The whole file is read with `text`, so an invalid UTF-8 file is reported as an
+///   `AocError::Io` error of kind `InvalidData`.
+/// - Empty pieces are filtered out.
+pub fn split_any(filename: impl AsRef<Path>, delims: &[char]) -> AocResult<impl Iterator<Item = String>> {
+    split_any_parsed(filename, delims)
+}
+
+/// Like `split_any`, but each piece is parsed into `T`. Parsing failures are skipped, see
+/// `split_any_parsed_with` to report them.
+pub fn split_any_parsed<T>(filename: impl AsRef<Path>, delims: &[char]) -> AocResult<impl Iterator<Item = T>>
+where
+    T: FromStr,
+{
+    let content = text(filename)?;
+    let pieces: Vec<T> = parse::split_any(&content, delims).collect();
+    Ok(pieces.into_iter())
+}
+
+/// Like `split_any_parsed`, with parse failures handled according to `mode`.
+pub fn split_any_parsed_with<T>(
+    filename: impl AsRef<Path>,
+    delims: &[char],
+    mode: ParseMode,
+) -> AocResult<impl Iterator<Item = AocResult<T>>>
 where
     T: FromStr,
+    T::Err: Display,
 {
-    Ok(split(filename, split_bit)?.map(|s| s.parse()))
+    let content = text(filename)?;
+    let pieces: Vec<AocResult<T>> = parse::split_any_with(&content, delims, mode).collect();
+    Ok(pieces.into_iter())
+}
+
+//------------------------------
+// Normalization
+//------------------------------
+
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Clean up applied to the content of input files before splitting it, so files saved with
+/// Windows line endings or by editors adding a byte order mark parse the same way with
+/// every helper.
+///
+/// `text` and the `split*` helpers apply it to the whole content (the default unless told
+/// otherwise), while the line based helpers always skip the BOM and strip the "\r" of
+/// CRLF endings, and never yield a final empty line.
+///
+/// ```
+/// use aoc_tools::input::Normalization;
+/// let raw = "\u{feff}a,b\r\nc\r\n";
+/// assert_eq!(Normalization::default().apply_str(raw), "a,b\nc");
+/// assert_eq!(Normalization::NONE.apply_str(raw), raw);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Normalization {
+    /// removes the UTF-8 byte order mark at the start of the content
+    pub strip_bom: bool,
+    /// replaces each "\r\n" with "\n"
+    pub crlf_to_lf: bool,
+    /// removes a single newline at the end of the content, so there is no final empty line
+    pub trim_final_newline: bool,
+}
+
+impl Default for Normalization {
+    fn default() -> Self {
+        Normalization {
+            strip_bom: true,
+            crlf_to_lf: true,
+            trim_final_newline: true,
+        }
+    }
+}
+
+impl Normalization {
+    /// leaves the content untouched
+    pub const NONE: Normalization = Normalization {
+        strip_bom: false,
+        crlf_to_lf: false,
+        trim_final_newline: false,
+    };
+
+    pub fn apply(&self, mut bytes: Vec<u8>) -> Vec<u8> {
+        if self.strip_bom && bytes.starts_with(BOM) {
+            bytes.drain(..BOM.len());
+        }
+        if self.crlf_to_lf {
+            let mut len = 0;
+            for i in 0..bytes.len() {
+                if bytes[i] != b'\r' || bytes.get(i + 1) != Some(&b'\n') {
+                    bytes[len] = bytes[i];
+                    len += 1;
+                }
+            }
+            bytes.truncate(len);
+        }
+        if self.trim_final_newline && bytes.last() == Some(&b'\n') {
+            bytes.pop();
+            if !self.crlf_to_lf && bytes.last() == Some(&b'\r') {
+                bytes.pop();
+            }
+        }
+        bytes
+    }
+
+    /// same as `apply`, on text already in memory (e.g. from `aoc_input!`)
+    pub fn apply_str(&self, text: &str) -> String {
+        let bytes = self.apply(text.as_bytes().to_vec());
+        // only ASCII bytes and a whole BOM are removed, so the text is still valid UTF-8
+        String::from_utf8(bytes).expect("normalization keeps UTF-8 valid")
+    }
 }
 
 //------------------------------
 // Helpers
 //------------------------------
 
+/// Path of `filename` inside the "inputs/" directory.
+fn input_path(filename: impl AsRef<Path>) -> PathBuf {
+    Path::new("inputs").join(filename)
+}
+
 /// Open `inputs/<filename>`.
-fn open_file(filename: &str) -> Result<File, io::Error> {
-    File::open("inputs/".to_string() + filename)
+fn open_file(filename: impl AsRef<Path>) -> io::Result<File> {
+    File::open(input_path(filename))
 }
 
-/// Returns a buffered reader for the file.
-fn buf_reader(filename: &str) -> io::Result<io::BufReader<File>> {
+/// Returns a buffered reader for the file, past the byte order mark if there is one.
+fn buf_reader(filename: impl AsRef<Path>) -> io::Result<io::BufReader<File>> {
     let file = open_file(filename)?;
-    Ok(io::BufReader::new(file))
+    let mut reader = io::BufReader::new(file);
+    if reader.fill_buf()?.starts_with(BOM) {
+        reader.consume(BOM.len());
+    }
+    Ok(reader)
+}
+
+/// Builds a grid from the file, converting each char with `f`, which returns the message of
+/// the parse error on failure. The first empty line ends the grid: anything but empty lines
+/// past it is an error.
+fn grid_with<T, F, E>(filename: impl AsRef<Path>, mut f: F) -> AocResult<Grid<T>>
+where
+    T: Clone,
+    F: FnMut(char) -> Result<T, E>,
+    E: Display,
+{
+    let mut flat = Vec::new();
+    let mut len_x = None;
+    let mut len_y = 0;
+    let mut lines = buf_reader(filename)?.lines().enumerate();
+    for (_, line) in lines.by_ref() {
+        let line = line?;
+        if line.is_empty() {
+            break;
+        }
+        let width = line.chars().count();
+        match len_x {
+            None => len_x = Some(width),
+            Some(expected) if expected != width => {
+                return Err(AocError::Dimensions {
+                    line: len_y + 1,
+                    expected,
+                    found: width,
+                });
+            }
+            _ => (),
+        }
+        for c in line.chars() {
+            flat.push(f(c).map_err(|e| AocError::parse(Some(len_y + 1), &c.to_string(), e))?);
+        }
+        len_y += 1;
+    }
+    for (i, line) in lines {
+        let line = line?;
+        if !line.is_empty() {
+            return Err(AocError::parse(
+                Some(i + 1),
+                &line,
+                "content after the empty line ending the grid",
+            ));
+        }
+    }
+    Ok(Grid::from_vec(len_x.unwrap_or(0), len_y, flat))
 }
 
 //------------------------------
@@ -156,6 +533,7 @@ fn buf_reader(filename: &str) -> io::Result<io::BufReader<File>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pair::Pair;
 
     #[test]
     fn test_lines() {
@@ -187,6 +565,142 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bytes() {
+        let raw = bytes("test_grid").unwrap();
+        assert!(raw.starts_with(b"0123\n"));
+        let rows: Vec<Vec<u8>> = byte_lines("test_grid").unwrap().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1], b"4567");
+        let text_rows = lines("test_grid").unwrap().count();
+        assert_eq!(rows.len(), text_rows);
+    }
+
+    #[test]
+    fn test_path_arguments() {
+        let from_str = lines("test").unwrap().count();
+        let from_path_buf = lines(PathBuf::from("test")).unwrap().count();
+        assert_eq!(from_str, from_path_buf);
+    }
+
+    #[test]
+    fn test_grid() {
+        let chars = grid("test_grid").unwrap();
+        assert_eq!((chars.len_x, chars.len_y), (4, 3));
+        assert_eq!(chars[(1usize, 2usize)], '9');
+
+        let digits = digit_grid("test_grid").unwrap();
+        assert_eq!(digits.row(1), &[4, 5, 6, 7]);
+
+        // lines of the lorem ipsum file have different widths
+        let err = grid("test").unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Dimensions {
+                line: 2,
+                expected: 60,
+                found: 55
+            }
+        ));
+        assert!(matches!(lines("missing"), Err(AocError::Io(_))));
+
+        // two blocks separated by an empty line are not stacked into one grid
+        let err = grid("test_blocks").unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: Some(4), .. }));
+    }
+
+    #[test]
+    fn test_split_with() {
+        let options = SplitOptions {
+            trim: true,
+            max_splits: Some(2),
+            ..Default::default()
+        };
+        let chunks: Vec<String> = split_with("test", b',', options).unwrap().collect();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[1], "consectetur adipiscing elit");
+        assert!(chunks[2].starts_with("sed\ndo eiusmod"));
+
+        let keep_empty = SplitOptions {
+            keep_empty: true,
+            ..Default::default()
+        };
+        // the file does not end with a newline, so there is no trailing empty chunk
+        let lines = split_with("test", b'\n', keep_empty).unwrap().count();
+        assert_eq!(lines, 5);
+    }
+
+    #[test]
+    fn test_split_any() {
+        let pieces: Vec<String> = split_any("test", &[',', '\n']).unwrap().collect();
+        assert_eq!(pieces.len(), 8);
+        assert_eq!(pieces[2], " sed");
+        assert_eq!(pieces[3], "do eiusmod tempor incididunt ut labore et dolore magnam");
+    }
+
+    #[test]
+    fn test_line_numbers() {
+        let (n, line) = lines_numbered("test").unwrap().last().unwrap();
+        assert_eq!(n, 5);
+        assert!(line.unwrap().starts_with("si aliquod"));
+
+        let parsed: Vec<_> = lines_parsed_explicit::<u32>("test_grid").unwrap().collect();
+        assert_eq!(parsed[1].as_ref().ok(), Some(&4567));
+        let err = lines_parsed_explicit::<u32>("test")
+            .unwrap()
+            .nth(2)
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: Some(3), .. }));
+        assert!(err.to_string().starts_with("line 3: invalid digit"));
+    }
+
+    #[test]
+    fn test_parse_mode() {
+        let lenient: Vec<u32> = lines_to_vec_with("test", ParseMode::Lenient).unwrap();
+        assert!(lenient.is_empty());
+        let err = lines_to_vec_with::<u32>("test", ParseMode::Strict).unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: Some(1), .. }));
+
+        let options = SplitOptions {
+            trim: true,
+            ..Default::default()
+        };
+        let numbers: Vec<u32> = split_to_vec_with("test_grid", b'\n', options, ParseMode::Strict).unwrap();
+        assert_eq!(numbers, vec![123, 4567, 8901]);
+        let err = split_to_vec_with::<u32>("test", b' ', options, ParseMode::Strict).unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: Some(1), .. }));
+        let skipped: Vec<_> = lines_parsed_with::<u32>("test", ParseMode::Lenient).unwrap().collect();
+        assert!(skipped.is_empty());
+        let failed = split_any_parsed_with::<u32>("test", &[' '], ParseMode::Strict)
+            .unwrap()
+            .next();
+        assert!(matches!(failed, Some(Err(AocError::Parse { line: Some(1), .. }))));
+    }
+
+    #[test]
+    fn test_normalization() {
+        // the file starts with a BOM and has CRLF line endings
+        let lines: Vec<String> = lines("test_crlf").unwrap().map(Result::unwrap).collect();
+        assert_eq!(lines, vec!["12,3", "45,6"]);
+        assert_eq!(text("test_crlf").unwrap(), "12,3\n45,6");
+        // without normalization the "\r" would make the pairs fail to parse
+        let pairs: Vec<Pair<u32>> = split_to_vec("test_crlf", b'\n').unwrap();
+        assert_eq!(pairs, vec![Pair::new(12, 3), Pair::new(45, 6)]);
+        let pieces: Vec<String> = split("test_crlf", b',').unwrap().collect();
+        assert_eq!(pieces, vec!["12", "3\n45", "6"]);
+        let numbers: Vec<u32> = split_any_parsed("test_crlf", &[',', '\n']).unwrap().collect();
+        assert_eq!(numbers, vec![12, 3, 45, 6]);
+
+        let raw = SplitOptions {
+            normalization: Normalization::NONE,
+            ..Default::default()
+        };
+        let last = split_with("test_crlf", b',', raw).unwrap().last().unwrap();
+        assert_eq!(last, "6\r\n");
+        assert!(bytes("test_crlf").unwrap().starts_with(BOM));
+    }
+
     #[test]
     fn test_lines_parsed() {
         //todo
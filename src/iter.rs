@@ -0,0 +1,278 @@
+//! Helpers over iterators missing from the standard library.
+//!
+//! - `kmerge` merges several sorted iterators into one sorted iterator, with a binary heap
+//!   holding the head of each input;
+//! - `is_strictly_sorted`, `is_sorted_desc` and `first_unsorted` validate orderings (std
+//!   already provides `Iterator::is_sorted_by`);
+//! - `window_sums` and `count_increases` compute sliding window statistics lazily.
+//!
+//! ```
+//! use aoc_tools::iter::{first_unsorted, kmerge};
+//! let merged: Vec<_> = kmerge([vec![1, 4, 7], vec![2, 5], vec![3, 6, 8]]).collect();
+//! assert_eq!(merged, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+//! assert_eq!(first_unsorted([1, 2, 2, 1, 5]), Some(3));
+//! ```
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, VecDeque},
+    ops::{Add, Sub},
+};
+
+//------------------------------
+// Merging
+//------------------------------
+
+/// Iterator returned by `kmerge`.
+#[derive(Debug, Clone)]
+pub struct KMerge<I: Iterator> {
+    sources: Vec<I>,
+    heap: BinaryHeap<Reverse<Head<I::Item>>>,
+}
+
+/// the next item of a source, ordered by item then by source so that equal items come out in
+/// the order of their sources
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Head<T> {
+    item: T,
+    source: usize,
+}
+
+impl<I> Iterator for KMerge<I>
+where
+    I: Iterator,
+    I::Item: Ord,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse(Head { item, source }) = self.heap.pop()?;
+        if let Some(next) = self.sources[source].next() {
+            self.heap.push(Reverse(Head { item: next, source }));
+        }
+        Some(item)
+    }
+}
+
+/// merges iterators that are each sorted in ascending order into a single sorted iterator.
+/// Equal items are yielded in the order of their iterators, so the merge is stable.
+pub fn kmerge<II>(iters: II) -> KMerge<<II::Item as IntoIterator>::IntoIter>
+where
+    II: IntoIterator,
+    II::Item: IntoIterator,
+    <II::Item as IntoIterator>::Item: Ord,
+{
+    let mut sources: Vec<_> = iters.into_iter().map(IntoIterator::into_iter).collect();
+    let heap = sources
+        .iter_mut()
+        .enumerate()
+        .filter_map(|(source, it)| {
+            Some(Reverse(Head {
+                item: it.next()?,
+                source,
+            }))
+        })
+        .collect();
+    KMerge { sources, heap }
+}
+
+/// An item compared only by its key.
+#[derive(Debug, Clone)]
+struct Keyed<K, T>(K, T);
+
+impl<K: Ord, T> PartialEq for Keyed<K, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<K: Ord, T> Eq for Keyed<K, T> {}
+
+impl<K: Ord, T> PartialOrd for Keyed<K, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, T> Ord for Keyed<K, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+/// same as `kmerge`, for iterators sorted by `key`, e.g. events sorted by timestamp
+///
+/// ```
+/// use aoc_tools::iter::kmerge_by_key;
+/// let a = [(1, "wake"), (5, "sleep")];
+/// let b = [(3, "begin")];
+/// let events: Vec<_> = kmerge_by_key([a.to_vec(), b.to_vec()], |e| e.0).map(|e| e.1).collect();
+/// assert_eq!(events, vec!["wake", "begin", "sleep"]);
+/// ```
+pub fn kmerge_by_key<II, K, F>(iters: II, key: F) -> impl Iterator<Item = <II::Item as IntoIterator>::Item>
+where
+    II: IntoIterator,
+    II::Item: IntoIterator,
+    K: Ord,
+    F: Fn(&<II::Item as IntoIterator>::Item) -> K + Clone,
+{
+    kmerge(iters.into_iter().map(move |it| {
+        let key = key.clone();
+        it.into_iter().map(move |x| Keyed(key(&x), x))
+    }))
+    .map(|Keyed(_, x)| x)
+}
+
+//------------------------------
+// Orderings
+//------------------------------
+
+/// returns the position of the first item smaller than the previous one, `None` if the
+/// items are sorted in ascending order
+pub fn first_unsorted<I>(iter: I) -> Option<usize>
+where
+    I: IntoIterator,
+    I::Item: PartialOrd,
+{
+    let mut iter = iter.into_iter();
+    let mut prev = iter.next()?;
+    for (i, item) in iter.enumerate() {
+        if item < prev {
+            return Some(i + 1);
+        }
+        prev = item;
+    }
+    None
+}
+
+/// returns true if each item is greater than the previous one
+pub fn is_strictly_sorted<I>(iter: I) -> bool
+where
+    I: IntoIterator,
+    I::Item: PartialOrd,
+{
+    iter.into_iter().is_sorted_by(|a, b| a < b)
+}
+
+/// returns true if the items are sorted in descending order
+pub fn is_sorted_desc<I>(iter: I) -> bool
+where
+    I: IntoIterator,
+    I::Item: PartialOrd,
+{
+    iter.into_iter().is_sorted_by(|a, b| a >= b)
+}
+
+//------------------------------
+// Windows
+//------------------------------
+
+/// Iterator returned by `window_sums`.
+#[derive(Debug, Clone)]
+pub struct WindowSums<I: Iterator> {
+    iter: I,
+    k: usize,
+    window: VecDeque<I::Item>,
+    sum: Option<I::Item>,
+}
+
+impl<I> Iterator for WindowSums<I>
+where
+    I: Iterator,
+    I::Item: Copy + Add<Output = I::Item> + Sub<Output = I::Item>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.window.len() < self.k {
+            let item = self.iter.next()?;
+            self.window.push_back(item);
+            self.sum = Some(self.sum.map_or(item, |sum| sum + item));
+        }
+        let sum = self.sum?;
+        let oldest = self.window.pop_front()?;
+        // the sum of the next window, once its last item is pushed
+        self.sum = (self.k > 1).then(|| sum - oldest);
+        Some(sum)
+    }
+}
+
+/// lazily yields the sum of each window of `k` consecutive items, updating the sum as the
+/// window slides instead of adding the `k` items again. Yields nothing if there are fewer
+/// than `k` items.
+///
+/// # Panics
+///
+/// Panics if `k` is 0.
+///
+/// ```
+/// use aoc_tools::iter::{count_increases, window_sums};
+/// let depths = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
+/// let depths = aoc_tools::parse::lines::<u32>(depths);
+/// assert_eq!(count_increases(window_sums(depths, 3)), 5);
+/// ```
+pub fn window_sums<I>(iter: I, k: usize) -> WindowSums<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: Copy + Add<Output = I::Item> + Sub<Output = I::Item>,
+{
+    assert!(k > 0, "windows must hold at least one item");
+    WindowSums {
+        iter: iter.into_iter(),
+        k,
+        window: VecDeque::with_capacity(k),
+        sum: None,
+    }
+}
+
+/// counts the items greater than the previous one
+pub fn count_increases<I>(iter: I) -> usize
+where
+    I: IntoIterator,
+    I::Item: PartialOrd,
+{
+    let mut iter = iter.into_iter();
+    let Some(mut prev) = iter.next() else {
+        return 0;
+    };
+    let mut count = 0;
+    for item in iter {
+        count += (item > prev) as usize;
+        prev = item;
+    }
+    count
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kmerge_is_stable() {
+        let a = [(1, 'a'), (2, 'a')];
+        let b = [(1, 'b'), (3, 'b')];
+        let merged: Vec<_> = kmerge_by_key([a.iter(), b.iter(), [].iter()], |e| e.0)
+            .map(|e| e.1)
+            .collect();
+        assert_eq!(merged, vec!['a', 'b', 'a', 'b']);
+        assert_eq!(kmerge(Vec::<Vec<u8>>::new()).count(), 0);
+
+        assert!(is_strictly_sorted([1, 2, 3]) && !is_strictly_sorted([1, 1]));
+        assert!(is_sorted_desc([3, 3, 1]));
+        assert_eq!(first_unsorted::<[u8; 0]>([]), None);
+    }
+
+    #[test]
+    fn test_window_sums() {
+        let items = [1, 2, 3, 4, 5];
+        assert_eq!(window_sums(items, 1).collect::<Vec<_>>(), items);
+        assert_eq!(window_sums(items, 2).collect::<Vec<_>>(), vec![3, 5, 7, 9]);
+        assert_eq!(window_sums(items, 5).collect::<Vec<_>>(), vec![15]);
+        assert_eq!(window_sums(items, 6).count(), 0);
+        assert_eq!(count_increases([3, 1, 2, 2, 5]), 2);
+        assert_eq!(count_increases::<[u8; 0]>([]), 0);
+    }
+}
@@ -1,4 +1,40 @@
+pub mod agents;
+pub mod algorithms;
+pub mod ascii;
+pub mod automaton;
+pub mod bits;
+pub mod cache;
+pub mod cursor;
+pub mod direction;
+pub mod error;
+pub mod expr;
+pub mod falling;
+pub mod flag_grid;
+pub mod font;
+pub mod game;
+pub mod geometry;
+pub mod geometry3;
+pub mod graph;
 pub mod grid;
+pub mod grid_journal;
+pub mod grid_view;
+pub mod hash;
 pub mod input;
+pub mod iter;
+pub mod list;
+pub mod marked_grid;
+pub mod math;
+pub mod packed_grid;
 pub mod pair;
 pub mod parse;
+pub mod pathfinding;
+pub mod point;
+pub mod prelude;
+pub mod rand;
+pub mod rules;
+pub mod runner;
+pub mod search;
+pub mod sparse_grid;
+pub mod stack_grid;
+pub mod strings;
+pub mod zobrist;
@@ -0,0 +1,378 @@
+//! A doubly linked list backed by an arena, addressed by index handles.
+//!
+//! Nodes are never deallocated: removing an element only unlinks it, so its `Handle` stays
+//! valid and the element may be linked back in with `move_after`/`move_before`. This makes
+//! the "remove and reinsert elsewhere" operations of mixing puzzles O(1) once the target
+//! node is known, instead of the O(n) shifting of a `Vec`.
+//!
+//! ```
+//! use aoc_tools::list::ArenaList;
+//! let mut list = ArenaList::new();
+//! let a = list.push_back('a');
+//! let b = list.push_back('b');
+//! list.push_back('c');
+//! list.move_after(a, b);
+//! assert_eq!(list.iter().collect::<String>(), "bac");
+//! ```
+use std::ops::{Index, IndexMut};
+
+/// Identifies a node of an `ArenaList`. Only meaningful for the list that created it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Handle(usize);
+
+impl Handle {
+    /// position of the node in the arena, which is its insertion order
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    value: T,
+    prev: Option<usize>,
+    next: Option<usize>,
+    linked: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArenaList<T> {
+    nodes: Vec<Node<T>>,
+    head: Option<usize>,
+    tail: Option<usize>,
+    len: usize,
+}
+
+impl<T> Default for ArenaList<T> {
+    fn default() -> Self {
+        ArenaList {
+            nodes: Vec::new(),
+            head: None,
+            tail: None,
+            len: 0,
+        }
+    }
+}
+
+impl<T> ArenaList<T> {
+    pub fn new() -> ArenaList<T> {
+        ArenaList::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> ArenaList<T> {
+        ArenaList {
+            nodes: Vec::with_capacity(capacity),
+            ..ArenaList::default()
+        }
+    }
+
+    /// number of linked elements
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    //------------------------------
+    // Insertion
+    //------------------------------
+
+    pub fn push_back(&mut self, value: T) -> Handle {
+        let i = self.alloc(value);
+        self.link_between(i, self.tail, None);
+        Handle(i)
+    }
+
+    pub fn push_front(&mut self, value: T) -> Handle {
+        let i = self.alloc(value);
+        self.link_between(i, None, self.head);
+        Handle(i)
+    }
+
+    /// inserts `value` right after the node `at`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is not linked.
+    pub fn insert_after(&mut self, at: Handle, value: T) -> Handle {
+        self.assert_linked(at);
+        let i = self.alloc(value);
+        self.link_between(i, Some(at.0), self.nodes[at.0].next);
+        Handle(i)
+    }
+
+    /// inserts `value` right before the node `at`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is not linked.
+    pub fn insert_before(&mut self, at: Handle, value: T) -> Handle {
+        self.assert_linked(at);
+        let i = self.alloc(value);
+        self.link_between(i, self.nodes[at.0].prev, Some(at.0));
+        Handle(i)
+    }
+
+    //------------------------------
+    // Removal and moves
+    //------------------------------
+
+    /// unlinks the node `h` from the list. Returns false if it was not linked.
+    ///
+    /// The value stays in the arena, so it is still accessible through `h` and may be linked
+    /// back with `move_after` or `move_before`.
+    pub fn remove(&mut self, h: Handle) -> bool {
+        if !self.nodes[h.0].linked {
+            return false;
+        }
+        self.unlink(h.0);
+        true
+    }
+
+    /// moves the node `h` right after `at`. `h` may be either linked or removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is not linked.
+    pub fn move_after(&mut self, h: Handle, at: Handle) {
+        if h == at {
+            return;
+        }
+        self.remove(h);
+        self.assert_linked(at);
+        self.link_between(h.0, Some(at.0), self.nodes[at.0].next);
+    }
+
+    /// moves the node `h` right before `at`. `h` may be either linked or removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is not linked.
+    pub fn move_before(&mut self, h: Handle, at: Handle) {
+        if h == at {
+            return;
+        }
+        self.remove(h);
+        self.assert_linked(at);
+        self.link_between(h.0, self.nodes[at.0].prev, Some(at.0));
+    }
+
+    /// moves the run of nodes from `first` to `last` (inclusive, following `next` links) so
+    /// that it comes right after `at`, keeping its internal order. Takes time linear in the
+    /// length of the run, which is walked to check the handles.
+    ///
+    /// # Panics
+    ///
+    /// - If `first` is not linked, or `last` is not reachable from `first`;
+    /// - If `at` is part of the run or is not linked.
+    pub fn splice(&mut self, first: Handle, last: Handle, at: Handle) {
+        self.assert_linked(first);
+        self.assert_linked(at);
+        let mut cur = first.0;
+        while cur != last.0 {
+            assert!(cur != at.0, "splice target is inside the moved run");
+            cur = self.nodes[cur]
+                .next
+                .expect("splice `last` is not reachable from `first`");
+        }
+        assert!(last.0 != at.0, "splice target is inside the moved run");
+
+        // detach the run
+        let before = self.nodes[first.0].prev;
+        let after = self.nodes[last.0].next;
+        self.set_next(before, after);
+        self.set_prev(after, before);
+
+        // reattach after `at`
+        let next = self.nodes[at.0].next;
+        self.nodes[at.0].next = Some(first.0);
+        self.nodes[first.0].prev = Some(at.0);
+        self.nodes[last.0].next = next;
+        self.set_prev(next, Some(last.0));
+    }
+
+    //------------------------------
+    // Access and navigation
+    //------------------------------
+
+    pub fn get(&self, h: Handle) -> &T {
+        &self.nodes[h.0].value
+    }
+
+    pub fn get_mut(&mut self, h: Handle) -> &mut T {
+        &mut self.nodes[h.0].value
+    }
+
+    pub fn is_linked(&self, h: Handle) -> bool {
+        self.nodes[h.0].linked
+    }
+
+    pub fn front(&self) -> Option<Handle> {
+        self.head.map(Handle)
+    }
+
+    pub fn back(&self) -> Option<Handle> {
+        self.tail.map(Handle)
+    }
+
+    pub fn next(&self, h: Handle) -> Option<Handle> {
+        self.nodes[h.0].next.map(Handle)
+    }
+
+    pub fn prev(&self, h: Handle) -> Option<Handle> {
+        self.nodes[h.0].prev.map(Handle)
+    }
+
+    /// like `next`, but wraps from the back of the list to the front, treating it as a ring
+    pub fn next_cyclic(&self, h: Handle) -> Handle {
+        self.next(h).or(self.front()).unwrap_or(h)
+    }
+
+    /// like `prev`, but wraps from the front of the list to the back, treating it as a ring
+    pub fn prev_cyclic(&self, h: Handle) -> Handle {
+        self.prev(h).or(self.back()).unwrap_or(h)
+    }
+
+    /// iterates over the linked values from front to back
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.handles().map(|h| self.get(h))
+    }
+
+    /// iterates over the handles of the linked nodes from front to back
+    pub fn handles(&self) -> impl Iterator<Item = Handle> + '_ {
+        std::iter::successors(self.front(), |&h| self.next(h))
+    }
+
+    //------------------------------
+    // Private
+    //------------------------------
+
+    fn alloc(&mut self, value: T) -> usize {
+        self.nodes.push(Node {
+            value,
+            prev: None,
+            next: None,
+            linked: false,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn assert_linked(&self, h: Handle) {
+        assert!(self.nodes[h.0].linked, "handle {} is not linked", h.0);
+    }
+
+    fn set_next(&mut self, node: Option<usize>, next: Option<usize>) {
+        match node {
+            Some(i) => self.nodes[i].next = next,
+            None => self.head = next,
+        }
+    }
+
+    fn set_prev(&mut self, node: Option<usize>, prev: Option<usize>) {
+        match node {
+            Some(i) => self.nodes[i].prev = prev,
+            None => self.tail = prev,
+        }
+    }
+
+    fn link_between(&mut self, i: usize, prev: Option<usize>, next: Option<usize>) {
+        self.nodes[i].prev = prev;
+        self.nodes[i].next = next;
+        self.nodes[i].linked = true;
+        self.set_next(prev, Some(i));
+        self.set_prev(next, Some(i));
+        self.len += 1;
+    }
+
+    fn unlink(&mut self, i: usize) {
+        let (prev, next) = (self.nodes[i].prev, self.nodes[i].next);
+        self.set_next(prev, next);
+        self.set_prev(next, prev);
+        let node = &mut self.nodes[i];
+        node.prev = None;
+        node.next = None;
+        node.linked = false;
+        self.len -= 1;
+    }
+}
+
+impl<T> FromIterator<T> for ArenaList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = ArenaList::new();
+        iter.into_iter().for_each(|v| {
+            list.push_back(v);
+        });
+        list
+    }
+}
+
+impl<T> Index<Handle> for ArenaList<T> {
+    type Output = T;
+
+    fn index(&self, h: Handle) -> &Self::Output {
+        self.get(h)
+    }
+}
+
+impl<T> IndexMut<Handle> for ArenaList<T> {
+    fn index_mut(&mut self, h: Handle) -> &mut Self::Output {
+        self.get_mut(h)
+    }
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(list: &ArenaList<u32>) -> Vec<u32> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut list = ArenaList::new();
+        let b = list.push_back(2);
+        let a = list.push_front(1);
+        let d = list.insert_after(b, 4);
+        list.insert_before(d, 3);
+        assert_eq!(collect(&list), vec![1, 2, 3, 4]);
+
+        assert!(list.remove(a));
+        assert!(!list.remove(a));
+        assert!(list.remove(d));
+        assert_eq!(collect(&list), vec![2, 3]);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[a], 1);
+
+        list.move_before(a, b);
+        assert_eq!(collect(&list), vec![1, 2, 3]);
+        assert_eq!(list.prev_cyclic(a), list.back().unwrap());
+    }
+
+    #[test]
+    fn test_splice() {
+        let mut list: ArenaList<u32> = (0..6).collect();
+        let handles: Vec<Handle> = list.handles().collect();
+        list.splice(handles[1], handles[2], handles[4]);
+        assert_eq!(collect(&list), vec![0, 3, 4, 1, 2, 5]);
+        list.splice(handles[0], handles[3], handles[5]);
+        assert_eq!(collect(&list), vec![4, 1, 2, 5, 0, 3]);
+        assert_eq!(list.back(), Some(handles[3]));
+
+        // bad handles panic before the list is modified
+        list.remove(handles[4]);
+        let bad = [(2, 1, 0), (1, 5, 2), (4, 4, 0), (0, 0, 4)];
+        for (first, last, at) in bad {
+            let (mut copy, [first, last, at]) = (list.clone(), [first, last, at].map(|i| handles[i]));
+            let spliced = std::panic::catch_unwind(move || copy.splice(first, last, at));
+            assert!(spliced.is_err());
+        }
+        assert_eq!(collect(&list), vec![1, 2, 5, 0, 3]);
+    }
+}
@@ -0,0 +1,89 @@
+//! A grid of values with a parallel grid of marks, as in a bingo board.
+//!
+//! ```
+//! use aoc_tools::grid::Grid;
+//! use aoc_tools::marked_grid::MarkedGrid;
+//! let mut board = MarkedGrid::new(Grid::from_vec(2, 2, vec![1u32, 2, 3, 4]));
+//! board.mark_value(&1);
+//! assert!(!board.any_row_complete());
+//! board.mark_value(&3);
+//! assert!(board.any_col_complete());
+//! assert_eq!(board.unmarked_sum(), 6);
+//! ```
+use crate::grid::Grid;
+use std::iter::Sum;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MarkedGrid<T> {
+    pub values: Grid<T>,
+    pub marks: Grid<bool>,
+}
+
+impl<T> MarkedGrid<T> {
+    /// wraps `values` with all the cells unmarked
+    pub fn new(values: Grid<T>) -> MarkedGrid<T> {
+        let marks = Grid::new(values.len_x, values.len_y, false);
+        MarkedGrid { values, marks }
+    }
+
+    pub fn is_marked(&self, x: usize, y: usize) -> bool {
+        *self.marks.get(x, y)
+    }
+
+    pub fn mark(&mut self, x: usize, y: usize) {
+        *self.marks.get_mut(x, y) = true;
+    }
+
+    /// removes all the marks
+    pub fn reset(&mut self) {
+        self.marks.iter_mut().for_each(|m| *m = false);
+    }
+
+    /// returns true if every cell of some row is marked
+    pub fn any_row_complete(&self) -> bool {
+        (0..self.marks.len_y).any(|y| self.marks.row(y).iter().all(|&m| m))
+    }
+
+    /// returns true if every cell of some column is marked
+    pub fn any_col_complete(&self) -> bool {
+        (0..self.marks.len_x).any(|x| self.marks.iter_col(x).all(|&m| m))
+    }
+
+    /// returns true if some row or column is completely marked
+    pub fn is_complete(&self) -> bool {
+        self.any_row_complete() || self.any_col_complete()
+    }
+
+    /// iterates over the values that are not marked
+    pub fn unmarked(&self) -> impl Iterator<Item = &T> {
+        self.values
+            .iter()
+            .zip(self.marks.iter())
+            .filter(|&(_, &m)| !m)
+            .map(|(v, _)| v)
+    }
+}
+
+impl<T: PartialEq> MarkedGrid<T> {
+    /// marks every cell holding `value`, returning how many cells matched.
+    pub fn mark_value(&mut self, value: &T) -> usize {
+        let mut count = 0;
+        for (v, m) in self.values.iter().zip(self.marks.iter_mut()) {
+            if v == value {
+                *m = true;
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+impl<T> MarkedGrid<T>
+where
+    T: Copy + Sum<T>,
+{
+    /// sum of the values that are not marked
+    pub fn unmarked_sum(&self) -> T {
+        self.unmarked().copied().sum()
+    }
+}
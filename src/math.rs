@@ -0,0 +1,312 @@
+//! Digit manipulation, base conversions, extrapolation of sequences and exact solutions of
+//! small linear systems.
+//!
+//! Balanced bases are described by their symbols, from the most negative to the most
+//! positive digit: SNAFU numbers use `"=-012"`, i.e. base 5 with digits `-2..=2`.
+//!
+//! ```
+//! use aoc_tools::math::{digits, from_balanced_base, to_balanced_base, to_base};
+//! assert_eq!(digits(1203), vec![1, 2, 0, 3]);
+//! assert_eq!(to_base(255, 16), "ff");
+//! assert_eq!(to_balanced_base(2022, "=-012"), "1=11-2");
+//! assert_eq!(from_balanced_base("1=11-2", "=-012"), Some(2022));
+//! ```
+
+/// the decimal digits of `n`, most significant first
+pub fn digits(n: u64) -> Vec<u8> {
+    digits_in_base(n, 10)
+}
+
+/// the digits of `n` in `base`, most significant first. `0` has the single digit `0`.
+///
+/// # Panics
+///
+/// Panics if `base < 2`.
+pub fn digits_in_base(mut n: u64, base: u64) -> Vec<u8> {
+    assert!(base >= 2, "base must be at least 2");
+    let mut digits = vec![(n % base) as u8];
+    n /= base;
+    while n > 0 {
+        digits.push((n % base) as u8);
+        n /= base;
+    }
+    digits.reverse();
+    digits
+}
+
+/// inverse of `digits`; overflow wraps
+pub fn from_digits(digits: &[u8]) -> u64 {
+    from_digits_in_base(digits, 10)
+}
+
+/// inverse of `digits_in_base`; overflow wraps
+pub fn from_digits_in_base(digits: &[u8], base: u64) -> u64 {
+    digits
+        .iter()
+        .fold(0u64, |acc, &d| acc.wrapping_mul(base).wrapping_add(d as u64))
+}
+
+/// renders `n` in `base` (up to 36) with the digits `0-9a-z`
+///
+/// # Panics
+///
+/// Panics if `base` is not in `2..=36`.
+pub fn to_base(n: u64, base: u32) -> String {
+    assert!((2..=36).contains(&base), "base must be in 2..=36");
+    digits_in_base(n, base as u64)
+        .into_iter()
+        .map(|d| char::from_digit(d as u32, base).unwrap())
+        .collect()
+}
+
+/// parses `s` in `base` (up to 36), case insensitive. Returns `None` on invalid digits,
+/// an empty string or overflow.
+pub fn from_base(s: &str, base: u32) -> Option<u64> {
+    u64::from_str_radix(s, base).ok()
+}
+
+/// renders `n` in the balanced base whose digits are `symbols`, ordered from the most
+/// negative to the most positive one.
+///
+/// # Panics
+///
+/// Panics if the number of symbols is not odd or is less than 3.
+pub fn to_balanced_base(n: i64, symbols: &str) -> String {
+    let symbols: Vec<char> = symbols.chars().collect();
+    let base = symbols.len() as i64;
+    assert!(
+        base >= 3 && base % 2 == 1,
+        "a balanced base needs an odd number of symbols"
+    );
+    let half = base / 2;
+    let mut n = n as i128;
+    let mut out = Vec::new();
+    loop {
+        // digit in -half..=half
+        let d = (n + half as i128).rem_euclid(base as i128) - half as i128;
+        out.push(symbols[(d + half as i128) as usize]);
+        n = (n - d) / base as i128;
+        if n == 0 {
+            break;
+        }
+    }
+    out.iter().rev().collect()
+}
+
+/// parses `s` in the balanced base whose digits are `symbols`. Returns `None` on unknown
+/// symbols, an empty string or overflow.
+pub fn from_balanced_base(s: &str, symbols: &str) -> Option<i64> {
+    let symbols: Vec<char> = symbols.chars().collect();
+    let base = symbols.len() as i64;
+    let half = base / 2;
+    if s.is_empty() {
+        return None;
+    }
+    s.chars().try_fold(0i64, |acc, c| {
+        let d = symbols.iter().position(|&sym| sym == c)? as i64 - half;
+        acc.checked_mul(base)?.checked_add(d)
+    })
+}
+
+//------------------------------
+// Sequences
+//------------------------------
+
+/// the difference table of `seq`: `seq` itself, then the differences between consecutive
+/// terms of the previous row, until a row is all zeros (that row included) or empty.
+pub fn differences(seq: &[i64]) -> Vec<Vec<i64>> {
+    let mut table = vec![seq.to_vec()];
+    while let Some(row) = table.last().filter(|row| row.iter().any(|&d| d != 0)) {
+        let next = row.windows(2).map(|w| w[1] - w[0]).collect();
+        table.push(next);
+    }
+    table
+}
+
+/// the term after the last of `seq`, assuming it is a polynomial sequence: the sum of the
+/// last terms of each row of the difference table
+pub fn extrapolate_next(seq: &[i64]) -> i64 {
+    differences(seq).iter().filter_map(|row| row.last()).sum()
+}
+
+/// the term before the first of `seq`, the same way as `extrapolate_next`
+///
+/// ```
+/// use aoc_tools::math::{extrapolate_next, extrapolate_prev};
+/// let seq = [10, 13, 16, 21, 30, 45];
+/// assert_eq!(extrapolate_next(&seq), 68);
+/// assert_eq!(extrapolate_prev(&seq), 5);
+/// ```
+pub fn extrapolate_prev(seq: &[i64]) -> i64 {
+    differences(seq)
+        .iter()
+        .rev()
+        .filter_map(|row| row.first())
+        .fold(0, |below, &first| first - below)
+}
+
+/// the common difference of `seq` if it is an arithmetic sequence of at least two terms
+pub fn common_difference(seq: &[i64]) -> Option<i64> {
+    let step = seq.get(1)? - seq[0];
+    seq.windows(2).all(|w| w[1] - w[0] == step).then_some(step)
+}
+
+//------------------------------
+// Linear systems
+//------------------------------
+
+/// Outcome of `solve_linear_2x2` and `solve_linear_3x3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Solution<const N: usize> {
+    /// the unique solution, all of its components being integers
+    Integer([i64; N]),
+    /// the unique solution, some component not being an integer: `x[i] = num[i] / den`,
+    /// with `den > 1` and no common factor to all the numerators and the denominator
+    Rational { num: [i64; N], den: i64 },
+    /// no unique solution: none or infinitely many
+    Singular,
+}
+
+impl<const N: usize> Solution<N> {
+    /// the solution if it is a unique integer one
+    pub fn integer(self) -> Option<[i64; N]> {
+        match self {
+            Solution::Integer(x) => Some(x),
+            _ => None,
+        }
+    }
+}
+
+/// solves `a * x = b` exactly with Cramer's rule, `a` given row by row. Intermediate
+/// products are computed in `i128`.
+///
+/// # Panics
+///
+/// Panics if a component of the solution does not fit in an `i64`.
+///
+/// ```
+/// use aoc_tools::math::{solve_linear_2x2, Solution};
+/// // button A moves the claw by (94, 34), B by (22, 67): reach (8400, 5400)
+/// let presses = solve_linear_2x2([[94, 22], [34, 67]], [8400, 5400]);
+/// assert_eq!(presses, Solution::Integer([80, 40]));
+/// let half = solve_linear_2x2([[2, 0], [0, 1]], [1, 1]);
+/// assert_eq!(half, Solution::Rational { num: [1, 2], den: 2 });
+/// assert_eq!(solve_linear_2x2([[1, 2], [2, 4]], [3, 6]), Solution::Singular);
+/// ```
+pub fn solve_linear_2x2(a: [[i64; 2]; 2], b: [i64; 2]) -> Solution<2> {
+    let det2 = |m: [[i128; 2]; 2]| m[0][0] * m[1][1] - m[0][1] * m[1][0];
+    let m = a.map(|row| row.map(i128::from));
+    let b = b.map(i128::from);
+    let nums = [0, 1].map(|col| {
+        let mut mc = m;
+        (0..2).for_each(|row| mc[row][col] = b[row]);
+        det2(mc)
+    });
+    exact_solution(nums, det2(m))
+}
+
+/// the 3x3 version of `solve_linear_2x2`
+///
+/// # Panics
+///
+/// Panics if a determinant overflows an `i128` or a component of the solution does not
+/// fit in an `i64`.
+pub fn solve_linear_3x3(a: [[i64; 3]; 3], b: [i64; 3]) -> Solution<3> {
+    let det3 = |m: [[i128; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1]) - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let m = a.map(|row| row.map(i128::from));
+    let b = b.map(i128::from);
+    let nums = [0, 1, 2].map(|col| {
+        let mut mc = m;
+        (0..3).for_each(|row| mc[row][col] = b[row]);
+        det3(mc)
+    });
+    exact_solution(nums, det3(m))
+}
+
+/// `nums / det`, reduced
+fn exact_solution<const N: usize>(mut nums: [i128; N], mut det: i128) -> Solution<N> {
+    if det == 0 {
+        return Solution::Singular;
+    }
+    if det < 0 {
+        (det, nums) = (-det, nums.map(|n| -n));
+    }
+    let gcd = |mut a: i128, mut b: i128| {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a.abs()
+    };
+    let common = nums.iter().fold(det, |g, &n| gcd(g, n));
+    let fit = |v: i128| i64::try_from(v).expect("solution does not fit in an i64");
+    let (nums, den) = (nums.map(|n| fit(n / common)), fit(det / common));
+    match den {
+        1 => Solution::Integer(nums),
+        _ => Solution::Rational { num: nums, den },
+    }
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trips() {
+        for n in [0, 1, 9, 10, 4890, u64::MAX] {
+            assert_eq!(from_digits(&digits(n)), n);
+            assert_eq!(from_base(&to_base(n, 7), 7), Some(n));
+        }
+        for n in [-4890, -3, -1, 0, 1, 2, 3, 314159265, i64::MIN + 1, i64::MAX] {
+            assert_eq!(
+                from_balanced_base(&to_balanced_base(n, "=-012"), "=-012"),
+                Some(n),
+                "n = {n}"
+            );
+        }
+        assert_eq!(to_balanced_base(3, "=-012"), "1=");
+        assert_eq!(to_balanced_base(-1, "T01"), "T");
+        assert_eq!(from_balanced_base("1x", "=-012"), None);
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        let squares: Vec<i64> = (1..=6).map(|n| n * n).collect();
+        assert_eq!(differences(&squares).len(), 4);
+        assert_eq!(extrapolate_next(&squares), 49);
+        assert_eq!(extrapolate_prev(&squares), 0);
+        assert_eq!((extrapolate_next(&[0, 3, 6]), extrapolate_prev(&[0, 3, 6])), (9, -3));
+        assert_eq!(extrapolate_next(&[]), 0);
+        assert_eq!(common_difference(&[7, 4, 1, -2]), Some(-3));
+        assert_eq!(common_difference(&squares), None);
+        assert_eq!(common_difference(&[5]), None);
+    }
+
+    #[test]
+    fn test_solve_linear() {
+        // the claw machine of the second part: large targets, exact integer answers
+        let b = [10000000012748, 10000000012176];
+        assert_eq!(
+            solve_linear_2x2([[26, 67], [66, 21]], b),
+            Solution::Integer([118679050709, 103199174542])
+        );
+        assert_eq!(solve_linear_2x2([[-2, 0], [0, 1]], [1, 3]).integer(), None);
+        assert_eq!(
+            solve_linear_2x2([[-2, 0], [0, 1]], [1, 3]),
+            Solution::Rational { num: [-1, 6], den: 2 }
+        );
+
+        let a = [[2, 1, -1], [-3, -1, 2], [-2, 1, 2]];
+        assert_eq!(solve_linear_3x3(a, [8, -11, -3]), Solution::Integer([2, 3, -1]));
+        assert_eq!(
+            solve_linear_3x3([[1, 2, 3], [2, 4, 6], [0, 1, 1]], [1, 2, 3]),
+            Solution::Singular
+        );
+    }
+}
@@ -0,0 +1,360 @@
+//! A two-dimensional array of small integers, packed into 2 or 4 bits per cell.
+//!
+//! Maps with tiny alphabets (open/tree/lumberyard, on/off) waste most of a byte per cell.
+//! Packing them keeps huge maps in cache, which makes automata steps noticeably faster.
+//! Cells hold values in `0..2^BITS`; map the symbols of the puzzle to small integers first.
+//!
+//! Same row major layout as `Grid`: `x` is the column and `y` the row.
+//!
+//! ```
+//! use aoc_tools::packed_grid::PackedGrid;
+//! let mut grid = PackedGrid::<2>::new(100, 100, 0);
+//! grid.set(3, 4, 2);
+//! assert_eq!(grid.get(3, 4), 2);
+//! assert_eq!(grid.iter().filter(|&v| v == 2).count(), 1);
+//! ```
+use crate::{grid::Grid, pair::Pair};
+use std::{fmt::Debug, hash::Hash, marker::PhantomData};
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct PackedGrid<const BITS: usize> {
+    words: Vec<u64>,
+    pub len_x: usize,
+    pub len_y: usize,
+}
+
+impl<const BITS: usize> PackedGrid<BITS> {
+    const PER_WORD: usize = 64 / BITS;
+    const MASK: u64 = (1 << BITS) - 1;
+
+    /// largest value a cell can hold
+    pub const MAX: u8 = (1 << BITS) - 1;
+
+    /// creates a new grid with all the elements having the `init`ial value
+    ///
+    /// # Panics
+    ///
+    /// Panics if `init` does not fit in `BITS` bits.
+    pub fn new(len_x: usize, len_y: usize, init: u8) -> PackedGrid<BITS> {
+        const { assert!(BITS == 2 || BITS == 4, "PackedGrid supports 2 or 4 bits per cell") };
+        assert!(init <= Self::MAX, "value {init} does not fit in {BITS} bits");
+        let pattern = (0..Self::PER_WORD).fold(0u64, |acc, i| acc | (init as u64) << (i * BITS));
+        PackedGrid {
+            words: vec![pattern; (len_x * len_y).div_ceil(Self::PER_WORD)],
+            len_x,
+            len_y,
+        }
+    }
+
+    /// The input iterator must have at least `len_x` * `len_y` elements, the remaining are
+    /// ignored.
+    ///
+    /// # Panics
+    ///
+    /// - If `iter` does not have enough elements to fill the grid;
+    /// - If some value does not fit in `BITS` bits.
+    pub fn from_iter<I>(len_x: usize, len_y: usize, iter: I) -> PackedGrid<BITS>
+    where
+        I: IntoIterator<Item = u8>,
+    {
+        let mut grid = PackedGrid::new(len_x, len_y, 0);
+        let mut iter = iter.into_iter();
+        for idx in 0..grid.size() {
+            let v = iter
+                .next()
+                .expect("iterator does not have enough elements to fill the grid");
+            grid.set_flat(idx, v);
+        }
+        grid
+    }
+
+    /// packs a `Grid<u8>`
+    pub fn from_grid(grid: &Grid<u8>) -> PackedGrid<BITS> {
+        PackedGrid::from_iter(grid.len_x, grid.len_y, grid.iter().copied())
+    }
+
+    /// unpacks into a `Grid<u8>`
+    pub fn to_grid(&self) -> Grid<u8> {
+        Grid::from_vec(self.len_x, self.len_y, self.iter().collect())
+    }
+
+    //------------------------------
+    // Single elements
+    //------------------------------
+
+    /// returns the value at position x,y.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        self.get_flat(self.index(x, y))
+    }
+
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds or if `v` does not fit in `BITS` bits.
+    pub fn set(&mut self, x: usize, y: usize, v: u8) {
+        self.set_flat(self.index(x, y), v);
+    }
+
+    /// returns the value at position `idx` in row major order
+    pub fn get_flat(&self, idx: usize) -> u8 {
+        assert!(idx < self.size(), "index out of bounds");
+        let (word, shift) = Self::locate(idx);
+        ((self.words[word] >> shift) & Self::MASK) as u8
+    }
+
+    pub fn set_flat(&mut self, idx: usize, v: u8) {
+        assert!(idx < self.size(), "index out of bounds");
+        assert!(v <= Self::MAX, "value {v} does not fit in {BITS} bits");
+        let (word, shift) = Self::locate(idx);
+        let w = &mut self.words[word];
+        *w = (*w & !(Self::MASK << shift)) | (v as u64) << shift;
+    }
+
+    //------------------------------
+    // Multiple elements
+    //------------------------------
+
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..self.size()).map(|idx| self.get_flat(idx))
+    }
+
+    pub fn row(&self, y: usize) -> impl Iterator<Item = u8> + '_ {
+        (0..self.len_x).map(move |x| self.get(x, y))
+    }
+
+    pub fn iter_col(&self, x: usize) -> impl Iterator<Item = u8> + '_ {
+        (0..self.len_y).map(move |y| self.get(x, y))
+    }
+
+    //------------------------------
+    // Helpers
+    //------------------------------
+
+    /// returns the total size of the array (len_x * len_y)
+    pub fn size(&self) -> usize {
+        self.len_x * self.len_y
+    }
+
+    pub fn xy_index(&self, idx: usize) -> (usize, usize) {
+        (idx % self.len_x, idx / self.len_x)
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        assert!(x < self.len_x && y < self.len_y, "index out of bounds");
+        self.len_x * y + x
+    }
+
+    fn locate(idx: usize) -> (usize, usize) {
+        (idx / Self::PER_WORD, (idx % Self::PER_WORD) * BITS)
+    }
+}
+
+//------------------------------
+// Integer keys
+//------------------------------
+
+/// Unsigned integers a small grid can be packed into, see `GridKey`.
+pub trait StateKey: Copy + Eq + Hash + Debug {
+    const BITS: usize;
+    const ZERO: Self;
+
+    /// the `bits` bits starting at `shift`
+    fn field(self, shift: usize, bits: usize) -> u8;
+
+    /// replaces the `bits` bits starting at `shift` with `v`
+    fn with_field(self, shift: usize, bits: usize, v: u8) -> Self;
+}
+
+macro_rules! impl_state_key {
+    ($($t:ty),*) => {
+        $(
+            impl StateKey for $t {
+                const BITS: usize = <$t>::BITS as usize;
+                const ZERO: $t = 0;
+
+                fn field(self, shift: usize, bits: usize) -> u8 {
+                    ((self >> shift) & ((1 << bits) - 1)) as u8
+                }
+
+                fn with_field(self, shift: usize, bits: usize, v: u8) -> $t {
+                    let mask: $t = ((1 << bits) - 1) << shift;
+                    (self & !mask) | ((v as $t) << shift & mask)
+                }
+            }
+        )*
+    };
+}
+
+impl_state_key!(u32, u64, u128);
+
+/// Packs grids of a fixed size and small labels (e.g. from `Grid::compact_alphabet`) into a
+/// single integer, `bits` bits per cell in row major order starting from the least
+/// significant bits.
+///
+/// Sliding block or amphipod puzzles search over states that are whole grids: keys are
+/// `Copy`, hash in a single step and take no allocation, where cloning and hashing a `Grid`
+/// per explored state dominates the search. Moves can be applied to a key directly.
+///
+/// ```
+/// use aoc_tools::{grid::Grid, packed_grid::GridKey, pair::Pair};
+/// // an 8 puzzle, 0 being the hole
+/// let codec = GridKey::<u64>::new(3, 3, 4);
+/// let start = Grid::from_vec(3, 3, vec![1, 2, 3, 4, 0, 5, 7, 8, 6]);
+/// let key = codec.encode(&start);
+/// let moved = codec.swap(key, Pair::new(1, 1), Pair::new(2, 1));
+/// assert_eq!(codec.get(moved, 2, 1), 0);
+/// assert_eq!(codec.decode(moved).row(1), &[4, 5, 0]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridKey<K: StateKey> {
+    pub len_x: usize,
+    pub len_y: usize,
+    bits: usize,
+    key: PhantomData<K>,
+}
+
+impl<K: StateKey> GridKey<K> {
+    /// # Panics
+    ///
+    /// Panics if `bits` is not in `1..=8` or if `len_x * len_y * bits` does not fit in `K`.
+    pub fn new(len_x: usize, len_y: usize, bits: usize) -> GridKey<K> {
+        assert!((1..=8).contains(&bits), "cells take from 1 to 8 bits, not {bits}");
+        assert!(
+            len_x * len_y * bits <= K::BITS,
+            "a {len_x}x{len_y} grid at {bits} bits per cell does not fit in {} bits",
+            K::BITS
+        );
+        GridKey {
+            len_x,
+            len_y,
+            bits,
+            key: PhantomData,
+        }
+    }
+
+    /// the smallest codec for `grid`, using as many bits as its largest label needs
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grid does not fit in `K`.
+    pub fn fitting(grid: &Grid<u8>) -> GridKey<K> {
+        let max = grid.iter().copied().max().unwrap_or(0);
+        GridKey::new(grid.len_x, grid.len_y, (u8::BITS - max.leading_zeros()).max(1) as usize)
+    }
+
+    /// largest label a cell can hold
+    pub fn max_label(&self) -> u8 {
+        ((1u16 << self.bits) - 1) as u8
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `grid` does not have the dimensions of the codec or a label does not fit.
+    pub fn encode(&self, grid: &Grid<u8>) -> K {
+        assert!(
+            (grid.len_x, grid.len_y) == (self.len_x, self.len_y),
+            "expected a {}x{} grid",
+            self.len_x,
+            self.len_y
+        );
+        grid.iter().enumerate().fold(K::ZERO, |key, (idx, &v)| {
+            assert!(v <= self.max_label(), "label {v} does not fit in {} bits", self.bits);
+            key.with_field(idx * self.bits, self.bits, v)
+        })
+    }
+
+    pub fn decode(&self, key: K) -> Grid<u8> {
+        let cells = (0..self.size()).map(|idx| key.field(idx * self.bits, self.bits));
+        Grid::from_vec(self.len_x, self.len_y, cells.collect())
+    }
+
+    /// returns the label at position x,y of `key`, without decoding it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn get(&self, key: K, x: usize, y: usize) -> u8 {
+        key.field(self.shift(x, y), self.bits)
+    }
+
+    /// returns `key` with the label at x,y replaced by `v`
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds or `v` does not fit.
+    pub fn set(&self, key: K, x: usize, y: usize, v: u8) -> K {
+        assert!(v <= self.max_label(), "label {v} does not fit in {} bits", self.bits);
+        key.with_field(self.shift(x, y), self.bits, v)
+    }
+
+    /// returns `key` with the labels at `a` and `b` exchanged, a sliding move
+    pub fn swap(&self, key: K, a: Pair<usize>, b: Pair<usize>) -> K {
+        let (va, vb) = (self.get(key, a.x, a.y), self.get(key, b.x, b.y));
+        self.set(self.set(key, a.x, a.y, vb), b.x, b.y, va)
+    }
+
+    /// position of the first cell of `key` labeled `v`
+    pub fn find(&self, key: K, v: u8) -> Option<Pair<usize>> {
+        (0..self.size())
+            .find(|idx| key.field(idx * self.bits, self.bits) == v)
+            .map(|idx| Pair::new(idx % self.len_x, idx / self.len_x))
+    }
+
+    /// number of cells in the grid
+    pub fn size(&self) -> usize {
+        self.len_x * self.len_y
+    }
+
+    fn shift(&self, x: usize, y: usize) -> usize {
+        assert!(x < self.len_x && y < self.len_y, "index out of bounds");
+        (self.len_x * y + x) * self.bits
+    }
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let grid = Grid::from_vec(5, 7, (0..35).map(|i| (i * 7 % 16) as u8).collect());
+        let packed = PackedGrid::<4>::from_grid(&grid);
+        assert_eq!(packed.to_grid(), grid);
+        assert_eq!(packed.row(1).collect::<Vec<_>>(), grid.row(1));
+    }
+
+    #[test]
+    fn test_set_keeps_neighbours() {
+        let mut grid = PackedGrid::<2>::new(40, 1, 3);
+        grid.set(31, 0, 0);
+        grid.set(32, 0, 1);
+        assert_eq!(grid.iter().filter(|&v| v == 3).count(), 38);
+        assert_eq!((grid.get(30, 0), grid.get(31, 0), grid.get(32, 0)), (3, 0, 1));
+    }
+
+    #[test]
+    fn test_grid_key() {
+        // 64 cells of one bit fill a u64 exactly
+        let grid = Grid::from_vec(8, 8, (0..64).map(|i| (i % 3 == 0) as u8).collect());
+        let codec = GridKey::<u64>::fitting(&grid);
+        let key = codec.encode(&grid);
+        assert_eq!(key.count_ones(), 22);
+        assert_eq!(codec.decode(key), grid);
+
+        let labels = Grid::from_vec(5, 5, (0..25).map(|i| (i * 11 % 32) as u8).collect());
+        let codec = GridKey::<u128>::fitting(&labels);
+        let key = codec.encode(&labels);
+        assert_eq!(codec.decode(key), labels);
+        assert_eq!(codec.get(key, 4, 4), *labels.get(4, 4));
+        let key = codec.set(key, 4, 4, 31);
+        assert_eq!((codec.get(key, 3, 4), codec.get(key, 4, 4)), (*labels.get(3, 4), 31));
+        assert_eq!(codec.find(key, 31), Some(Pair::new(4, 4)));
+    }
+}
@@ -1,5 +1,6 @@
 //! A helper struct for representing 2d values, i.e: coordinates, indexes, points on a
 //! grid or plane, etc.
+use crate::error::AocError;
 use std::{
     cmp,
     convert::{TryFrom, TryInto},
@@ -129,6 +130,188 @@ where
     }
 }
 
+//--------------------------------------------------------------------
+// Component-wise combinators
+//--------------------------------------------------------------------
+
+impl<U> Pair<U> {
+    /// applies `f` to both components
+    ///
+    /// ```
+    /// use aoc_tools::pair::Pair;
+    /// assert_eq!(Pair::new(-3, 4).map(i32::abs), Pair::new(3, 4));
+    /// assert_eq!(Pair::new(1, 2).map(|c| c as f64 / 2.0), Pair::new(0.5, 1.0));
+    /// ```
+    pub fn map<V, F>(self, mut f: F) -> Pair<V>
+    where
+        F: FnMut(U) -> V,
+    {
+        Pair {
+            x: f(self.x),
+            y: f(self.y),
+        }
+    }
+}
+
+impl<U: Copy> Pair<U> {
+    /// combines the components of `self` and `rhs` pairwise with `f`
+    ///
+    /// ```
+    /// use aoc_tools::pair::Pair;
+    /// let (a, b) = (Pair::new(7, 2), Pair::new(3, 5));
+    /// assert_eq!(a.zip_with(&b, |p, q| p % q), Pair::new(1, 2));
+    /// assert_eq!(a.min_components(&b), Pair::new(3, 2));
+    /// assert_eq!(a.max_components(&b), Pair::new(7, 5));
+    /// assert_eq!(Pair::new(-4, 9).clamp(&Pair::new(0, 0), &Pair::new(5, 5)), Pair::new(0, 5));
+    /// ```
+    pub fn zip_with<V, F>(&self, rhs: &Pair<U>, mut f: F) -> Pair<V>
+    where
+        F: FnMut(U, U) -> V,
+    {
+        Pair {
+            x: f(self.x, rhs.x),
+            y: f(self.y, rhs.y),
+        }
+    }
+
+    /// smallest of each component, i.e. the top left corner of the box holding both
+    /// points
+    pub fn min_components(&self, rhs: &Pair<U>) -> Pair<U>
+    where
+        U: Ord,
+    {
+        self.zip_with(rhs, cmp::min)
+    }
+
+    /// largest of each component, i.e. the bottom right corner of the box holding both
+    /// points
+    pub fn max_components(&self, rhs: &Pair<U>) -> Pair<U>
+    where
+        U: Ord,
+    {
+        self.zip_with(rhs, cmp::max)
+    }
+
+    /// restricts each component to the range given by the components of `min` and `max`
+    ///
+    /// # Panics
+    ///
+    /// Panics if a component of `min` is greater than the one of `max`.
+    pub fn clamp(&self, min: &Pair<U>, max: &Pair<U>) -> Pair<U>
+    where
+        U: Ord,
+    {
+        Pair {
+            x: self.x.clamp(min.x, max.x),
+            y: self.y.clamp(min.y, max.y),
+        }
+    }
+}
+
+//--------------------------------------------------------------------
+// Checked, wrapping and saturating arithmetic
+//--------------------------------------------------------------------
+
+/// Component-wise versions of the integer methods of the same name. The checked variants
+/// return `None` if either component overflows, which lets callers handle coordinate math
+/// near the edges (e.g. `0 - 1` on unsigned pairs) gracefully.
+macro_rules! impl_integer_ops {
+    ($($t:ty),*) => {$(
+        impl Pair<$t> {
+            pub fn checked_add(self, rhs: Self) -> Option<Self> {
+                Some(Pair::new(self.x.checked_add(rhs.x)?, self.y.checked_add(rhs.y)?))
+            }
+
+            pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+                Some(Pair::new(self.x.checked_sub(rhs.x)?, self.y.checked_sub(rhs.y)?))
+            }
+
+            /// multiplication by scalar
+            pub fn checked_mul(self, scalar: $t) -> Option<Self> {
+                Some(Pair::new(self.x.checked_mul(scalar)?, self.y.checked_mul(scalar)?))
+            }
+
+            pub fn wrapping_add(self, rhs: Self) -> Self {
+                Pair::new(self.x.wrapping_add(rhs.x), self.y.wrapping_add(rhs.y))
+            }
+
+            pub fn wrapping_sub(self, rhs: Self) -> Self {
+                Pair::new(self.x.wrapping_sub(rhs.x), self.y.wrapping_sub(rhs.y))
+            }
+
+            pub fn saturating_add(self, rhs: Self) -> Self {
+                Pair::new(self.x.saturating_add(rhs.x), self.y.saturating_add(rhs.y))
+            }
+
+            pub fn saturating_sub(self, rhs: Self) -> Self {
+                Pair::new(self.x.saturating_sub(rhs.x), self.y.saturating_sub(rhs.y))
+            }
+        }
+    )*};
+}
+
+impl_integer_ops!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Moving unsigned coordinates by signed deltas, e.g. a `Pair<usize>` position by a
+/// `Pair<isize>` direction.
+macro_rules! impl_signed_delta_ops {
+    ($($t:ty => $s:ty),*) => {$(
+        impl Pair<$t> {
+            /// returns `None` if either component would go below zero or overflow
+            ///
+            /// ```
+            /// use aoc_tools::pair::Pair;
+            #[doc = concat!("let p = Pair::<", stringify!($t), ">::new(0, 3);")]
+            /// assert_eq!(p.checked_add_signed(Pair::new(1, -1)), Some(Pair::new(1, 2)));
+            /// assert_eq!(p.checked_add_signed(Pair::new(-1, 0)), None);
+            /// ```
+            pub fn checked_add_signed(self, delta: Pair<$s>) -> Option<Self> {
+                Some(Pair::new(self.x.checked_add_signed(delta.x)?, self.y.checked_add_signed(delta.y)?))
+            }
+
+            pub fn wrapping_add_signed(self, delta: Pair<$s>) -> Self {
+                Pair::new(self.x.wrapping_add_signed(delta.x), self.y.wrapping_add_signed(delta.y))
+            }
+
+            pub fn saturating_add_signed(self, delta: Pair<$s>) -> Self {
+                Pair::new(self.x.saturating_add_signed(delta.x), self.y.saturating_add_signed(delta.y))
+            }
+        }
+    )*};
+}
+
+impl_signed_delta_ops!(u8 => i8, u16 => i16, u32 => i32, u64 => i64, u128 => i128, usize => isize);
+
+/// Batch operations over slices of pairs.
+///
+/// Written as plain loops over the slice so the compiler is free to vectorize them, which
+/// matters when thousands of points (particles, moons) are updated per tick.
+impl<U> Pair<U>
+where
+    U: AddAssign + Copy,
+{
+    /// adds `delta` to every pair of `pairs`
+    pub fn add_slices(pairs: &mut [Pair<U>], delta: Pair<U>) {
+        for p in pairs.iter_mut() {
+            p.x += delta.x;
+            p.y += delta.y;
+        }
+    }
+
+    /// adds `deltas[i]` to `pairs[i]`, e.g. velocities to positions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slices have different lengths.
+    pub fn add_zipped(pairs: &mut [Pair<U>], deltas: &[Pair<U>]) {
+        assert_eq!(pairs.len(), deltas.len(), "slices must have the same length");
+        for (p, d) in pairs.iter_mut().zip(deltas) {
+            p.x += d.x;
+            p.y += d.y;
+        }
+    }
+}
+
 //--------------------------------------------------------------------
 // Conversion traits
 //--------------------------------------------------------------------
@@ -144,12 +327,13 @@ where
     U: TryInto<T>,
     <U as TryInto<T>>::Error: std::error::Error + 'static,
 {
-    type Error = Box<dyn Error>;
+    type Error = AocError;
 
     fn try_from(item: (U, U)) -> Result<Self, Self::Error> {
+        let convert = |u: U| u.try_into().map_err(|e| AocError::Conversion(e.to_string()));
         Ok(Pair {
-            x: item.0.try_into()?,
-            y: item.1.try_into()?,
+            x: convert(item.0)?,
+            y: convert(item.1)?,
         })
     }
 }
@@ -159,20 +343,111 @@ where
     U: FromStr + Copy,
     <U as FromStr>::Err: std::error::Error + 'static,
 {
-    type Err = Box<dyn Error>;
+    type Err = AocError;
 
     /// string needs to have two values separated by comma (','), no blank space allowed.
     /// Examples: "15,21" or "A,B".
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s.split(',');
-        let x = iter
-            .next()
-            .ok_or("Could not parse the number before the comma.")?
-            .parse::<U>()?;
-        let y = iter
-            .next()
-            .ok_or("Could not parse the number after the comma.")?
-            .parse::<U>()?;
-        Ok(Pair::new(x, y))
+        parse_pair(s).map_err(|e| AocError::parse(None, s, e))
+    }
+}
+
+fn parse_pair<U>(s: &str) -> Result<Pair<U>, Box<dyn Error>>
+where
+    U: FromStr + Copy,
+    <U as FromStr>::Err: std::error::Error + 'static,
+{
+    let mut iter = s.split(',');
+    let x = iter
+        .next()
+        .ok_or("Could not parse the number before the comma.")?
+        .parse::<U>()?;
+    let y = iter
+        .next()
+        .ok_or("Could not parse the number after the comma.")?
+        .parse::<U>()?;
+    Ok(Pair::new(x, y))
+}
+
+impl<U> From<[U; 2]> for Pair<U> {
+    fn from([x, y]: [U; 2]) -> Self {
+        Pair { x, y }
     }
 }
+
+impl<U> From<Pair<U>> for [U; 2] {
+    fn from(p: Pair<U>) -> Self {
+        [p.x, p.y]
+    }
+}
+
+//--------------------------------------------------------------------
+// Casts
+//--------------------------------------------------------------------
+
+impl<U: Copy> Pair<U> {
+    /// converts both components with `TryInto`, e.g. `Pair<usize>` to `Pair<i64>`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a component does not fit in `V`.
+    ///
+    /// ```
+    /// use aoc_tools::pair::Pair;
+    /// let p: Pair<i64> = Pair::new(3usize, 4).cast();
+    /// assert_eq!(p, Pair::new(3i64, 4));
+    /// ```
+    #[track_caller]
+    pub fn cast<V>(self) -> Pair<V>
+    where
+        U: TryInto<V> + Debug,
+    {
+        let convert = |u: U| {
+            u.try_into()
+                .unwrap_or_else(|_| panic!("{u:?} does not fit in the target type"))
+        };
+        Pair {
+            x: convert(self.x),
+            y: convert(self.y),
+        }
+    }
+
+    /// converts both components with `as`, which truncates, wraps or saturates instead of
+    /// failing.
+    ///
+    /// ```
+    /// use aoc_tools::pair::Pair;
+    /// assert_eq!(Pair::new(-1i64, 300).cast_lossy::<u8>(), Pair::new(255u8, 44));
+    /// assert_eq!(Pair::new(2.7f64, -1.5).cast_lossy::<i32>(), Pair::new(2, -1));
+    /// ```
+    pub fn cast_lossy<V>(self) -> Pair<V>
+    where
+        U: LossyCast<V>,
+    {
+        Pair {
+            x: self.x.lossy_cast(),
+            y: self.y.lossy_cast(),
+        }
+    }
+}
+
+/// Conversion with the semantics of the `as` operator between primitive numbers.
+pub trait LossyCast<V> {
+    fn lossy_cast(self) -> V;
+}
+
+macro_rules! impl_lossy_cast {
+    ($($from:ty),*) => {$(
+        impl_lossy_cast!(@to $from; u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+    )*};
+    (@to $from:ty; $($to:ty),*) => {$(
+        impl LossyCast<$to> for $from {
+            #[inline]
+            fn lossy_cast(self) -> $to {
+                self as $to
+            }
+        }
+    )*};
+}
+
+impl_lossy_cast!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
@@ -1,21 +1,208 @@
 //! Simple helpers to parse strings into `Vector`s or `Iterator`s
-use std::str::FromStr;
+use crate::{
+    direction::Direction,
+    error::{AocError, AocResult},
+    hash::FastMap,
+    runner::{span, spanned},
+};
+use std::{
+    fmt::Display,
+    hash::Hash,
+    iter::Peekable,
+    str::{Chars, FromStr},
+};
 
 //------------------------------
 // Vectors
 //------------------------------
 
-/// parses an `input` where each line is an entry into a `Vec`.
+/// parses an `input` where each line is an entry into a `Vec`. Parse failures are skipped.
 pub fn lines_into_vec<T: FromStr>(text: &str) -> Vec<T> {
-    text.lines().flat_map(str::parse::<T>).collect()
+    let _span = span("parse");
+    text.lines().flat_map(parse_piece::<T>).collect()
 }
 
 /// parses an `input` into a `Vec<T>`. Entries in the string slice are separated by the `split_at` characters.
+/// Parse failures are skipped.
 pub fn split_into_vec<T>(input: &str, split_at: &str) -> Vec<T>
 where
     T: FromStr,
 {
-    input.split(split_at).flat_map(str::parse::<T>).collect()
+    let _span = span("parse");
+    input.split(split_at).flat_map(parse_piece::<T>).collect()
+}
+
+/// like `lines_into_vec`, with failures handled according to `mode`
+pub fn lines_into_vec_with<T>(text: &str, mode: ParseMode) -> AocResult<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    parse_all(text.lines(), mode)
+}
+
+/// like `split_into_vec`, with failures handled according to `mode`
+pub fn split_into_vec_with<T>(input: &str, split_at: &str, mode: ParseMode) -> AocResult<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    parse_all(input.split(split_at), mode)
+}
+
+/// How `transpose_lines` handles lines shorter than the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ragged {
+    /// pads short lines with the given char up to the length of the longest line
+    Pad(char),
+    /// truncates every line to the length of the shortest line
+    Truncate,
+}
+
+/// reads a block of lines and returns its columns as strings, the first string being the
+/// leftmost column read from top to bottom. Lines of different lengths are handled according
+/// to `ragged`, e.g. `Ragged::Pad(' ')` for crate-stack diagrams whose trailing spaces were
+/// trimmed.
+///
+/// ```
+/// use aoc_tools::parse::{transpose_lines, Ragged};
+/// let cols = transpose_lines("ab\ncde\n", Ragged::Pad('.'));
+/// assert_eq!(cols, vec!["ac", "bd", ".e"]);
+/// assert_eq!(transpose_lines("ab\ncde\n", Ragged::Truncate), vec!["ac", "bd"]);
+/// ```
+pub fn transpose_lines(input: &str, ragged: Ragged) -> Vec<String> {
+    let lines: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
+    let lengths = lines.iter().map(Vec::len);
+    let width = match ragged {
+        Ragged::Pad(_) => lengths.max(),
+        Ragged::Truncate => lengths.min(),
+    }
+    .unwrap_or(0);
+    let pad = match ragged {
+        Ragged::Pad(c) => c,
+        Ragged::Truncate => ' ',
+    };
+
+    (0..width)
+        .map(|x| lines.iter().map(|l| l.get(x).copied().unwrap_or(pad)).collect())
+        .collect()
+}
+
+/// splits the non empty lines of `input` into those for which `pred` is true and the
+/// others, keeping their order. Separates inputs mixing two kinds of lines, e.g. page
+/// ordering rules and updates, before parsing each kind.
+///
+/// ```
+/// use aoc_tools::parse::partition_lines;
+/// let input = "47|53\n97|13\n\n75,47,61\n97,61\n";
+/// let (rules, updates) = partition_lines(input, |l| l.contains('|'));
+/// assert_eq!(rules, vec!["47|53", "97|13"]);
+/// assert_eq!(updates, vec!["75,47,61", "97,61"]);
+/// ```
+pub fn partition_lines<F>(input: &str, mut pred: F) -> (Vec<&str>, Vec<&str>)
+where
+    F: FnMut(&str) -> bool,
+{
+    input.lines().filter(|l| !l.is_empty()).partition(|l| pred(l))
+}
+
+/// groups the non empty lines of `input` by the kind `classify` gives them, keeping the
+/// order of the lines within each kind.
+///
+/// ```
+/// use aoc_tools::parse::classify_lines;
+/// let input = "px{a<2006:qkq,rfg}\n\n{x=787,m=2655}\n# comment\n";
+/// let kinds = classify_lines(input, |l| l.chars().next());
+/// assert_eq!(kinds[&Some('{')], vec!["{x=787,m=2655}"]);
+/// assert_eq!(kinds.len(), 3);
+/// ```
+pub fn classify_lines<K, F>(input: &str, mut classify: F) -> FastMap<K, Vec<&str>>
+where
+    K: Hash + Eq,
+    F: FnMut(&str) -> K,
+{
+    let mut kinds: FastMap<K, Vec<&str>> = FastMap::default();
+    for line in input.lines().filter(|l| !l.is_empty()) {
+        kinds.entry(classify(line)).or_default().push(line);
+    }
+    kinds
+}
+
+//------------------------------
+// Strictness
+//------------------------------
+
+/// Tells the parsing helpers taking a mode (the `*_with` functions of `parse` and `input`)
+/// what to do with the pieces that fail to parse. The helpers without a mode are lenient.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// skip them, as the helpers without a mode do
+    #[default]
+    Lenient,
+    /// stop at the first one and report it
+    Strict,
+}
+
+/// parses every piece into a `T`. Failures are skipped or reported according to `mode`, the
+/// error telling the number of the piece (starting at 1).
+///
+/// ```
+/// use aoc_tools::parse::{parse_all, ParseMode};
+/// let lenient: Vec<u32> = parse_all("1,x,3".split(','), ParseMode::Lenient).unwrap();
+/// assert_eq!(lenient, vec![1, 3]);
+/// let err = parse_all::<u32, _>("1,x,3".split(','), ParseMode::Strict).unwrap_err();
+/// assert_eq!(err.to_string(), "line 2: invalid digit found in string (\"x\")");
+/// ```
+pub fn parse_all<'a, T, I>(pieces: I, mode: ParseMode) -> AocResult<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+    I: IntoIterator<Item = &'a str>,
+{
+    parse_each(pieces, mode).collect()
+}
+
+/// returns an iterator parsing each piece into a `T`, the path shared by the helpers taking
+/// a mode. `ParseMode::Lenient` skips the failures, `ParseMode::Strict` yields them, telling
+/// the number of the piece (starting at 1).
+///
+/// ```
+/// use aoc_tools::parse::{parse_each, ParseMode};
+/// let parsed: Vec<_> = parse_each::<u32, _, _>(["1", "x", "3"], ParseMode::Strict).collect();
+/// assert!(parsed[1].is_err());
+/// assert_eq!(parse_each::<u32, _, _>(["1", "x", "3"], ParseMode::Lenient).count(), 2);
+/// ```
+pub fn parse_each<T, I, S>(pieces: I, mode: ParseMode) -> impl Iterator<Item = AocResult<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let parsed = pieces.into_iter().enumerate().filter_map(move |(i, s)| {
+        let s = s.as_ref();
+        match mode {
+            ParseMode::Lenient => parse_piece(s).ok().map(Ok),
+            ParseMode::Strict => Some(parse_at(i + 1, s)),
+        }
+    });
+    spanned("parse", parsed)
+}
+
+/// parses `s`, found at line (or piece) `line`, into an `AocError` on failure
+pub(crate) fn parse_at<T>(line: usize, s: &str) -> AocResult<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    parse_piece(s).map_err(|e| AocError::parse(Some(line), s, e))
+}
+
+/// parses a single piece. The helpers calling it time their whole work in the "parse"
+/// span, so it is not timed itself.
+#[inline]
+pub(crate) fn parse_piece<T: FromStr>(s: &str) -> Result<T, T::Err> {
+    s.parse()
 }
 
 //------------------------------
@@ -23,11 +210,21 @@ where
 //------------------------------
 
 /// returns an iterator over parsed values of an `input` string slice where the entries are separated by a new line.
+/// Parse failures are skipped, see `lines_with` to report them.
 pub fn lines<'a, T>(input: &'a str) -> impl Iterator<Item = T> + 'a
 where
     T: FromStr + 'a,
 {
-    input.lines().flat_map(str::parse::<T>)
+    spanned("parse", input.lines().flat_map(parse_piece::<T>))
+}
+
+/// like `lines`, with failures handled according to `mode`
+pub fn lines_with<'a, T>(input: &'a str, mode: ParseMode) -> impl Iterator<Item = AocResult<T>> + 'a
+where
+    T: FromStr + 'a,
+    T::Err: Display,
+{
+    parse_each(input.lines(), mode)
 }
 
 /// returns an iterator over parsed values of an `input` string slice where the entries are separated by a new line.
@@ -37,14 +234,571 @@ pub fn lines_cloneable<'a, T>(input: &'a str) -> impl Iterator<Item = T> + Clone
 where
     T: FromStr + Clone + 'a,
 {
-    input.lines().map(str::parse::<T>).flatten()
+    input.lines().map(parse_piece::<T>).flatten()
 }
 
 /// returns an iterator over parsed values of an `input` string where the entries are separated by the `split_at`
-/// characters.
+/// characters. Parse failures are skipped, see `split_with` to report them.
 pub fn split<'a, T>(input: &'a str, split_at: &'a str) -> impl Iterator<Item = T> + 'a
 where
     T: FromStr + 'a,
 {
-    input.split(split_at).flat_map(str::parse::<T>)
+    spanned("parse", input.split(split_at).flat_map(parse_piece::<T>))
+}
+
+/// like `split`, with failures handled according to `mode`
+pub fn split_with<'a, T>(input: &'a str, split_at: &'a str, mode: ParseMode) -> impl Iterator<Item = AocResult<T>> + 'a
+where
+    T: FromStr + 'a,
+    T::Err: Display,
+{
+    parse_each(input.split(split_at), mode)
+}
+
+/// returns an iterator over lines parsed into a pair `(A, B)`. Each line is split at the first
+/// occurrence of `sep`, e.g. "R 4" -> ('R', 4) or "forward 5" -> ("forward", 5) with `sep = " "`.
+///
+/// Lines without `sep` or where either side fails to parse are skipped, see `line_pairs_with`
+/// to report them.
+pub fn line_pairs<'a, A, B>(input: &'a str, sep: &'a str) -> impl Iterator<Item = (A, B)> + 'a
+where
+    A: FromStr + 'a,
+    B: FromStr + 'a,
+{
+    let pairs = input.lines().flat_map(move |line| {
+        let (a, b) = line.split_once(sep)?;
+        Some((parse_piece(a).ok()?, parse_piece(b).ok()?))
+    });
+    spanned("parse", pairs)
+}
+
+/// like `line_pairs`, with the lines without `sep` or failing to parse handled according
+/// to `mode`.
+///
+/// ```
+/// use aoc_tools::parse::{line_pairs_with, ParseMode};
+/// let pairs: Result<Vec<(char, u32)>, _> = line_pairs_with("R 4\nU x", " ", ParseMode::Strict).collect();
+/// assert_eq!(pairs.unwrap_err().to_string(), "line 2: invalid digit found in string (\"x\")");
+/// ```
+pub fn line_pairs_with<'a, A, B>(
+    input: &'a str,
+    sep: &'a str,
+    mode: ParseMode,
+) -> impl Iterator<Item = AocResult<(A, B)>> + 'a
+where
+    A: FromStr + 'a,
+    B: FromStr + 'a,
+    A::Err: Display,
+    B::Err: Display,
+{
+    let pairs = input.lines().enumerate().filter_map(move |(i, line)| match mode {
+        ParseMode::Lenient => {
+            let (a, b) = line.split_once(sep)?;
+            Some(Ok((parse_piece(a).ok()?, parse_piece(b).ok()?)))
+        }
+        ParseMode::Strict => Some(match line.split_once(sep) {
+            Some((a, b)) => parse_at(i + 1, a).and_then(|a| Ok((a, parse_at(i + 1, b)?))),
+            None => Err(AocError::parse(Some(i + 1), line, format!("missing separator '{sep}'"))),
+        }),
+    });
+    spanned("parse", pairs)
+}
+
+/// returns an iterator over parsed values of an `input` string slice split at any of the
+/// `delims` characters. Empty pieces (e.g. between two consecutive delimiters) and parse
+/// failures are skipped, see `split_any_with` to report the failures.
+///
+/// ```
+/// use aoc_tools::parse::split_any;
+/// let pieces: Vec<String> = split_any("3-5 m: mmmmm", &['-', ' ', ':']).collect();
+/// assert_eq!(pieces, vec!["3", "5", "m", "mmmmm"]);
+/// let numbers: Vec<u32> = split_any("3-5 m: mmmmm", &['-', ' ', ':']).collect();
+/// assert_eq!(numbers, vec![3, 5]);
+/// ```
+pub fn split_any<'a, T>(input: &'a str, delims: &'a [char]) -> impl Iterator<Item = T> + 'a
+where
+    T: FromStr + 'a,
+{
+    spanned(
+        "parse",
+        input.split(delims).filter(|s| !s.is_empty()).flat_map(parse_piece::<T>),
+    )
+}
+
+/// like `split_any`, with failures handled according to `mode`. Empty pieces are still
+/// skipped and not counted in the numbers of the errors.
+pub fn split_any_with<'a, T>(
+    input: &'a str,
+    delims: &'a [char],
+    mode: ParseMode,
+) -> impl Iterator<Item = AocResult<T>> + 'a
+where
+    T: FromStr + 'a,
+    T::Err: Display,
+{
+    parse_each(input.split(delims).filter(|s| !s.is_empty()), mode)
+}
+
+//------------------------------
+// Quoted fields
+//------------------------------
+
+/// splits a single record at `delim`, CSV style, except where the delimiter is:
+/// - inside double quotes. The quotes are removed and `""` inside them stands for a quote;
+/// - escaped by a backslash, which is removed;
+/// - nested in brackets (`()`, `[]` or `{}`), which are kept.
+///
+/// Fields are not trimmed. Newlines are part of the fields, see `records` to split several
+/// lines into records.
+///
+/// ```
+/// use aoc_tools::parse::split_fields;
+/// let fields = split_fields(r#"a,"b,c",[1,[2,3]],d\,e"#, ',');
+/// assert_eq!(fields, vec!["a", "b,c", "[1,[2,3]]", "d,e"]);
+/// assert_eq!(split_fields(r#""say ""hi""",,"#, ','), vec![r#"say "hi""#, "", ""]);
+/// ```
+pub fn split_fields(record: &str, delim: char) -> Vec<String> {
+    let mut chars = record.chars().peekable();
+    next_record(&mut chars, delim, false).unwrap_or_default()
+}
+
+/// returns an iterator over the records of `input`, one per line, each split into fields
+/// with the rules of `split_fields`. A newline inside quotes does not end the record, and
+/// empty lines are skipped.
+///
+/// ```
+/// use aoc_tools::parse::records;
+/// let input = "name,notes\nelf,\"likes\nsnow, mostly\"\n";
+/// let rows: Vec<Vec<String>> = records(input, ',').collect();
+/// assert_eq!(rows.len(), 2);
+/// assert_eq!(rows[1], vec!["elf", "likes\nsnow, mostly"]);
+/// // a blank line is skipped, while `""` is a record of one empty field
+/// let rows: Vec<Vec<String>> = records("a\n\n\"\"\r\n", ',').collect();
+/// assert_eq!(rows, vec![vec!["a"], vec![""]]);
+/// ```
+pub fn records(input: &str, delim: char) -> impl Iterator<Item = Vec<String>> + '_ {
+    let mut chars = input.chars().peekable();
+    std::iter::from_fn(move || {
+        while chars.next_if(|&c| c == '\n' || c == '\r').is_some() {}
+        next_record(&mut chars, delim, true)
+    })
+}
+
+/// consumes the chars of one record, stopping after an unquoted newline if
+/// `stop_at_newline`. Returns `None` once `chars` is exhausted.
+fn next_record(chars: &mut Peekable<Chars>, delim: char, stop_at_newline: bool) -> Option<Vec<String>> {
+    chars.peek()?;
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let (mut quoted, mut depth) = (false, 0usize);
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => field.extend(chars.next()),
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            _ if quoted => field.push(c),
+            '(' | '[' | '{' => {
+                depth += 1;
+                field.push(c);
+            }
+            ')' | ']' | '}' => {
+                depth = depth.saturating_sub(1);
+                field.push(c);
+            }
+            _ if c == delim && depth == 0 => fields.push(std::mem::take(&mut field)),
+            '\n' if stop_at_newline => break,
+            _ => field.push(c),
+        }
+    }
+    if stop_at_newline && field.ends_with('\r') {
+        field.pop();
+    }
+    fields.push(field);
+    Some(fields)
+}
+
+//------------------------------
+// Patterns
+//------------------------------
+
+/// Values captured by `match_line`, by placeholder name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Captures<'a> {
+    captures: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Captures<'a> {
+    /// text captured by the placeholder `name`
+    pub fn get(&self, name: &str) -> Option<&'a str> {
+        self.captures.iter().find(|(n, _)| *n == name).map(|&(_, v)| v)
+    }
+
+    /// parses the text captured by the placeholder `name`. Fails with an `AocError::Parse`
+    /// if there is no such placeholder or if the text does not parse.
+    pub fn parse<T>(&self, name: &str) -> AocResult<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = self
+            .get(name)
+            .ok_or_else(|| AocError::parse(None, name, "no such placeholder"))?;
+        parse_piece(value).map_err(|e| AocError::parse(None, value, e))
+    }
+
+    /// `(name, text)` of each placeholder, in the order of the pattern
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a str)> + '_ {
+        self.captures.iter().copied()
+    }
+}
+
+#[derive(Debug)]
+enum Segment<'a> {
+    Literal(String),
+    Placeholder(&'a str),
+}
+
+/// matches a whole `line` against `pattern`, where each `{name}` placeholder captures at
+/// least one char (`{{` and `}}` stand for literal braces). Returns `None` if the line does
+/// not match.
+///
+/// Placeholders capture as little as possible, backtracking when the rest of the pattern
+/// does not match, so "{a}-{b}" splits "1-2-3" into "1" and "2-3".
+///
+/// # Panics
+///
+/// Panics if a placeholder is not closed.
+///
+/// ```
+/// use aoc_tools::parse::match_line;
+/// let pattern = "turn {action} {x0},{y0} through {x1},{y1}";
+/// let m = match_line("turn off 499,0 through 500,999", pattern).unwrap();
+/// assert_eq!(m.get("action"), Some("off"));
+/// assert_eq!(m.parse::<u32>("y1").unwrap(), 999);
+/// assert!(m.parse::<u32>("action").is_err());
+/// assert!(match_line("toggle 1,2 through 3,4", pattern).is_none());
+/// let m = match_line("1-2-3", "{a}-{b}").unwrap();
+/// assert_eq!(m.iter().collect::<Vec<_>>(), vec![("a", "1"), ("b", "2-3")]);
+/// ```
+pub fn match_line<'a>(line: &'a str, pattern: &'a str) -> Option<Captures<'a>> {
+    let segments = pattern_segments(pattern);
+    let mut captures = Vec::new();
+    match_segments(&segments, line, &mut captures).then_some(Captures { captures })
+}
+
+fn pattern_segments(pattern: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut rest = pattern;
+    while let Some(c) = rest.chars().next() {
+        if let Some(r) = rest.strip_prefix("{{").or_else(|| rest.strip_prefix("}}")) {
+            literal.push(c);
+            rest = r;
+        } else if c == '{' {
+            let end = rest.find('}').expect("unclosed placeholder in pattern");
+            if !literal.is_empty() {
+                segments.push(Segment::Literal(std::mem::take(&mut literal)));
+            }
+            segments.push(Segment::Placeholder(&rest[1..end]));
+            rest = &rest[end + 1..];
+        } else {
+            literal.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    segments
+}
+
+fn match_segments<'a>(segments: &[Segment<'a>], line: &'a str, captures: &mut Vec<(&'a str, &'a str)>) -> bool {
+    match segments {
+        [] => line.is_empty(),
+        [Segment::Literal(lit), rest @ ..] => line
+            .strip_prefix(lit.as_str())
+            .is_some_and(|line| match_segments(rest, line, captures)),
+        [Segment::Placeholder(name), rest @ ..] => {
+            let ends = line.char_indices().skip(1).map(|(i, _)| i);
+            for end in ends.chain((!line.is_empty()).then_some(line.len())) {
+                captures.push((name, &line[..end]));
+                if match_segments(rest, &line[end..], captures) {
+                    return true;
+                }
+                captures.pop();
+            }
+            false
+        }
+    }
+}
+
+/// extracts the `(from, to)` pair of each line of `input` matching `pattern`, which must
+/// have `{from}` and `{to}` placeholders (other placeholders are matched and ignored). Lines
+/// that do not match are skipped.
+///
+/// Use `graph::adjacency_from_edges` to turn the pairs into a graph.
+///
+/// # Panics
+///
+/// Panics if `pattern` lacks the `{from}` or `{to}` placeholder.
+///
+/// ```
+/// use aoc_tools::{graph::adjacency_from_edges, parse::edges};
+/// let rules = "Step C must be finished before step A can begin.
+/// Step C must be finished before step F can begin.
+/// Step A must be finished before step B can begin.";
+/// let pairs = edges(rules, "Step {from} must be finished before step {to} can begin.");
+/// assert_eq!(pairs[0], ("C".to_string(), "A".to_string()));
+/// let (names, adjacency) = adjacency_from_edges(&pairs);
+/// assert_eq!(names, ["A", "B", "C", "F"]);
+/// assert_eq!(adjacency[2], vec![0, 3]);
+/// ```
+pub fn edges(input: &str, pattern: &str) -> Vec<(String, String)> {
+    let names: Vec<_> = pattern_segments(pattern)
+        .into_iter()
+        .filter_map(|seg| match seg {
+            Segment::Placeholder(name) => Some(name),
+            Segment::Literal(_) => None,
+        })
+        .collect();
+    assert!(
+        names.contains(&"from") && names.contains(&"to"),
+        "pattern needs {{from}} and {{to}} placeholders"
+    );
+    input
+        .lines()
+        .filter_map(|line| match_line(line, pattern))
+        .map(|m| (m.get("from").unwrap().to_string(), m.get("to").unwrap().to_string()))
+        .collect()
+}
+
+//------------------------------
+// Moves
+//------------------------------
+
+/// How the direction of each move is spelled in `moves_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveStyle {
+    /// a single char followed by the amount, with or without a space: "R8", "U 5", "^3".
+    /// See `Direction::from_char` for the accepted chars.
+    Letter,
+    /// a word and the amount separated by spaces: "up 3", "left 2", or the submarine course
+    /// "forward 5" / "down 3" ("forward" is `Right`, "back" is `Left`).
+    Word,
+    /// either of the above, decided for each move
+    Any,
+}
+
+/// returns an iterator over the moves of a path, as `(direction, amount)`. Moves are
+/// separated by commas or new lines, e.g. "R8,U5,L5,D3" or "forward 5\ndown 3", and their
+/// style is detected for each move. Moves that fail to parse are skipped.
+///
+/// ```
+/// use aoc_tools::{direction::Direction, parse::moves};
+/// let wire: Vec<_> = moves("R8,U5").collect();
+/// assert_eq!(wire, vec![(Direction::Right, 8), (Direction::Up, 5)]);
+/// let course: Vec<_> = moves("forward 5\ndown 3\n").collect();
+/// assert_eq!(course, vec![(Direction::Right, 5), (Direction::Down, 3)]);
+/// ```
+pub fn moves(input: &str) -> impl Iterator<Item = (Direction, u32)> + '_ {
+    moves_with(input, MoveStyle::Any, ParseMode::Lenient).flatten()
+}
+
+/// same as `moves`, accepting only the given `style`, with the moves that fail to parse
+/// handled according to `mode`. Errors tell the number of the move, starting at 1.
+///
+/// ```
+/// use aoc_tools::parse::{moves_with, MoveStyle, ParseMode};
+/// let err = moves_with("R8,up 5", MoveStyle::Letter, ParseMode::Strict).nth(1).unwrap();
+/// assert_eq!(err.unwrap_err().to_string(), "line 2: not a valid move (\"up 5\")");
+/// assert_eq!(moves_with("R8,up 5", MoveStyle::Letter, ParseMode::Lenient).count(), 1);
+/// ```
+pub fn moves_with(
+    input: &str,
+    style: MoveStyle,
+    mode: ParseMode,
+) -> impl Iterator<Item = AocResult<(Direction, u32)>> + '_ {
+    let steps = input
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .enumerate()
+        .filter_map(move |(i, token)| match (parse_move(token, style), mode) {
+            (Some(step), _) => Some(Ok(step)),
+            (None, ParseMode::Lenient) => None,
+            (None, ParseMode::Strict) => Some(Err(AocError::parse(Some(i + 1), token, "not a valid move"))),
+        });
+    spanned("parse", steps)
+}
+
+fn parse_move(token: &str, style: MoveStyle) -> Option<(Direction, u32)> {
+    let word = || {
+        let (word, amount) = token.split_once(char::is_whitespace)?;
+        let dir = match word.to_ascii_lowercase().as_str() {
+            "up" | "north" => Direction::Up,
+            "right" | "east" | "forward" => Direction::Right,
+            "down" | "south" => Direction::Down,
+            "left" | "west" | "back" => Direction::Left,
+            _ => return None,
+        };
+        Some((dir, parse_piece(amount.trim()).ok()?))
+    };
+    let letter = || {
+        let c = token.chars().next()?;
+        let dir = Direction::from_char(c)?;
+        Some((dir, parse_piece(token[c.len_utf8()..].trim()).ok()?))
+    };
+    match style {
+        MoveStyle::Letter => letter(),
+        MoveStyle::Word => word(),
+        MoveStyle::Any => letter().or_else(word),
+    }
+}
+
+//------------------------------
+// Blocks
+//------------------------------
+
+/// The lines of a block matched by `blocks_to_struct`, with their prefixes stripped.
+#[derive(Debug, Clone, Copy)]
+pub struct Fields<'a, const N: usize> {
+    lines: [&'a str; N],
+    rest: [&'a str; N],
+    /// number of each line in the block, starting at 1
+    numbers: [usize; N],
+}
+
+impl<'a, const N: usize> Fields<'a, N> {
+    /// the `i`-th line past its prefix, trimmed
+    pub fn get(&self, i: usize) -> &'a str {
+        self.rest[i]
+    }
+
+    /// parses the `i`-th field
+    pub fn parse<U: FromStr>(&self, i: usize) -> AocResult<U> {
+        parse_piece(self.rest[i]).map_err(|_| self.error(i, &format!("cannot parse \"{}\"", self.rest[i])))
+    }
+
+    /// parses the `i`-th field as a list of values separated by `sep`
+    pub fn list<U: FromStr>(&self, i: usize, sep: &str) -> AocResult<Vec<U>> {
+        self.rest[i]
+            .split(sep)
+            .map(|s| {
+                parse_piece(s.trim()).map_err(|_| self.error(i, &format!("cannot parse list item \"{}\"", s.trim())))
+            })
+            .collect()
+    }
+
+    /// builds an error pointing at the `i`-th line, for validations done by the builder
+    pub fn error(&self, i: usize, reason: &str) -> AocError {
+        AocError::parse(Some(self.numbers[i]), self.lines[i], reason)
+    }
+}
+
+/// parses a block of exactly `N` lines into a `T`. Each line, once trimmed, must start with
+/// the matching entry of `prefixes`. The rest of the lines are handed to `build`, which
+/// extracts the typed values through `Fields`.
+///
+/// Errors are `AocError::Parse` errors pointing at the first line with the wrong prefix, a
+/// missing or extra line, or the field `build` failed to parse. Lines are numbered from 1,
+/// blank lines included.
+///
+/// ```
+/// use aoc_tools::{error::{AocError, AocResult}, parse::{blocks_to_struct, Fields}};
+/// struct Monkey {
+///     items: Vec<u64>,
+///     divisor: u64,
+///     targets: (usize, usize),
+/// }
+/// let block = "\
+/// Monkey 0:
+///   Starting items: 79, 98
+///   Test: divisible by 23
+///     If true: throw to monkey 2
+///     If false: throw to monkey 3";
+/// let prefixes = ["Monkey", "Starting items:", "Test: divisible by", "If true: throw to monkey", "If false: throw to monkey"];
+/// let build = |f: &Fields<5>| -> AocResult<Monkey> {
+///     Ok(Monkey { items: f.list(1, ",")?, divisor: f.parse(2)?, targets: (f.parse(3)?, f.parse(4)?) })
+/// };
+/// let monkey = blocks_to_struct(block, prefixes, build).unwrap();
+/// assert_eq!((monkey.items, monkey.divisor, monkey.targets), (vec![79, 98], 23, (2, 3)));
+///
+/// let err = blocks_to_struct(&block.replace("23", "x"), prefixes, build).err().unwrap();
+/// assert!(matches!(err, AocError::Parse { line: Some(3), .. }));
+/// ```
+pub fn blocks_to_struct<'a, T, F, const N: usize>(block: &'a str, prefixes: [&str; N], build: F) -> AocResult<T>
+where
+    F: FnOnce(&Fields<'a, N>) -> AocResult<T>,
+{
+    let _span = span("parse");
+    let mut lines = block
+        .lines()
+        .enumerate()
+        .map(|(n, l)| (n + 1, l))
+        .filter(|(_, l)| !l.trim().is_empty());
+    let mut fields = Fields {
+        lines: [""; N],
+        rest: [""; N],
+        numbers: [0; N],
+    };
+    let mut last = 0;
+    for (i, prefix) in prefixes.iter().enumerate() {
+        let (n, line) = lines
+            .next()
+            .ok_or_else(|| AocError::parse(Some(last + 1), "", format!("missing line, expected \"{prefix}\"")))?;
+        let rest = line
+            .trim()
+            .strip_prefix(prefix)
+            .ok_or_else(|| AocError::parse(Some(n), line, format!("expected prefix \"{prefix}\"")))?;
+        fields.lines[i] = line;
+        fields.rest[i] = rest.trim();
+        fields.numbers[i] = n;
+        last = n;
+    }
+    if let Some((n, extra)) = lines.next() {
+        return Err(AocError::parse(Some(n), extra, "unexpected line"));
+    }
+    build(&fields)
+}
+
+/// parses every block of `input` (separated by empty lines) with `blocks_to_struct`,
+/// stopping at the first error. The lines of the errors are numbered within the whole
+/// `input`.
+///
+/// ```
+/// use aoc_tools::parse::blocks_to_structs;
+/// let input = "a: 1\nb: 2\n\na: 3\nb: x";
+/// let err = blocks_to_structs(input, ["a:", "b:"], |f| Ok((f.parse::<u32>(0)?, f.parse::<u32>(1)?))).unwrap_err();
+/// assert_eq!(err.to_string(), "line 5: cannot parse \"x\" (\"b: x\")");
+/// ```
+pub fn blocks_to_structs<'a, T, F, const N: usize>(
+    input: &'a str,
+    prefixes: [&str; N],
+    mut build: F,
+) -> AocResult<Vec<T>>
+where
+    F: FnMut(&Fields<'a, N>) -> AocResult<T>,
+{
+    let mut offset = 0;
+    input
+        .split("\n\n")
+        .map(|b| {
+            // lines before the block: those of the previous blocks and their separators
+            let before = offset;
+            offset += b.matches('\n').count() + 2;
+            (before, b)
+        })
+        .filter(|(_, b)| !b.trim().is_empty())
+        .map(|(before, b)| {
+            blocks_to_struct(b, prefixes, &mut build).map_err(|mut e| {
+                if let AocError::Parse { line: Some(line), .. } = &mut e {
+                    *line += before;
+                }
+                e
+            })
+        })
+        .collect()
 }
@@ -0,0 +1,425 @@
+//! Shortest path searches returning the path itself, not only its cost.
+//!
+//! - `dijkstra` works over any implicit graph: states of type `N` and a `successors`
+//!   closure returning the neighbouring states with the cost to move into them;
+//! - `grid_dijkstra` is the common special case of moving across the 4 orthogonal
+//!   neighbours of a `Grid` cell.
+//!
+//! `dijkstra_with` bounds the search with `SearchLimits` (maximum cost, number of
+//! expansions) and reports each state expanded, e.g. to collect every state within a cost.
+//!
+//! Both return a `PathResult`, which keeps the predecessor map of the search so the best
+//! path may be reconstructed.
+//!
+//! ```
+//! use aoc_tools::{grid::Grid, pair::Pair, pathfinding::grid_dijkstra};
+//! let grid = Grid::from_vec(3, 2, vec![1u64, 9, 1, 1, 1, 1]);
+//! let goal = Pair::new(2, 0);
+//! let result = grid_dijkstra(&grid, Pair::new(0, 0), |p| p == goal, |_, to| Some(grid[to])).unwrap();
+//! assert_eq!(result.cost, 4);
+//! assert_eq!(result.path().len(), 5);
+//! ```
+use crate::{
+    grid::{Connectivity, Grid},
+    hash::{FastMap, FastSet},
+    pair::Pair,
+    runner::{count, span},
+};
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    hash::Hash,
+    ops::{Add, ControlFlow},
+};
+
+/// Outcome of a successful search.
+#[derive(Debug, Clone)]
+pub struct PathResult<N, C = u64> {
+    /// total cost from the start to `goal`
+    pub cost: C,
+    /// the first goal state reached
+    pub goal: N,
+    /// number of states expanded (popped from the frontier) during the search
+    pub visited_count: usize,
+    start: N,
+    predecessors: FastMap<N, N>,
+}
+
+impl<N, C> PathResult<N, C>
+where
+    N: Hash + Eq + Clone,
+{
+    /// the states of the best path, from the start to the goal (both included)
+    pub fn path(&self) -> Vec<N> {
+        let mut path = vec![self.goal.clone()];
+        let mut cur = &self.goal;
+        while *cur != self.start {
+            cur = &self.predecessors[cur];
+            path.push(cur.clone());
+        }
+        path.reverse();
+        path
+    }
+
+    /// maps each state reached by the search to the state it was first reached from with the
+    /// lowest cost. The start state has no entry.
+    pub fn predecessors(&self) -> &FastMap<N, N> {
+        &self.predecessors
+    }
+}
+
+/// Bounds stopping a search early, for `dijkstra_with` and `search::bfs_with_limits`
+/// (whose cost is the number of steps).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchLimits<C = u64> {
+    /// states reached with a higher cost are not explored
+    pub max_cost: Option<C>,
+    /// the search gives up after expanding this many states
+    pub max_expansions: Option<usize>,
+}
+
+impl<C> SearchLimits<C> {
+    /// no limits
+    pub const NONE: SearchLimits<C> = SearchLimits {
+        max_cost: None,
+        max_expansions: None,
+    };
+
+    pub fn with_max_cost(self, max_cost: C) -> SearchLimits<C> {
+        SearchLimits {
+            max_cost: Some(max_cost),
+            ..self
+        }
+    }
+
+    pub fn with_max_expansions(self, max_expansions: usize) -> SearchLimits<C> {
+        SearchLimits {
+            max_expansions: Some(max_expansions),
+            ..self
+        }
+    }
+
+    /// true if a state reached for `cost` is within `max_cost`
+    pub fn allows(&self, cost: &C) -> bool
+    where
+        C: PartialOrd,
+    {
+        self.max_cost.as_ref().is_none_or(|max| cost <= max)
+    }
+}
+
+impl<C> Default for SearchLimits<C> {
+    fn default() -> Self {
+        SearchLimits::NONE
+    }
+}
+
+/// Dijkstra's algorithm over an implicit graph. Returns `None` if no goal is reachable.
+///
+/// `successors` yields each neighbouring state with the (non-negative) cost of moving to it.
+pub fn dijkstra<N, C, FN, IN, FG>(start: N, successors: FN, is_goal: FG) -> Option<PathResult<N, C>>
+where
+    N: Hash + Eq + Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FG: FnMut(&N) -> bool,
+{
+    dijkstra_with(start, successors, is_goal, SearchLimits::NONE, |_, _| {
+        ControlFlow::Continue(())
+    })
+}
+
+/// like `dijkstra`, within `limits`, calling `on_visit` with each state expanded and its
+/// cost (in increasing cost order) before testing whether it is a goal. The search ends,
+/// returning `None`, once `on_visit` breaks or the expansion limit is reached.
+///
+/// ```
+/// use aoc_tools::pathfinding::{dijkstra_with, SearchLimits};
+/// use std::ops::ControlFlow;
+/// // states reachable from 0 in at most 10 steps of +1 or +3
+/// let successors = |&n: &u32| [(n + 1, 1u32), (n + 3, 1)];
+/// let mut reached = Vec::new();
+/// let limits = SearchLimits::NONE.with_max_cost(10);
+/// let found = dijkstra_with(0, successors, |_| false, limits, |&n, _| {
+///     reached.push(n);
+///     ControlFlow::Continue(())
+/// });
+/// assert!(found.is_none());
+/// // every value up to 30, except 29 which takes 9 * 3 + 2 * 1
+/// assert_eq!(reached.len(), 30);
+/// assert!(!reached.contains(&29));
+/// ```
+pub fn dijkstra_with<N, C, FN, IN, FG, FV>(
+    start: N,
+    mut successors: FN,
+    mut is_goal: FG,
+    limits: SearchLimits<C>,
+    mut on_visit: FV,
+) -> Option<PathResult<N, C>>
+where
+    N: Hash + Eq + Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FG: FnMut(&N) -> bool,
+    FV: FnMut(&N, C) -> ControlFlow<()>,
+{
+    let mut best = FastMap::from_iter([(start.clone(), C::default())]);
+    let mut predecessors = FastMap::default();
+    // the heap only holds indices into `pushed`, so states do not need to be `Ord`
+    let mut pushed = vec![start.clone()];
+    let mut heap = BinaryHeap::from([Reverse((C::default(), 0))]);
+    let mut visited_count = 0;
+    let _span = span("dijkstra");
+
+    let found = 'search: {
+        while let Some(Reverse((cost, i))) = heap.pop() {
+            let node = &pushed[i];
+            if best.get(node).is_some_and(|&c| c < cost) {
+                continue; // stale entry
+            }
+            if limits.max_expansions.is_some_and(|max| visited_count >= max) {
+                break 'search None;
+            }
+            visited_count += 1;
+            if on_visit(node, cost).is_break() {
+                break 'search None;
+            }
+            if is_goal(node) {
+                break 'search Some(PathResult {
+                    cost,
+                    goal: pushed.swap_remove(i),
+                    visited_count,
+                    start,
+                    predecessors,
+                });
+            }
+            for (next, step) in successors(node) {
+                let next_cost = cost + step;
+                if limits.allows(&next_cost) && best.get(&next).is_none_or(|&c| next_cost < c) {
+                    best.insert(next.clone(), next_cost);
+                    predecessors.insert(next.clone(), pushed[i].clone());
+                    heap.push(Reverse((next_cost, pushed.len())));
+                    pushed.push(next);
+                }
+            }
+        }
+        None
+    };
+    count("dijkstra expansions", visited_count as u64);
+    found
+}
+
+/// Dijkstra's algorithm across the orthogonal neighbours of `grid` cells.
+///
+/// `cost(from, to)` returns the cost of stepping from `from` into `to`, or `None` if the step
+/// is not allowed (walls, height rules, etc).
+pub fn grid_dijkstra<T, C, FC, FG>(
+    grid: &Grid<T>,
+    start: Pair<usize>,
+    mut is_goal: FG,
+    mut cost: FC,
+) -> Option<PathResult<Pair<usize>, C>>
+where
+    C: Copy + Ord + Add<Output = C> + Default,
+    FC: FnMut(Pair<usize>, Pair<usize>) -> Option<C>,
+    FG: FnMut(Pair<usize>) -> bool,
+{
+    dijkstra(
+        start,
+        |&p| {
+            neighbors4(grid, p)
+                .filter_map(|n| Some((n, cost(p, n)?)))
+                .collect::<Vec<_>>()
+        },
+        |&p| is_goal(p),
+    )
+}
+
+/// Outcome of `dijkstra_all_best_paths`.
+#[derive(Debug, Clone)]
+pub struct AllBestPaths<N, C = u64> {
+    /// cost of the optimal paths
+    pub cost: C,
+    /// the goal states reached with the optimal cost
+    pub goals: Vec<N>,
+    /// every state lying on at least one optimal path, start and goals included
+    pub states: FastSet<N>,
+    /// number of distinct optimal paths
+    pub path_count: u64,
+}
+
+/// Dijkstra's algorithm keeping every optimal predecessor, so it can answer questions about
+/// all the best paths at once: which states lie on any of them and how many there are.
+/// Returns `None` if no goal is reachable.
+///
+/// All the goal states reached with the minimal cost are taken into account, e.g. when the
+/// state is `(position, direction)` and the goal is any state at the end position.
+///
+/// Step costs must be strictly positive, zero cost steps may produce wrong path counts.
+pub fn dijkstra_all_best_paths<N, C, FN, IN, FG>(
+    start: N,
+    mut successors: FN,
+    mut is_goal: FG,
+) -> Option<AllBestPaths<N, C>>
+where
+    N: Hash + Eq + Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FG: FnMut(&N) -> bool,
+{
+    // states are interned, every other table is indexed by the state id
+    let mut ids = FastMap::from_iter([(start.clone(), 0)]);
+    let mut nodes = vec![start];
+    let mut dist = vec![C::default()];
+    let mut counts = vec![1u64];
+    let mut preds: Vec<Vec<usize>> = vec![Vec::new()];
+    let mut done = vec![false];
+    let mut heap = BinaryHeap::from([Reverse((C::default(), 0))]);
+
+    let mut best: Option<C> = None;
+    let mut goals = Vec::new();
+    while let Some(Reverse((cost, i))) = heap.pop() {
+        if done[i] || cost > dist[i] {
+            continue;
+        }
+        if best.is_some_and(|b| cost > b) {
+            break;
+        }
+        done[i] = true;
+        if is_goal(&nodes[i]) {
+            best = Some(cost);
+            goals.push(i);
+            continue;
+        }
+        for (next, step) in successors(&nodes[i]) {
+            let next_cost = cost + step;
+            let j = *ids.entry(next).or_insert_with_key(|next| {
+                nodes.push(next.clone());
+                dist.push(next_cost);
+                counts.push(0);
+                preds.push(Vec::new());
+                done.push(false);
+                heap.push(Reverse((next_cost, nodes.len() - 1)));
+                nodes.len() - 1
+            });
+            if next_cost < dist[j] {
+                dist[j] = next_cost;
+                counts[j] = counts[i];
+                preds[j] = vec![i];
+                heap.push(Reverse((next_cost, j)));
+            } else if next_cost == dist[j] && !done[j] {
+                counts[j] += counts[i];
+                preds[j].push(i);
+            }
+        }
+    }
+
+    let cost = best?;
+    let path_count = goals.iter().map(|&g| counts[g]).sum();
+    let mut on_path = vec![false; nodes.len()];
+    let mut stack = goals.clone();
+    while let Some(i) = stack.pop() {
+        if !std::mem::replace(&mut on_path[i], true) {
+            stack.extend(&preds[i]);
+        }
+    }
+
+    let states = on_path
+        .iter()
+        .zip(&nodes)
+        .filter(|&(&keep, _)| keep)
+        .map(|(_, n)| n.clone())
+        .collect();
+    Some(AllBestPaths {
+        cost,
+        goals: goals.into_iter().map(|g| nodes[g].clone()).collect(),
+        states,
+        path_count,
+    })
+}
+
+//------------------------------
+// Helpers
+//------------------------------
+
+/// orthogonal neighbours of `p` inside the grid
+fn neighbors4<T>(grid: &Grid<T>, p: Pair<usize>) -> impl Iterator<Item = Pair<usize>> {
+    grid.neighbors_by(&Connectivity::FOUR, p.x, p.y).map(Pair::from_tuple)
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_best_paths() {
+        // open 3x3 grid, corner to corner: 6 shortest paths covering every cell
+        let grid = Grid::new(3, 3, ());
+        let start = Pair::new(0, 0);
+        let goal = Pair::new(2, 2);
+        let result = dijkstra_all_best_paths(
+            start,
+            |&p| neighbors4(&grid, p).map(|n| (n, 1u32)).collect::<Vec<_>>(),
+            |&p| p == goal,
+        )
+        .unwrap();
+        assert_eq!(result.cost, 4);
+        assert_eq!(result.path_count, 6);
+        assert_eq!(result.states.len(), 9);
+        assert_eq!(result.goals, vec![goal]);
+    }
+
+    #[test]
+    fn test_all_best_paths_detour() {
+        // 0 -> 1 -> 2 is optimal, the detour through 3 must not be reported
+        let edges = |&n: &u8| match n {
+            0 => vec![(1, 1u32), (3, 2)],
+            1 => vec![(2, 1)],
+            3 => vec![(2, 5)],
+            _ => vec![],
+        };
+        let result = dijkstra_all_best_paths(0u8, edges, |&n| n == 2).unwrap();
+        assert_eq!(result.cost, 2);
+        assert_eq!(result.path_count, 1);
+        assert_eq!(result.states, FastSet::from_iter([0, 1, 2]));
+
+        let found = dijkstra(0u8, edges, |&n| n == 2).unwrap();
+        assert_eq!(found.path(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_search_limits() {
+        let line = |&n: &u32| [(n + 1, 1u32)];
+        let continue_ = |_: &u32, _| ControlFlow::Continue(());
+        let limits = SearchLimits::NONE.with_max_cost(5);
+        assert!(dijkstra_with(0, line, |&n| n == 6, limits, continue_).is_none());
+        assert_eq!(dijkstra_with(0, line, |&n| n == 5, limits, continue_).unwrap().cost, 5);
+
+        // expanding 0..=4 is not enough to pop 5
+        let limits = SearchLimits::NONE.with_max_expansions(5);
+        assert!(dijkstra_with(0, line, |&n| n == 5, limits, continue_).is_none());
+        let limits = SearchLimits::NONE.with_max_expansions(6);
+        assert_eq!(
+            dijkstra_with(0, line, |&n| n == 5, limits, continue_)
+                .unwrap()
+                .visited_count,
+            6
+        );
+
+        let stop_at_3 = |&n: &u32, _| {
+            if n == 3 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        };
+        assert!(dijkstra_with(0, line, |&n| n == 5, SearchLimits::NONE, stop_at_3).is_none());
+    }
+}
@@ -0,0 +1,171 @@
+//! Points with a const number of dimensions.
+//!
+//! `PointN<T, N>` generalizes `Pair` to any dimension, so the 3d and 4d variants of grid
+//! puzzles (hypercube automata, constellations) share the same point math.
+//!
+//! ```
+//! use aoc_tools::point::PointN;
+//! let a = PointN::new([0i32, 0, 0, 0]);
+//! let b = PointN::new([3, -1, 0, 2]);
+//! assert_eq!(a.manhattan(&b), 6);
+//! assert_eq!((a + b)[3], 2);
+//! assert_eq!(a.neighbors().count(), 80);
+//! ```
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PointN<T, const N: usize> {
+    pub coords: [T; N],
+}
+
+pub type Point3<T> = PointN<T, 3>;
+pub type Point4<T> = PointN<T, 4>;
+
+impl<T, const N: usize> PointN<T, N> {
+    pub fn new(coords: [T; N]) -> PointN<T, N> {
+        PointN { coords }
+    }
+}
+
+impl<T: Default + Copy, const N: usize> Default for PointN<T, N> {
+    fn default() -> Self {
+        PointN::new([T::default(); N])
+    }
+}
+
+impl<T, const N: usize> PointN<T, N>
+where
+    T: Copy + Sub<Output = T> + PartialOrd + Add<Output = T> + Default,
+{
+    /// sum of the absolute differences of the coordinates
+    pub fn manhattan(&self, rhs: &Self) -> T {
+        (0..N).fold(T::default(), |acc, i| {
+            let (a, b) = (self.coords[i], rhs.coords[i]);
+            acc + if a > b { a - b } else { b - a }
+        })
+    }
+}
+
+macro_rules! impl_neighbors {
+    ($($t:ty),*) => {$(
+        impl<const N: usize> PointN<$t, N> {
+            /// the `3^N - 1` points differing by at most one on each coordinate
+            pub fn neighbors(self) -> impl Iterator<Item = PointN<$t, N>> {
+                (0..3usize.pow(N as u32)).filter_map(move |mut code| {
+                    let mut p = self;
+                    let mut is_self = true;
+                    for c in p.coords.iter_mut() {
+                        let d = (code % 3) as $t - 1;
+                        code /= 3;
+                        is_self &= d == 0;
+                        *c += d;
+                    }
+                    (!is_self).then_some(p)
+                })
+            }
+
+            /// the `2N` points differing by one on a single coordinate
+            pub fn orthogonal_neighbors(self) -> impl Iterator<Item = PointN<$t, N>> {
+                (0..N).flat_map(move |i| {
+                    [-1, 1].map(|d| {
+                        let mut p = self;
+                        p.coords[i] += d;
+                        p
+                    })
+                })
+            }
+        }
+    )*};
+}
+
+impl_neighbors!(i8, i16, i32, i64, i128, isize);
+
+//------------------------------
+// Operators
+//------------------------------
+
+impl<T: Copy + Add<Output = T>, const N: usize> Add for PointN<T, N> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        PointN::new(std::array::from_fn(|i| self.coords[i] + rhs.coords[i]))
+    }
+}
+
+impl<T: Copy + AddAssign, const N: usize> AddAssign for PointN<T, N> {
+    fn add_assign(&mut self, rhs: Self) {
+        (0..N).for_each(|i| self.coords[i] += rhs.coords[i]);
+    }
+}
+
+impl<T: Copy + Sub<Output = T>, const N: usize> Sub for PointN<T, N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        PointN::new(std::array::from_fn(|i| self.coords[i] - rhs.coords[i]))
+    }
+}
+
+impl<T: Copy + SubAssign, const N: usize> SubAssign for PointN<T, N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        (0..N).for_each(|i| self.coords[i] -= rhs.coords[i]);
+    }
+}
+
+/// multiplication by scalar
+impl<T: Copy + Mul<Output = T>, const N: usize> Mul<T> for PointN<T, N> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        PointN::new(self.coords.map(|c| c * rhs))
+    }
+}
+
+impl<T: Copy + Neg<Output = T>, const N: usize> Neg for PointN<T, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        PointN::new(self.coords.map(|c| -c))
+    }
+}
+
+impl<T, const N: usize> Index<usize> for PointN<T, N> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &Self::Output {
+        &self.coords[i]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for PointN<T, N> {
+    fn index_mut(&mut self, i: usize) -> &mut Self::Output {
+        &mut self.coords[i]
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for PointN<T, N> {
+    fn from(coords: [T; N]) -> Self {
+        PointN::new(coords)
+    }
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbors() {
+        let p: Point3<i32> = PointN::new([1, 2, 3]);
+        let all: Vec<_> = p.neighbors().collect();
+        assert_eq!(all.len(), 26);
+        assert!(all.iter().all(|q| *q != p && (0..3).all(|i| (q[i] - p[i]).abs() <= 1)));
+        let orthogonal: Vec<_> = p.orthogonal_neighbors().collect();
+        assert_eq!(orthogonal.len(), 6);
+        assert!(orthogonal.iter().all(|q| q.manhattan(&p) == 1));
+        assert_eq!(-p * 2 + p, -p);
+    }
+}
@@ -0,0 +1,27 @@
+//! The items most solutions need, so a day starts with a single `use` line.
+//!
+//! `input` and `parse` are re-exported as modules, as both have `lines` and `split`
+//! helpers: call them as `input::lines(...)` or `parse::lines(...)`.
+//!
+//! ```
+//! use aoc_tools::prelude::*;
+//! let maze = Grid::from_vec(3, 2, "..#...".chars().collect());
+//! let best = grid_dijkstra(&maze, Pair::new(0, 0), |p| p == Pair::new(2, 1), |_, to| {
+//!     (maze[to] == '.').then_some(1)
+//! });
+//! assert_eq!(best.unwrap().cost, 3);
+//! let steps: Vec<(Direction, u32)> = parse::moves("R8,U5").collect();
+//! assert_eq!(steps[1].0, Direction::Up);
+//! ```
+pub use crate::{
+    direction::Direction,
+    error::{AocError, AocResult},
+    grid::Grid,
+    hash::{FastMap, FastSet},
+    input,
+    iter::{count_increases, is_strictly_sorted, kmerge, window_sums},
+    pair::Pair,
+    parse,
+    pathfinding::{PathResult, dijkstra, grid_dijkstra},
+    search::{bfs, bfs_with_visited},
+};
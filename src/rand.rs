@@ -0,0 +1,226 @@
+//! A tiny deterministic pseudo random number generator, with no external dependencies.
+//!
+//! Uses xorshift64* for generation, seeded through SplitMix64 so that similar seeds (0, 1,
+//! 2...) still produce unrelated streams. Not suitable for cryptography, but good enough for
+//! Monte Carlo estimations, random restarts or randomized min-cut while keeping solutions
+//! reproducible.
+//!
+//! ```
+//! use aoc_tools::rand::Rng;
+//! let mut rng = Rng::new(42);
+//! let roll = rng.gen_range(1..=6u32);
+//! assert!((1..=6).contains(&roll));
+//!
+//! let mut v = vec![1, 2, 3, 4, 5];
+//! rng.shuffle(&mut v);
+//! v.sort();
+//! assert_eq!(v, vec![1, 2, 3, 4, 5]);
+//! ```
+use std::ops::{Range, RangeInclusive};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// creates a generator from `seed`. The same seed always yields the same sequence.
+    pub fn new(seed: u64) -> Rng {
+        // splitmix64 step, also guarantees the xorshift state is never zero
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        Rng { state: z.max(1) }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// returns a float in the interval [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// returns true with probability `p`
+    pub fn gen_bool(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+
+    /// returns a uniformly distributed value inside `range`, which may be either half-open
+    /// (`a..b`) or inclusive (`a..=b`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty.
+    pub fn gen_range<T, R: SampleRange<T>>(&mut self, range: R) -> T {
+        range.sample(self)
+    }
+
+    /// shuffles `slice` in place (Fisher-Yates)
+    pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            slice.swap(i, j);
+        }
+    }
+
+    /// returns a random element of `slice`, or `None` if it is empty
+    pub fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
+        match slice.len() {
+            0 => None,
+            n => slice.get(self.below(n as u64) as usize),
+        }
+    }
+
+    /// returns an element of `items`, each `(item, weight)` being picked with probability
+    /// proportional to its weight. `None` if the weights add up to zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the weights add up to more than `u64::MAX`.
+    pub fn choose_weighted<'a, T>(&mut self, items: &'a [(T, u64)]) -> Option<&'a T> {
+        let total = items
+            .iter()
+            .try_fold(0u64, |acc, &(_, w)| acc.checked_add(w))
+            .expect("total weight overflows u64");
+        if total == 0 {
+            return None;
+        }
+        let mut pick = self.below(total);
+        items.iter().find_map(|(item, w)| match pick.checked_sub(*w) {
+            Some(rest) => {
+                pick = rest;
+                None
+            }
+            None => Some(item),
+        })
+    }
+
+    /// uniform value in [0, n), rejecting the biased tail of the u64 range
+    fn below(&mut self, n: u64) -> u64 {
+        debug_assert!(n > 0);
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let v = self.next_u64();
+            if v < zone {
+                return v % n;
+            }
+        }
+    }
+
+    /// uniform value in the inclusive span [0, span]
+    fn up_to(&mut self, span: u64) -> u64 {
+        match span.checked_add(1) {
+            Some(n) => self.below(n),
+            None => self.next_u64(),
+        }
+    }
+}
+
+/// Ranges that `Rng::gen_range` can sample from.
+pub trait SampleRange<T> {
+    fn sample(self, rng: &mut Rng) -> T;
+}
+
+macro_rules! impl_sample_range {
+    ($($t:ty),*) => {$(
+        impl SampleRange<$t> for Range<$t> {
+            fn sample(self, rng: &mut Rng) -> $t {
+                assert!(self.start < self.end, "cannot sample an empty range");
+                let span = self.end.wrapping_sub(self.start) as u64;
+                self.start.wrapping_add(rng.below(span) as $t)
+            }
+        }
+
+        impl SampleRange<$t> for RangeInclusive<$t> {
+            fn sample(self, rng: &mut Rng) -> $t {
+                let (start, end) = self.into_inner();
+                assert!(start <= end, "cannot sample an empty range");
+                let span = end.wrapping_sub(start) as u64;
+                start.wrapping_add(rng.up_to(span) as $t)
+            }
+        }
+    )*};
+}
+
+impl_sample_range!(u8, u16, u32, u64, usize);
+
+macro_rules! impl_sample_range_signed {
+    ($($t:ty => $u:ty),*) => {$(
+        impl SampleRange<$t> for Range<$t> {
+            fn sample(self, rng: &mut Rng) -> $t {
+                assert!(self.start < self.end, "cannot sample an empty range");
+                let span = self.end.wrapping_sub(self.start) as $u as u64;
+                self.start.wrapping_add(rng.below(span) as $t)
+            }
+        }
+
+        impl SampleRange<$t> for RangeInclusive<$t> {
+            fn sample(self, rng: &mut Rng) -> $t {
+                let (start, end) = self.into_inner();
+                assert!(start <= end, "cannot sample an empty range");
+                let span = end.wrapping_sub(start) as $u as u64;
+                start.wrapping_add(rng.up_to(span) as $t)
+            }
+        }
+    )*};
+}
+
+impl_sample_range_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize);
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deterministic() {
+        let a: Vec<u64> = (0..5).scan(Rng::new(7), |r, _| Some(r.next_u64())).collect();
+        let b: Vec<u64> = (0..5).scan(Rng::new(7), |r, _| Some(r.next_u64())).collect();
+        let c: Vec<u64> = (0..5).scan(Rng::new(8), |r, _| Some(r.next_u64())).collect();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_gen_range_bounds() {
+        let mut rng = Rng::new(0);
+        for _ in 0..1000 {
+            let v = rng.gen_range(-3..3i64);
+            assert!((-3..3).contains(&v));
+            let v = rng.gen_range(i8::MIN..=i8::MAX);
+            assert!((i8::MIN..=i8::MAX).contains(&v));
+            assert_eq!(rng.gen_range(5..=5u8), 5);
+        }
+        let _ = rng.gen_range(0..=u64::MAX);
+    }
+
+    #[test]
+    fn test_choose_weighted() {
+        let mut rng = Rng::new(3);
+        let items = [('a', 1), ('b', 0), ('c', 3)];
+        let mut counts = [0; 3];
+        for _ in 0..4000 {
+            match rng.choose_weighted(&items) {
+                Some('a') => counts[0] += 1,
+                Some('b') => counts[1] += 1,
+                _ => counts[2] += 1,
+            }
+        }
+        assert_eq!(counts[1], 0);
+        assert!((2800..3200).contains(&counts[2]), "{counts:?}");
+        assert_eq!(rng.choose_weighted(&[('z', 0)]), None);
+    }
+}
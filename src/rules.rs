@@ -0,0 +1,279 @@
+//! Workflows sending items with numeric categories through conditional rules, as the part
+//! sorting workflows of the elf factory.
+//!
+//! Each line of the input names a workflow and lists its rules, tried in order:
+//! `px{a<2006:qkq,m>2090:A,rfg}` sends an item with `a < 2006` to workflow `qkq`, else one
+//! with `m > 2090` to `A` (accepted), else to `rfg`. `R` rejects, and the last rule may be
+//! spelled `otherwise:rfg`. Items are slices of values, one per category in the order given
+//! to `Rules::parse`.
+//!
+//! Items are either run one by one (`Rules::accepts`), or whole ranges of items are split
+//! along the conditions and propagated through the workflows (`Rules::accepted_ranges`),
+//! to count the accepted items without enumerating them.
+//!
+//! ```
+//! use aoc_tools::rules::Rules;
+//! let rules = Rules::parse("in{x>10:A,m<20:R,otherwise:b}\nb{x<3:R,A}", &["x", "m"]).unwrap();
+//! let start = rules.workflow("in").unwrap();
+//! assert!(rules.accepts(start, &[11, 0]));
+//! assert!(!rules.accepts(start, &[5, 10]));
+//! assert!(rules.accepts(start, &[5, 30]));
+//! // x and m in 1..=20: 200 items with x > 10, then 8 * 1 with x in 3..=10 and m = 20
+//! assert_eq!(rules.count_accepted(start, &[1..=20, 1..=20]), 208);
+//! ```
+use crate::error::{AocError, AocResult};
+use std::ops::RangeInclusive;
+
+/// How a condition compares the value of a category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cmp {
+    Less,
+    Greater,
+}
+
+/// `category cmp value`, e.g. `a<2006`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Condition {
+    /// index of the category in the item
+    pub category: usize,
+    pub cmp: Cmp,
+    pub value: i64,
+}
+
+impl Condition {
+    pub fn holds(&self, item: &[i64]) -> bool {
+        let v = item[self.category];
+        match self.cmp {
+            Cmp::Less => v < self.value,
+            Cmp::Greater => v > self.value,
+        }
+    }
+
+    /// splits `range` into the values satisfying the condition and the others, either of
+    /// which may be empty
+    pub fn split(&self, range: &RangeInclusive<i64>) -> (RangeInclusive<i64>, RangeInclusive<i64>) {
+        let (start, end) = (*range.start(), *range.end());
+        match self.cmp {
+            Cmp::Less => (start..=end.min(self.value - 1), start.max(self.value)..=end),
+            Cmp::Greater => (start.max(self.value + 1)..=end, start..=end.min(self.value)),
+        }
+    }
+}
+
+/// Where a rule sends an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Accept,
+    Reject,
+    /// index of a workflow, see `Rules::workflow`
+    Workflow(usize),
+}
+
+/// A rule of a workflow: items satisfying `condition` (all of them if `None`) go to `target`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    pub condition: Option<Condition>,
+    pub target: Target,
+}
+
+/// The workflows parsed from an input, addressed by index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    names: Vec<String>,
+    workflows: Vec<Vec<Rule>>,
+}
+
+impl Rules {
+    /// parses one workflow per non empty line, `categories` naming the values of the items
+    /// in order. Fails on malformed lines, unknown categories or targets, and workflows
+    /// whose last rule has a condition.
+    pub fn parse(input: &str, categories: &[&str]) -> AocResult<Rules> {
+        let lines: Vec<(usize, &str)> = input
+            .lines()
+            .enumerate()
+            .map(|(i, l)| (i, l.trim()))
+            .filter(|(_, l)| !l.is_empty())
+            .collect();
+
+        let mut names = Vec::with_capacity(lines.len());
+        let mut bodies = Vec::with_capacity(lines.len());
+        for &(i, line) in &lines {
+            let (name, body) = line
+                .strip_suffix('}')
+                .and_then(|l| l.split_once('{'))
+                .ok_or_else(|| AocError::parse(Some(i + 1), line, "expected name{rules}"))?;
+            names.push(name.trim().to_string());
+            bodies.push((i, line, body));
+        }
+
+        let target = |s: &str| match s {
+            "A" => Some(Target::Accept),
+            "R" => Some(Target::Reject),
+            _ => names.iter().position(|n| n == s).map(Target::Workflow),
+        };
+        let mut workflows = Vec::with_capacity(bodies.len());
+        for (i, line, body) in bodies {
+            let err = |message: String| AocError::parse(Some(i + 1), line, message);
+            let mut rules = Vec::new();
+            for rule in body.split(',').map(str::trim) {
+                let (condition, dest) = match rule.split_once(':') {
+                    Some(("otherwise", dest)) => (None, dest),
+                    Some((cond, dest)) => (Some(parse_condition(cond, categories).map_err(err)?), dest),
+                    None => (None, rule),
+                };
+                let target = target(dest.trim()).ok_or_else(|| err(format!("unknown target '{dest}'")))?;
+                rules.push(Rule { condition, target });
+            }
+            if rules.last().is_none_or(|r| r.condition.is_some()) {
+                return Err(err("the last rule must have no condition".to_string()));
+            }
+            workflows.push(rules);
+        }
+        Ok(Rules { names, workflows })
+    }
+
+    /// index of the workflow called `name`
+    pub fn workflow(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    pub fn name(&self, workflow: usize) -> &str {
+        &self.names[workflow]
+    }
+
+    pub fn rules(&self, workflow: usize) -> &[Rule] {
+        &self.workflows[workflow]
+    }
+
+    /// the target of the first rule of `workflow` matching `item`
+    pub fn step(&self, workflow: usize, item: &[i64]) -> Target {
+        self.workflows[workflow]
+            .iter()
+            .find(|r| r.condition.is_none_or(|c| c.holds(item)))
+            .map(|r| r.target)
+            .expect("the last rule has no condition")
+    }
+
+    /// runs `item` through the workflows from `start` until it is accepted or rejected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the item goes around a loop of workflows.
+    pub fn accepts(&self, start: usize, item: &[i64]) -> bool {
+        let mut workflow = start;
+        for _ in 0..=self.workflows.len() {
+            match self.step(workflow, item) {
+                Target::Accept => return true,
+                Target::Reject => return false,
+                Target::Workflow(next) => workflow = next,
+            }
+        }
+        panic!("item loops through the workflows");
+    }
+
+    /// splits the items with values in `ranges` (one range per category) along the
+    /// conditions met from `start`, returning the disjoint boxes of accepted items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some items go around a loop of workflows.
+    pub fn accepted_ranges(&self, start: usize, ranges: &[RangeInclusive<i64>]) -> Vec<Vec<RangeInclusive<i64>>> {
+        let mut accepted = Vec::new();
+        let mut queue = vec![(start, ranges.to_vec(), 0)];
+        while let Some((workflow, mut ranges, depth)) = queue.pop() {
+            assert!(depth <= self.workflows.len(), "items loop through the workflows");
+            for rule in &self.workflows[workflow] {
+                if ranges.iter().any(RangeInclusive::is_empty) {
+                    break;
+                }
+                let taken = match rule.condition {
+                    None => std::mem::take(&mut ranges),
+                    Some(c) => {
+                        let (yes, no) = c.split(&ranges[c.category]);
+                        let mut taken = ranges.clone();
+                        (taken[c.category], ranges[c.category]) = (yes, no);
+                        taken
+                    }
+                };
+                match rule.target {
+                    _ if taken.iter().any(RangeInclusive::is_empty) => (),
+                    Target::Accept => accepted.push(taken),
+                    Target::Reject => (),
+                    Target::Workflow(next) => queue.push((next, taken, depth + 1)),
+                }
+                if rule.condition.is_none() {
+                    break;
+                }
+            }
+        }
+        accepted
+    }
+
+    /// number of items with values in `ranges` accepted from `start`
+    pub fn count_accepted(&self, start: usize, ranges: &[RangeInclusive<i64>]) -> u64 {
+        self.accepted_ranges(start, ranges)
+            .iter()
+            .map(|b| b.iter().map(|r| (r.end() - r.start() + 1) as u64).product::<u64>())
+            .sum()
+    }
+}
+
+fn parse_condition(cond: &str, categories: &[&str]) -> Result<Condition, String> {
+    let at = cond
+        .find(['<', '>'])
+        .ok_or_else(|| format!("expected a comparison in '{cond}'"))?;
+    let name = cond[..at].trim();
+    let category = categories
+        .iter()
+        .position(|&c| c == name)
+        .ok_or_else(|| format!("unknown category '{name}'"))?;
+    let cmp = if cond.as_bytes()[at] == b'<' {
+        Cmp::Less
+    } else {
+        Cmp::Greater
+    };
+    let value = cond[at + 1..].trim().parse().map_err(|e| format!("{e} in '{cond}'"))?;
+    Ok(Condition { category, cmp, value })
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}";
+
+    #[test]
+    fn test_workflows() {
+        let rules = Rules::parse(EXAMPLE, &["x", "m", "a", "s"]).unwrap();
+        let start = rules.workflow("in").unwrap();
+        let parts = [
+            [787, 2655, 1222, 2876],
+            [1679, 44, 2067, 496],
+            [2036, 264, 79, 2244],
+            [2461, 1339, 466, 291],
+            [2127, 1623, 2188, 1013],
+        ];
+        let accepted: Vec<bool> = parts.iter().map(|p| rules.accepts(start, p)).collect();
+        assert_eq!(accepted, vec![true, false, true, false, true]);
+        let full = vec![1..=4000; 4];
+        assert_eq!(rules.count_accepted(start, &full), 167409079868000);
+
+        assert!(Rules::parse("in{x>1:A}", &["x"]).is_err());
+        assert!(Rules::parse("in{y>1:A,R}", &["x"]).is_err());
+        assert!(Rules::parse("in{x>1:out,R}", &["x"]).is_err());
+    }
+}
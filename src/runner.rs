@@ -0,0 +1,524 @@
+//! Runs solvers, timing them and collecting a report per run.
+//!
+//! `Runner::run` calls a solver once, while `Runner::bench` calls it repeatedly and reports
+//! the mean time per invocation. Each run is recorded in a `Report`. Solvers that take a
+//! while can show how far along they are with a `Progress` line.
+//!
+//! With the `alloc-stats` feature, reports also include the peak heap memory used by the
+//! solver. This requires registering the counting allocator in the binary:
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static ALLOC: aoc_tools::runner::CountingAllocator = aoc_tools::runner::CountingAllocator;
+//! ```
+//!
+//! With the `profile` feature, reports also break the time down by phase: the searches,
+//! automata and parsers of the crate open a timing `span` and `count` their work (e.g. the
+//! states expanded), which the report aggregates by name. Solvers may add their own. Without
+//! the feature, spans and counters compile to nothing.
+//!
+//! ```
+//! use aoc_tools::runner::Runner;
+//! let mut runner = Runner::new();
+//! let answer = runner.run("day01 part1", || (1..=100).sum::<u32>());
+//! assert_eq!(answer, 5050);
+//! runner.bench("day01 part2", 10, || (1..=20).product::<u64>());
+//! assert_eq!(runner.reports()[1].invocations, 10);
+//! ```
+use std::{
+    fmt::{self, Display},
+    io::{self, IsTerminal},
+    time::{Duration, Instant},
+};
+
+/// Outcome of running a solver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub label: String,
+    /// the answer returned by the solver, rendered with `Display`
+    pub answer: String,
+    /// total time over all the invocations
+    pub elapsed: Duration,
+    /// how many times the solver was called
+    pub invocations: u32,
+    /// peak heap memory above the memory in use before the run, in bytes. Only available
+    /// with the `alloc-stats` feature and the `CountingAllocator` registered.
+    pub peak_bytes: Option<usize>,
+    /// time spent in each span opened by the solver, by name in order of first use, per
+    /// invocation as `mean`. Only filled with the `profile` feature.
+    pub spans: Vec<(&'static str, Duration)>,
+    /// sum of each counter per invocation, by name in order of first use. Only filled with
+    /// the `profile` feature.
+    pub counters: Vec<(&'static str, u64)>,
+}
+
+impl Report {
+    /// mean time per invocation
+    pub fn mean(&self) -> Duration {
+        self.elapsed / self.invocations.max(1)
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} ({:?}", self.label, self.answer, self.mean())?;
+        if self.invocations > 1 {
+            write!(f, " mean over {} runs", self.invocations)?;
+        }
+        if let Some(bytes) = self.peak_bytes {
+            write!(f, ", peak {:.1} KiB", bytes as f64 / 1024.0)?;
+        }
+        write!(f, ")")?;
+        let spans = self.spans.iter().map(|(name, elapsed)| format!("{name}: {elapsed:?}"));
+        let counters = self
+            .counters
+            .iter()
+            .map(|&(name, n)| format!("{name}: {}", short_count(n)));
+        let phases: Vec<String> = spans.chain(counters).collect();
+        if !phases.is_empty() {
+            write!(f, " [{}]", phases.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Runner {
+    reports: Vec<Report>,
+}
+
+impl Runner {
+    pub fn new() -> Runner {
+        Runner::default()
+    }
+
+    /// runs `solver` once, recording a report and returning its answer
+    pub fn run<T, F>(&mut self, label: &str, solver: F) -> T
+    where
+        T: Display,
+        F: FnOnce() -> T,
+    {
+        let baseline = alloc_stats::reset_peak();
+        profile::take();
+        let start = Instant::now();
+        let answer = solver();
+        let elapsed = start.elapsed();
+        self.push(label, &answer, elapsed, 1, alloc_stats::peak_since(baseline));
+        answer
+    }
+
+    /// runs `solver` `invocations` times (at least once), recording a report with the total
+    /// time and returning the last answer. The spans and counters of the report are averaged
+    /// over the invocations.
+    pub fn bench<T, F>(&mut self, label: &str, invocations: u32, mut solver: F) -> T
+    where
+        T: Display,
+        F: FnMut() -> T,
+    {
+        let invocations = invocations.max(1);
+        let baseline = alloc_stats::reset_peak();
+        profile::take();
+        let start = Instant::now();
+        let mut answer = solver();
+        for _ in 1..invocations {
+            answer = std::hint::black_box(solver());
+        }
+        let elapsed = start.elapsed();
+        self.push(label, &answer, elapsed, invocations, alloc_stats::peak_since(baseline));
+        answer
+    }
+
+    pub fn reports(&self) -> &[Report] {
+        &self.reports
+    }
+
+    /// total time over all the reports
+    pub fn total(&self) -> Duration {
+        self.reports.iter().map(|r| r.elapsed).sum()
+    }
+
+    /// prints each report on its own line, followed by the total time
+    pub fn print_summary(&self) {
+        self.reports.iter().for_each(|r| println!("{r}"));
+        println!("total: {:?}", self.total());
+    }
+
+    fn push<T: Display>(&mut self, label: &str, answer: &T, elapsed: Duration, invocations: u32, peak: Option<usize>) {
+        // averaged over the invocations, to compare with the mean time
+        let (mut spans, mut counters) = profile::take();
+        spans.iter_mut().for_each(|(_, t)| *t /= invocations);
+        counters.iter_mut().for_each(|(_, n)| *n /= u64::from(invocations));
+        self.reports.push(Report {
+            label: label.to_string(),
+            answer: answer.to_string(),
+            elapsed,
+            invocations,
+            peak_bytes: peak,
+            spans,
+            counters,
+        });
+    }
+}
+
+//------------------------------
+// Progress
+//------------------------------
+
+/// Progress line of a long running solver, printed to stderr.
+///
+/// Call `update` as often as needed: the line is redrawn at most once per `interval`
+/// (200ms by default), with the completed percentage and an estimate of the remaining time
+/// based on the mean speed so far. Nothing is printed when stderr is not a terminal, so
+/// piped output and test runs stay clean.
+///
+/// ```
+/// use aoc_tools::runner::Progress;
+/// let mut progress = Progress::new("seeds");
+/// for i in 0..1_000 {
+///     // ... brute force step
+///     progress.update(i + 1, 1_000);
+/// }
+/// progress.finish();
+/// ```
+#[derive(Debug)]
+pub struct Progress {
+    label: String,
+    start: Instant,
+    /// time of the last redraw, `None` while no line is on screen
+    last_draw: Option<Instant>,
+    /// `done` and `total` of the last update, drawn or not
+    latest: (u64, u64),
+    interval: Duration,
+    enabled: bool,
+}
+
+impl Progress {
+    pub fn new(label: &str) -> Progress {
+        Progress {
+            label: label.to_string(),
+            start: Instant::now(),
+            last_draw: None,
+            latest: (0, 0),
+            interval: Duration::from_millis(200),
+            enabled: io::stderr().is_terminal(),
+        }
+    }
+
+    /// sets the minimum time between two redraws
+    pub fn with_interval(mut self, interval: Duration) -> Progress {
+        self.interval = interval;
+        self
+    }
+
+    /// forces printing on or off, regardless of whether stderr is a terminal
+    pub fn with_enabled(mut self, enabled: bool) -> Progress {
+        self.enabled = enabled;
+        self
+    }
+
+    /// reports that `done` out of `total` units of work are complete
+    pub fn update(&mut self, done: u64, total: u64) {
+        self.latest = (done, total);
+        if !self.enabled {
+            return;
+        }
+        let now = Instant::now();
+        if self.last_draw.is_some_and(|last| now - last < self.interval) {
+            return;
+        }
+        self.last_draw = Some(now);
+        eprint!("\r{}\x1b[K", self.status(done, total, now - self.start));
+    }
+
+    /// redraws the line with the last update, which may have been throttled, and ends it so
+    /// later output starts on a new line. Finishing again does nothing.
+    pub fn finish(&mut self) {
+        if self.last_draw.take().is_some() {
+            let (done, total) = self.latest;
+            eprintln!("\r{}\x1b[K", self.status(done, total, self.start.elapsed()));
+        }
+    }
+
+    /// text of the progress line after `elapsed`
+    fn status(&self, done: u64, total: u64, elapsed: Duration) -> String {
+        let percent = if total == 0 {
+            100.0
+        } else {
+            100.0 * done as f64 / total as f64
+        };
+        let mut status = format!("{}: {done}/{total} ({percent:.1}%)", self.label);
+        if done > 0 && done < total {
+            let remaining = elapsed.as_secs_f64() * (total - done) as f64 / done as f64;
+            status += &format!(", eta {:?}", Duration::from_secs(remaining.ceil() as u64));
+        }
+        status
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+//------------------------------
+// Profiling
+//------------------------------
+
+/// Guard timing a phase until it is dropped, returned by `span`.
+#[must_use = "the span ends when the guard is dropped"]
+#[derive(Debug)]
+pub struct Span {
+    #[cfg(feature = "profile")]
+    name: &'static str,
+    #[cfg(feature = "profile")]
+    start: Instant,
+}
+
+/// starts timing the phase `name` until the returned guard is dropped. The time is added
+/// to the span of that name in the report of the current run, so spans entered many times
+/// (one per search, say) add up. Spans are recorded per thread: the ones opened by other
+/// threads are not reported.
+///
+/// ```
+/// use aoc_tools::runner::{count, span, Runner};
+/// let mut runner = Runner::new();
+/// runner.run("day", || {
+///     let numbers: Vec<u64> = {
+///         let _parse = span("parse");
+///         "1 2 3".split(' ').map(|n| n.parse().unwrap()).collect()
+///     };
+///     count("numbers", numbers.len() as u64);
+///     numbers.iter().sum::<u64>()
+/// });
+/// // with the `profile` feature: "day: 6 (...) [parse: ..., numbers: 3]"
+/// println!("{}", runner.reports()[0]);
+/// ```
+#[inline]
+pub fn span(name: &'static str) -> Span {
+    let _ = name;
+    Span {
+        #[cfg(feature = "profile")]
+        name,
+        #[cfg(feature = "profile")]
+        start: Instant::now(),
+    }
+}
+
+/// Iterator adaptor timing the iteration of a lazy helper in a single span, from its
+/// creation until it is exhausted or dropped. Work done by the consumer between items is
+/// included.
+pub(crate) struct Spanned<I> {
+    iter: I,
+    span: Option<Span>,
+}
+
+/// wraps `iter` so its whole iteration is timed in the span `name`
+pub(crate) fn spanned<I: Iterator>(name: &'static str, iter: I) -> Spanned<I> {
+    Spanned {
+        iter,
+        span: Some(span(name)),
+    }
+}
+
+impl<I: Iterator> Iterator for Spanned<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let next = self.iter.next();
+        if next.is_none() {
+            self.span = None;
+        }
+        next
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// adds `n` to the counter `name` of the current run, see `span`
+#[inline]
+pub fn count(name: &'static str, n: u64) {
+    profile::add_count(name, n);
+}
+
+#[cfg(feature = "profile")]
+impl Drop for Span {
+    fn drop(&mut self) {
+        profile::add_time(self.name, self.start.elapsed());
+    }
+}
+
+/// `n` with a k/M/G suffix past a thousand
+fn short_count(n: u64) -> String {
+    match n {
+        0..1_000 => n.to_string(),
+        1_000..1_000_000 => format!("{:.1}k", n as f64 / 1e3),
+        1_000_000..1_000_000_000 => format!("{:.1}M", n as f64 / 1e6),
+        _ => format!("{:.1}G", n as f64 / 1e9),
+    }
+}
+
+/// spans and counters of a run, as in `Report`
+type Phases = (Vec<(&'static str, Duration)>, Vec<(&'static str, u64)>);
+
+#[cfg(feature = "profile")]
+mod profile {
+    use super::Phases;
+    use std::{cell::RefCell, time::Duration};
+
+    thread_local! {
+        static PHASES: RefCell<Phases> = const { RefCell::new((Vec::new(), Vec::new())) };
+    }
+
+    fn add<T: std::ops::AddAssign>(entries: &mut Vec<(&'static str, T)>, name: &'static str, value: T) {
+        match entries.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, total)) => *total += value,
+            None => entries.push((name, value)),
+        }
+    }
+
+    pub fn add_time(name: &'static str, elapsed: Duration) {
+        PHASES.with_borrow_mut(|(spans, _)| add(spans, name, elapsed));
+    }
+
+    pub fn add_count(name: &'static str, n: u64) {
+        PHASES.with_borrow_mut(|(_, counters)| add(counters, name, n));
+    }
+
+    /// the spans and counters recorded so far, clearing them
+    pub fn take() -> Phases {
+        PHASES.with_borrow_mut(std::mem::take)
+    }
+}
+
+#[cfg(not(feature = "profile"))]
+mod profile {
+    use super::Phases;
+
+    #[inline]
+    pub fn add_count(_name: &'static str, _n: u64) {}
+
+    pub fn take() -> Phases {
+        (Vec::new(), Vec::new())
+    }
+}
+
+//------------------------------
+// Allocation statistics
+//------------------------------
+
+#[cfg(feature = "alloc-stats")]
+pub use alloc_stats::CountingAllocator;
+
+#[cfg(feature = "alloc-stats")]
+mod alloc_stats {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    };
+
+    static CURRENT: AtomicUsize = AtomicUsize::new(0);
+    static PEAK: AtomicUsize = AtomicUsize::new(0);
+    static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+    /// A global allocator wrapping `System` that keeps track of the bytes in use and their
+    /// peak.
+    pub struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ACTIVE.store(true, Ordering::Relaxed);
+            let ptr = unsafe { System.alloc(layout) };
+            if !ptr.is_null() {
+                let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+                PEAK.fetch_max(current, Ordering::Relaxed);
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) };
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+    }
+
+    /// sets the peak to the current usage, returning it
+    pub fn reset_peak() -> usize {
+        let current = CURRENT.load(Ordering::Relaxed);
+        PEAK.store(current, Ordering::Relaxed);
+        current
+    }
+
+    /// peak usage above `baseline`, `None` if the allocator is not registered
+    pub fn peak_since(baseline: usize) -> Option<usize> {
+        ACTIVE
+            .load(Ordering::Relaxed)
+            .then(|| PEAK.load(Ordering::Relaxed).saturating_sub(baseline))
+    }
+}
+
+#[cfg(not(feature = "alloc-stats"))]
+mod alloc_stats {
+    pub fn reset_peak() -> usize {
+        0
+    }
+
+    pub fn peak_since(_baseline: usize) -> Option<usize> {
+        None
+    }
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_status() {
+        let progress = Progress::new("part2").with_enabled(false);
+        let status = progress.status(250, 1000, Duration::from_secs(10));
+        assert_eq!(status, "part2: 250/1000 (25.0%), eta 30s");
+        assert_eq!(progress.status(0, 0, Duration::ZERO), "part2: 0/0 (100.0%)");
+
+        // the second update is throttled, but still the one shown by `finish`
+        let mut progress = Progress::new("part2")
+            .with_enabled(true)
+            .with_interval(Duration::from_secs(60));
+        progress.update(1, 10);
+        progress.update(10, 10);
+        assert_eq!(progress.latest, (10, 10));
+        progress.finish();
+        assert!(progress.last_draw.is_none());
+    }
+
+    #[test]
+    fn test_profile_spans() {
+        let mut runner = Runner::new();
+        runner.run("spans", || {
+            for _ in 0..3 {
+                let _search = span("search");
+                count("expansions", 600_000);
+            }
+            0
+        });
+        runner.bench("bench", 4, || {
+            count("steps", 10);
+            0
+        });
+        let report = &runner.reports()[0];
+        if cfg!(feature = "profile") {
+            assert_eq!(report.spans.iter().map(|s| s.0).collect::<Vec<_>>(), vec!["search"]);
+            assert_eq!(report.counters, vec![("expansions", 1_800_000)]);
+            assert!(report.to_string().ends_with(", expansions: 1.8M]"));
+            // per invocation, as the mean time
+            assert_eq!(runner.reports()[1].counters, vec![("steps", 10)]);
+        } else {
+            assert!(report.spans.is_empty() && report.counters.is_empty());
+        }
+    }
+}
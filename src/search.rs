@@ -0,0 +1,540 @@
+//! Searches over implicit state graphs.
+//!
+//! The visited set is pluggable through the `Visited` trait: a `HashSet` works for any
+//! hashable state, while `BitVisited` stores one bit per state for states that can be packed
+//! into a dense integer, which cuts the memory of big searches by orders of magnitude.
+//!
+//! ```
+//! use aoc_tools::search::{bfs, bfs_with_visited, BitVisited};
+//! // reach 10 from 1, each step either doubles or adds one
+//! let successors = |&n: &u32| [n * 2, n + 1].into_iter().filter(|&m| m <= 10);
+//! assert_eq!(bfs(1, successors, |&n| n == 10), Some((10, 4)));
+//!
+//! let mut visited = BitVisited::new(11, |&n: &u32| n as usize);
+//! assert_eq!(bfs_with_visited(1, successors, |&n| n == 10, &mut visited), Some((10, 4)));
+//! ```
+//!
+//! "Collect every key" puzzles are solved in two phases: `target_distances` runs a BFS from
+//! each target of a grid, and `visit_all_targets` finds the best visiting order over the
+//! resulting distances with a bitmask DP. The same DP backs `tsp` and `tsp_longest` over a
+//! plain distance matrix.
+//!
+//! `with_deadline` runs a long search for a bounded time, returning the best result recorded
+//! before the deadline if the search did not complete.
+use crate::{
+    grid::{Connectivity, Grid},
+    hash::FastSet,
+    pair::Pair,
+    pathfinding::SearchLimits,
+    runner::{count, span},
+};
+use std::{
+    collections::{HashSet, VecDeque},
+    hash::{BuildHasher, Hash},
+    ops::{Add, ControlFlow, Range},
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A set of states already seen by a search.
+pub trait Visited<N> {
+    /// marks `state` as visited, returning true if it had not been visited before
+    fn insert(&mut self, state: &N) -> bool;
+}
+
+impl<N: Hash + Eq + Clone, S: BuildHasher> Visited<N> for HashSet<N, S> {
+    fn insert(&mut self, state: &N) -> bool {
+        HashSet::insert(self, state.clone())
+    }
+}
+
+/// A visited set storing one bit per state, for states that can be encoded into an integer
+/// in `0..capacity` (e.g. positions packed with their direction or a bitmask of keys).
+pub struct BitVisited<F> {
+    bits: Vec<u64>,
+    encode: F,
+}
+
+impl<F> BitVisited<F> {
+    /// creates an empty set for states encoded by `encode` into `0..capacity`
+    pub fn new<N>(capacity: usize, encode: F) -> BitVisited<F>
+    where
+        F: Fn(&N) -> usize,
+    {
+        BitVisited {
+            bits: vec![0; capacity.div_ceil(64)],
+            encode,
+        }
+    }
+
+    /// forgets every visited state, keeping the allocation
+    pub fn clear(&mut self) {
+        self.bits.iter_mut().for_each(|b| *b = 0);
+    }
+}
+
+impl<N, F: Fn(&N) -> usize> Visited<N> for BitVisited<F> {
+    /// # Panics
+    ///
+    /// Panics if the encoded state is out of the capacity given at construction.
+    fn insert(&mut self, state: &N) -> bool {
+        let i = (self.encode)(state);
+        let (word, bit) = (i / 64, 1 << (i % 64));
+        let new = self.bits[word] & bit == 0;
+        self.bits[word] |= bit;
+        new
+    }
+}
+
+/// Breadth first search from `start`, using a `FastSet` as the visited set.
+///
+/// Returns the first goal state found and the number of steps to reach it.
+pub fn bfs<N, FN, IN, FG>(start: N, successors: FN, is_goal: FG) -> Option<(N, usize)>
+where
+    N: Hash + Eq + Clone,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+    FG: FnMut(&N) -> bool,
+{
+    bfs_with_visited(start, successors, is_goal, &mut FastSet::default())
+}
+
+/// Breadth first search from `start`, recording the states in the caller provided
+/// `visited` set.
+///
+/// Returns the first goal state found and the number of steps to reach it. After the
+/// search, `visited` holds every state discovered so far.
+pub fn bfs_with_visited<N, FN, IN, FG, V>(start: N, successors: FN, is_goal: FG, visited: &mut V) -> Option<(N, usize)>
+where
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+    FG: FnMut(&N) -> bool,
+    V: Visited<N>,
+{
+    bfs_with_limits(start, successors, is_goal, visited, SearchLimits::NONE, |_, _| {
+        ControlFlow::Continue(())
+    })
+}
+
+/// like `bfs_with_visited`, within `limits` (the cost being the number of steps), calling
+/// `on_visit` with each state expanded and its number of steps before testing whether it
+/// is a goal. The search ends, returning `None`, once `on_visit` breaks or the expansion
+/// limit is reached.
+///
+/// States beyond `max_cost` steps are not recorded in `visited`, which therefore holds the
+/// states reachable within the bound once the search has run out of states.
+///
+/// ```
+/// use aoc_tools::{hash::FastSet, pathfinding::SearchLimits, search::bfs_with_limits};
+/// use std::ops::ControlFlow;
+/// // king moves on an unbounded board: (2n + 1)² cells within n steps
+/// let successors = |&(x, y): &(i32, i32)| {
+///     (-1..=1).flat_map(move |dx| (-1..=1).map(move |dy| (x + dx, y + dy)))
+/// };
+/// let mut visited = FastSet::default();
+/// let limits = SearchLimits::NONE.with_max_cost(50);
+/// bfs_with_limits((0, 0), successors, |_| false, &mut visited, limits, |_, _| ControlFlow::Continue(()));
+/// assert_eq!(visited.len(), 101 * 101);
+/// ```
+pub fn bfs_with_limits<N, FN, IN, FG, V, FV>(
+    start: N,
+    mut successors: FN,
+    mut is_goal: FG,
+    visited: &mut V,
+    limits: SearchLimits<usize>,
+    mut on_visit: FV,
+) -> Option<(N, usize)>
+where
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+    FG: FnMut(&N) -> bool,
+    V: Visited<N>,
+    FV: FnMut(&N, usize) -> ControlFlow<()>,
+{
+    visited.insert(&start);
+    let mut queue = VecDeque::from([(start, 0)]);
+    let mut expanded = 0;
+    let _span = span("bfs");
+    let found = 'search: {
+        while let Some((state, steps)) = queue.pop_front() {
+            if limits.max_expansions.is_some_and(|max| expanded >= max) {
+                break 'search None;
+            }
+            expanded += 1;
+            if on_visit(&state, steps).is_break() {
+                break 'search None;
+            }
+            if is_goal(&state) {
+                break 'search Some((state, steps));
+            }
+            if !limits.allows(&(steps + 1)) {
+                continue;
+            }
+            for next in successors(&state) {
+                if visited.insert(&next) {
+                    queue.push_back((next, steps + 1));
+                }
+            }
+        }
+        None
+    };
+    count("bfs expansions", expanded as u64);
+    found
+}
+
+//------------------------------
+// Visiting all targets
+//------------------------------
+
+/// Pairwise distances between the targets of a grid, computed by `target_distances`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetDistances {
+    /// `dist[a][b]` is the number of steps from target `a` to target `b`, `None` if it is
+    /// unreachable
+    pub dist: Vec<Vec<Option<usize>>>,
+    /// `needs[a][b]` is the bitmask of the targets that must be visited before walking the
+    /// shortest path from `a` to `b` (e.g. the keys of the doors on the way)
+    pub needs: Vec<Vec<u64>>,
+}
+
+/// runs a BFS across the orthogonal neighbours from each of the `targets` (at most 64),
+/// moving only through `passable` cells. Cells for which `requires` returns the index of a
+/// target (a door and its key) can be crossed, but the target is added to the needs of every
+/// path going through them.
+///
+/// Only the shortest path between each pair is considered, which is exact for tree-like
+/// mazes such as the key-and-door puzzles.
+///
+/// # Panics
+///
+/// Panics if there are more than 64 targets.
+pub fn target_distances<T, P, R>(
+    grid: &Grid<T>,
+    targets: &[Pair<usize>],
+    mut passable: P,
+    mut requires: R,
+) -> TargetDistances
+where
+    P: FnMut(&T) -> bool,
+    R: FnMut(&T) -> Option<usize>,
+{
+    assert!(targets.len() <= 64, "at most 64 targets are supported");
+    let n = targets.len();
+    let target_at: Vec<Option<usize>> = (0..grid.size())
+        .map(|i| targets.iter().position(|t| grid.flat_index(t.x, t.y) == i))
+        .collect();
+    let mut distances = TargetDistances {
+        dist: vec![vec![None; n]; n],
+        needs: vec![vec![0; n]; n],
+    };
+    for (a, start) in targets.iter().enumerate() {
+        let mut seen = vec![false; grid.size()];
+        let start = grid.flat_index(start.x, start.y);
+        seen[start] = true;
+        let mut queue = VecDeque::from([(start, 0, 0u64)]);
+        while let Some((i, steps, needs)) = queue.pop_front() {
+            if let Some(b) = target_at[i] {
+                distances.dist[a][b] = Some(steps);
+                distances.needs[a][b] = needs;
+            }
+            let (x, y) = grid.xy_index(i);
+            for (nx, ny) in grid.neighbors_by(&Connectivity::FOUR, x, y) {
+                let j = grid.flat_index(nx, ny);
+                let cell = grid.get_flat(j);
+                if seen[j] || !passable(cell) {
+                    continue;
+                }
+                seen[j] = true;
+                let needs = requires(cell).map_or(needs, |key| needs | 1 << key);
+                queue.push_back((j, steps + 1, needs));
+            }
+        }
+    }
+    distances
+}
+
+/// minimum number of steps to visit every target starting from target `start`, respecting
+/// the needs of each path. Returns `None` if some target can not be reached.
+///
+/// ```
+/// use aoc_tools::{grid::Grid, pair::Pair, search::{target_distances, visit_all_targets}};
+/// // start at @, key a opens door A
+/// let grid = Grid::from_vec(9, 1, "b.A.@.a..".chars().collect());
+/// let targets = [Pair::new(4, 0), Pair::new(6, 0), Pair::new(0, 0)];
+/// let requires = |&c: &char| (c == 'A').then_some(1);
+/// let distances = target_distances(&grid, &targets, |&c| c != '#', requires);
+/// assert_eq!(visit_all_targets(&distances, 0), Some(8));
+/// ```
+pub fn visit_all_targets(distances: &TargetDistances, start: usize) -> Option<usize> {
+    let edge = |i: usize, j: usize, visited: u64| {
+        let d = distances.dist[i][j]?;
+        (distances.needs[i][j] & !visited == 0).then_some(d)
+    };
+    held_karp(distances.dist.len(), Some(start), false, edge, |a, b| a < b)
+}
+
+//------------------------------
+// Traveling salesman
+//------------------------------
+
+/// shortest route visiting every vertex of `dist` once, `dist[a][b]` being the cost from `a`
+/// to `b`. The route starts at `start`, or anywhere if it is `None`, and if
+/// `return_to_start` is set it closes the cycle back to its first vertex.
+///
+/// Held-Karp bitmask DP: O(2^n n^2), for the dozen of locations of route puzzles.
+///
+/// ```
+/// use aoc_tools::search::{tsp, tsp_longest};
+/// // London, Dublin, Belfast
+/// let dist = vec![vec![0, 464, 518], vec![464, 0, 141], vec![518, 141, 0]];
+/// assert_eq!(tsp(&dist, None, false), Some(605));
+/// assert_eq!(tsp_longest(&dist, None, false), Some(982));
+/// assert_eq!(tsp(&dist, Some(0), true), Some(1123));
+/// ```
+pub fn tsp<C>(dist: &[Vec<C>], start: Option<usize>, return_to_start: bool) -> Option<C>
+where
+    C: Copy + Ord + Add<Output = C> + Default,
+{
+    held_karp(
+        dist.len(),
+        start,
+        return_to_start,
+        |i, j, _| Some(dist[i][j]),
+        |a, b| a < b,
+    )
+}
+
+/// same as `tsp`, but finds the most expensive route, e.g. the happiest seating plan
+pub fn tsp_longest<C>(dist: &[Vec<C>], start: Option<usize>, return_to_start: bool) -> Option<C>
+where
+    C: Copy + Ord + Add<Output = C> + Default,
+{
+    held_karp(
+        dist.len(),
+        start,
+        return_to_start,
+        |i, j, _| Some(dist[i][j]),
+        |a, b| a > b,
+    )
+}
+
+/// Held-Karp over `n` vertices. `edge(i, j, visited)` is the cost from `i` to `j` given the
+/// bitmask of visited vertices, `None` if the move is not allowed, and `better(a, b)` tells
+/// whether cost `a` should replace `b`.
+fn held_karp<C, E, B>(n: usize, start: Option<usize>, return_to_start: bool, mut edge: E, better: B) -> Option<C>
+where
+    C: Copy + Add<Output = C> + Default,
+    E: FnMut(usize, usize, u64) -> Option<C>,
+    B: Fn(C, C) -> bool,
+{
+    if n == 0 {
+        return None;
+    }
+    assert!(n < 64, "too many vertices for a bitmask DP");
+    // the start of a cycle does not matter
+    let start = start.or(return_to_start.then_some(0));
+    let full = (1u64 << n) - 1;
+    let keep = |slot: &mut Option<C>, cost: C| {
+        if slot.is_none_or(|c| better(cost, c)) {
+            *slot = Some(cost);
+        }
+    };
+    // best[mask][i]: best cost to have visited `mask`, standing at vertex `i`
+    let mut best = vec![vec![None::<C>; n]; 1 << n];
+    for s in start.map_or(0..n, |s| s..s + 1) {
+        best[1 << s][s] = Some(C::default());
+    }
+    for mask in 1..full {
+        for i in 0..n {
+            let Some(cost) = best[mask as usize][i] else {
+                continue;
+            };
+            for j in (0..n).filter(|&j| mask & 1 << j == 0) {
+                if let Some(d) = edge(i, j, mask) {
+                    keep(&mut best[(mask | 1 << j) as usize][j], cost + d);
+                }
+            }
+        }
+    }
+    let mut result = None;
+    for (i, cost) in best[full as usize].iter().enumerate() {
+        let Some(cost) = *cost else {
+            continue;
+        };
+        match (return_to_start, start) {
+            (true, Some(s)) if n > 1 => {
+                if let Some(d) = edge(i, s, full) {
+                    keep(&mut result, cost + d);
+                }
+            }
+            _ => keep(&mut result, cost),
+        }
+    }
+    result
+}
+
+//------------------------------
+// Parallel brute force
+//------------------------------
+
+/// numbers tested by a worker before claiming the next chunk
+const PAR_CHUNK: u64 = 1 << 12;
+
+/// returns the smallest value of `range` satisfying `predicate`, testing the range with one
+/// thread per available core, e.g. to find the lowest nonce whose hash starts with zeros.
+///
+/// Workers claim chunks of the range in increasing order and stop as soon as the chunks left
+/// start after the best value found so far, so the result is the same as a sequential
+/// search, just faster.
+///
+/// ```
+/// use aoc_tools::search::par_find_first;
+/// assert_eq!(par_find_first(1..1_000_000, |n| n % 7919 == 0 && n % 3 == 0), Some(23757));
+/// assert_eq!(par_find_first(0..100, |n| n > 100), None);
+/// ```
+pub fn par_find_first<F>(range: Range<u64>, predicate: F) -> Option<u64>
+where
+    F: Fn(u64) -> bool + Sync,
+{
+    let next_chunk = AtomicU64::new(range.start);
+    let best = AtomicU64::new(u64::MAX);
+    let workers = thread::available_parallelism().map_or(1, |n| n.get());
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    let start = next_chunk.fetch_add(PAR_CHUNK, Ordering::Relaxed);
+                    // `start < range.start` if the counter wrapped around
+                    if start >= range.end || start < range.start || start >= best.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let end = start.saturating_add(PAR_CHUNK).min(range.end);
+                    if let Some(found) = (start..end).find(|&n| predicate(n)) {
+                        best.fetch_min(found, Ordering::Relaxed);
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    // `u64::MAX` is never in an exclusive range, so it works as the "not found" marker
+    let best = best.into_inner();
+    (best != u64::MAX).then_some(best)
+}
+
+//------------------------------
+// Deadlines
+//------------------------------
+
+/// Handed by `with_deadline` to the search it runs: tells whether time is up and keeps the
+/// best result found so far.
+#[derive(Debug)]
+pub struct Deadline<R> {
+    end: Instant,
+    best: Option<R>,
+    expired: bool,
+}
+
+impl<R> Deadline<R> {
+    /// true once the deadline has passed
+    pub fn expired(&mut self) -> bool {
+        self.expired = self.expired || Instant::now() >= self.end;
+        self.expired
+    }
+
+    /// `Break` once the deadline has passed, to be returned from the `on_visit` hook of
+    /// `bfs_with_limits` or `dijkstra_with`
+    pub fn check(&mut self) -> ControlFlow<()> {
+        if self.expired() {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    /// records `result` as the best so far, replacing the previous one
+    pub fn record(&mut self, result: R) {
+        self.best = Some(result);
+    }
+
+    /// records `result` if there is no best so far or `better(result, best)`
+    pub fn offer<F>(&mut self, result: R, better: F)
+    where
+        F: FnOnce(&R, &R) -> bool,
+    {
+        if self.best.as_ref().is_none_or(|best| better(&result, best)) {
+            self.best = Some(result);
+        }
+    }
+
+    pub fn best(&self) -> Option<&R> {
+        self.best.as_ref()
+    }
+}
+
+/// Outcome of `with_deadline`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Anytime<R> {
+    /// the result of the search if it returned one, else the best one it recorded
+    pub best: Option<R>,
+    /// false if the search returned no result after the deadline expired
+    pub complete: bool,
+    pub elapsed: Duration,
+}
+
+/// runs `search`, which should check its `Deadline` regularly and stop once it expires,
+/// recording its best result so far along the way. Returns the result of the search as
+/// complete whenever there is one, even if the deadline expired meanwhile, and falls back
+/// to the best recorded one otherwise.
+///
+/// ```
+/// use aoc_tools::{pathfinding::SearchLimits, search::{bfs_with_limits, with_deadline}};
+/// use std::{collections::HashSet, time::Duration};
+/// // an endless search for the largest number: only a partial answer
+/// let found = with_deadline(Duration::from_millis(20), |deadline| {
+///     let on_visit = |&n: &u64, _| {
+///         deadline.offer(n, |a, b| a > b);
+///         deadline.check()
+///     };
+///     let successors = |&n: &u64| [n + 1];
+///     bfs_with_limits(0, successors, |_| false, &mut HashSet::new(), SearchLimits::NONE, on_visit).map(|(n, _)| n)
+/// });
+/// assert!(!found.complete);
+/// assert!(found.best.is_some_and(|n| n > 0));
+///
+/// let quick = with_deadline(Duration::from_secs(60), |deadline| {
+///     bfs_with_limits(0u64, |&n| [n + 1], |&n| n == 10, &mut HashSet::new(), SearchLimits::NONE, |_, _| deadline.check())
+/// });
+/// assert_eq!((quick.best, quick.complete), (Some((10, 10)), true));
+///
+/// // finished with an answer after the deadline: the answer wins over the recorded one
+/// let late = with_deadline(Duration::ZERO, |deadline| {
+///     deadline.record(1);
+///     while !deadline.expired() {}
+///     Some(2)
+/// });
+/// assert_eq!((late.best, late.complete), (Some(2), true));
+/// ```
+pub fn with_deadline<R, F>(duration: Duration, search: F) -> Anytime<R>
+where
+    F: FnOnce(&mut Deadline<R>) -> Option<R>,
+{
+    let start = Instant::now();
+    let mut deadline = Deadline {
+        end: start + duration,
+        best: None,
+        expired: false,
+    };
+    let result = search(&mut deadline);
+    let (best, complete) = if result.is_some() {
+        (result, true)
+    } else {
+        (deadline.best, !deadline.expired)
+    };
+    Anytime {
+        best,
+        complete,
+        elapsed: start.elapsed(),
+    }
+}
@@ -0,0 +1,89 @@
+//! A grid of unbounded extent storing only the cells that were set.
+//!
+//! Coordinates are signed, so the grid can grow in any direction, which suits shapes drawn
+//! from move instructions or spreading simulations whose bounds are not known upfront.
+//!
+//! ```
+//! use aoc_tools::{pair::Pair, sparse_grid::SparseGrid};
+//! let mut grid = SparseGrid::new();
+//! grid.insert(Pair::new(-2, 5), '#');
+//! grid.insert(Pair::new(3, -1), '#');
+//! assert_eq!(grid.get(Pair::new(-2, 5)), Some(&'#'));
+//! assert_eq!(grid.bounds(), Some((Pair::new(-2, -1), Pair::new(3, 5))));
+//! ```
+use crate::{hash::FastMap, pair::Pair};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: FastMap<Pair<i64>, T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: FastMap::default(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::default()
+    }
+
+    pub fn get(&self, p: Pair<i64>) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn get_mut(&mut self, p: Pair<i64>) -> Option<&mut T> {
+        self.cells.get_mut(&p)
+    }
+
+    /// sets the cell at `p`, returning its previous value
+    pub fn insert(&mut self, p: Pair<i64>, value: T) -> Option<T> {
+        self.cells.insert(p, value)
+    }
+
+    pub fn remove(&mut self, p: Pair<i64>) -> Option<T> {
+        self.cells.remove(&p)
+    }
+
+    pub fn contains(&self, p: Pair<i64>) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    /// number of cells set
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// iterates over the cells set, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (Pair<i64>, &T)> {
+        self.cells.iter().map(|(&p, v)| (p, v))
+    }
+
+    /// the smallest and largest coordinates of the cells set (both inclusive), `None` if the
+    /// grid is empty
+    pub fn bounds(&self) -> Option<(Pair<i64>, Pair<i64>)> {
+        let mut points = self.cells.keys();
+        let first = *points.next()?;
+        Some(points.fold((first, first), |(min, max), p| {
+            (
+                Pair::new(min.x.min(p.x), min.y.min(p.y)),
+                Pair::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        }))
+    }
+}
+
+impl<T> FromIterator<(Pair<i64>, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Pair<i64>, T)>>(iter: I) -> Self {
+        SparseGrid {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
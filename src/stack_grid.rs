@@ -0,0 +1,199 @@
+//! A two-dimensional array with dimensions known at compile time, stored inline.
+//!
+//! Mirrors the `Grid` API, but the cells live in a `[[T; W]; H]` array instead of a heap
+//! allocated `Vec`. Meant for tiny fixed-size grids (keypads, 5x5 bingo boards) used in hot
+//! loops, where allocation and dynamic dimensions add measurable overhead.
+//!
+//! Like `Grid`, this is a row major implementation: `x` is the column and `y` the row.
+//!
+//! ```
+//! use aoc_tools::stack_grid::StackGrid;
+//! let mut keypad = StackGrid::<u8, 3, 3>::from_iter(1..=9);
+//! assert_eq!(keypad[(1usize, 1usize)], 5);
+//! *keypad.get_mut(2, 0) = 0;
+//! assert_eq!(keypad.row(0), &[1, 2, 0]);
+//! ```
+use crate::pair::Pair;
+use std::ops::{Index, IndexMut};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct StackGrid<T, const W: usize, const H: usize> {
+    cells: [[T; W]; H],
+}
+
+impl<T: Copy, const W: usize, const H: usize> StackGrid<T, W, H> {
+    /// creates a new grid with all the elements having the `init`ial value
+    pub fn new(init: T) -> StackGrid<T, W, H> {
+        StackGrid { cells: [[init; W]; H] }
+    }
+}
+
+/// The input iterator must have at least `W` * `H` elements, if it has more, the remaining
+/// are ignored.
+///
+/// # Panics
+///
+/// - If the iterator does not have enough elements to fill the grid.
+impl<T: Default + Copy, const W: usize, const H: usize> FromIterator<T> for StackGrid<T, W, H> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut grid = StackGrid::new(T::default());
+        let mut iter = iter.into_iter();
+        for cell in grid.iter_mut() {
+            *cell = iter
+                .next()
+                .expect("iterator does not have enough elements to fill the grid");
+        }
+        grid
+    }
+}
+
+impl<T: Default + Copy, const W: usize, const H: usize> Default for StackGrid<T, W, H> {
+    fn default() -> Self {
+        StackGrid::new(T::default())
+    }
+}
+
+impl<T, const W: usize, const H: usize> StackGrid<T, W, H> {
+    /// wraps an existing array of rows
+    pub fn from_array(cells: [[T; W]; H]) -> StackGrid<T, W, H> {
+        StackGrid { cells }
+    }
+
+    /// number of columns
+    pub const fn len_x(&self) -> usize {
+        W
+    }
+
+    /// number of rows
+    pub const fn len_y(&self) -> usize {
+        H
+    }
+
+    //------------------------------
+    // Getters for single elements
+    //------------------------------
+
+    /// returns the value at position x,y.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn get(&self, x: usize, y: usize) -> &T {
+        &self.cells[y][x]
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> &mut T {
+        &mut self.cells[y][x]
+    }
+
+    /// returns the value at position `idx` of the cells in row major order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn get_flat(&self, idx: usize) -> &T {
+        &self.cells.as_flattened()[idx]
+    }
+
+    pub fn get_flat_mut(&mut self, idx: usize) -> &mut T {
+        &mut self.cells.as_flattened_mut()[idx]
+    }
+
+    //------------------------------
+    // Getters for multiple elements
+    //------------------------------
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.as_flattened().iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.as_flattened_mut().iter_mut()
+    }
+
+    // returns an array slice for a line of the grid
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.cells[y]
+    }
+
+    pub fn iter_col(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.cells.iter().map(move |row| &row[x])
+    }
+
+    pub fn iter_col_mut(&mut self, x: usize) -> impl DoubleEndedIterator<Item = &mut T> + ExactSizeIterator {
+        self.cells.iter_mut().map(move |row| &mut row[x])
+    }
+
+    //------------------------------
+    // Helpers
+    //------------------------------
+
+    /// returns the total size of the array (W * H)
+    pub const fn size(&self) -> usize {
+        W * H
+    }
+
+    pub fn xy_index(&self, idx: usize) -> (usize, usize) {
+        (idx % W, idx / W)
+    }
+}
+
+impl<T: PartialEq, const W: usize, const H: usize> StackGrid<T, W, H> {
+    pub fn find(&self, item: &T) -> Option<(usize, usize)> {
+        let (idx, _) = self.iter().enumerate().find(|&(_, x)| x == item)?;
+        Some(self.xy_index(idx))
+    }
+}
+
+//------------------------------
+// Indexing
+//------------------------------
+
+/// Uses a tuple for indexing.
+impl<T, V, const W: usize, const H: usize> Index<(V, V)> for StackGrid<T, W, H>
+where
+    V: Into<usize>,
+{
+    type Output = T;
+
+    fn index(&self, index: (V, V)) -> &Self::Output {
+        self.get(index.0.into(), index.1.into())
+    }
+}
+
+impl<T, V, const W: usize, const H: usize> IndexMut<(V, V)> for StackGrid<T, W, H>
+where
+    V: Into<usize>,
+{
+    fn index_mut(&mut self, index: (V, V)) -> &mut Self::Output {
+        self.get_mut(index.0.into(), index.1.into())
+    }
+}
+
+/// Uses a `Pair` for indexing.
+impl<T, U, const W: usize, const H: usize> Index<Pair<U>> for StackGrid<T, W, H>
+where
+    U: Into<usize>,
+{
+    type Output = T;
+
+    fn index(&self, index: Pair<U>) -> &Self::Output {
+        self.get(index.x.into(), index.y.into())
+    }
+}
+
+impl<T, U, const W: usize, const H: usize> IndexMut<Pair<U>> for StackGrid<T, W, H>
+where
+    U: Into<usize>,
+{
+    fn index_mut(&mut self, index: Pair<U>) -> &mut Self::Output {
+        self.get_mut(index.x.into(), index.y.into())
+    }
+}
@@ -0,0 +1,170 @@
+//! Small string analyses that keep coming back in checksum and ID-scanning puzzles.
+//!
+//! ```
+//! use aoc_tools::strings::{char_counts, common_chars, differing_positions, has_n_of_any_letter};
+//! assert_eq!(char_counts("bababc").count(&'a'), 2);
+//! assert!(has_n_of_any_letter("bababc", 3));
+//! assert_eq!(differing_positions("fghij", "fguij"), vec![2]);
+//! assert_eq!(common_chars("fghij", "fguij"), "fgij");
+//! ```
+//!
+//! Repetition codes and diagnostic reports are read column by column with
+//! `column_frequencies`, which counts the chars of each column in a `Counter`.
+use crate::hash::FastMap;
+use std::hash::Hash;
+
+/// counts how many times each char appears in `s`
+pub fn char_counts(s: &str) -> Counter<char> {
+    s.chars().collect()
+}
+
+/// returns true if some letter appears exactly `n` times in `s`
+pub fn has_n_of_any_letter(s: &str, n: usize) -> bool {
+    char_counts(s).iter().any(|(c, count)| c.is_alphabetic() && count == n)
+}
+
+/// returns the chars that are equal at the same position in both strings. Comparison stops
+/// at the end of the shortest string.
+pub fn common_chars(a: &str, b: &str) -> String {
+    a.chars()
+        .zip(b.chars())
+        .filter(|(x, y)| x == y)
+        .map(|(x, _)| x)
+        .collect()
+}
+
+/// returns the (char) positions where the strings differ. If the lengths differ, every
+/// position past the end of the shortest string is also reported.
+pub fn differing_positions(a: &str, b: &str) -> Vec<usize> {
+    let (len_a, len_b) = (a.chars().count(), b.chars().count());
+    a.chars()
+        .zip(b.chars())
+        .enumerate()
+        .filter(|(_, (x, y))| x != y)
+        .map(|(i, _)| i)
+        .chain(len_a.min(len_b)..len_a.max(len_b))
+        .collect()
+}
+
+/// returns true if both strings are made of the same chars, with the same multiplicity
+pub fn is_anagram(a: &str, b: &str) -> bool {
+    let (mut a, mut b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    a.sort_unstable();
+    b.sort_unstable();
+    a == b
+}
+
+//------------------------------
+// Frequencies
+//------------------------------
+
+/// Number of occurrences of each distinct item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<T: Hash + Eq> {
+    counts: FastMap<T, usize>,
+}
+
+impl<T: Hash + Eq> Default for Counter<T> {
+    fn default() -> Self {
+        Counter {
+            counts: FastMap::default(),
+        }
+    }
+}
+
+impl<T: Hash + Eq> Counter<T> {
+    pub fn new() -> Counter<T> {
+        Counter::default()
+    }
+
+    pub fn add(&mut self, item: T) {
+        *self.counts.entry(item).or_default() += 1;
+    }
+
+    /// occurrences of `item`, 0 if it was never added
+    pub fn count(&self, item: &T) -> usize {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    /// number of distinct items
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// distinct items with their counts, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.counts.iter().map(|(item, &n)| (item, n))
+    }
+}
+
+impl<T: Hash + Eq + Ord> Counter<T> {
+    /// the item added the most times, ties going to the largest item (a `1` over a `0` in
+    /// a diagnostic report)
+    pub fn most_common(&self) -> Option<(&T, usize)> {
+        self.iter().max_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)))
+    }
+
+    /// the item added the fewest times, ties going to the smallest item
+    pub fn least_common(&self) -> Option<(&T, usize)> {
+        self.iter().min_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)))
+    }
+}
+
+impl<T: Hash + Eq> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        iter.into_iter().for_each(|item| counter.add(item));
+        counter
+    }
+}
+
+/// counts the chars of each column of `lines`, the first counter being the leftmost
+/// column. Shorter lines do not count in the columns past their end.
+///
+/// ```
+/// use aoc_tools::strings::{column_frequencies, least_common_per_column, most_common_per_column};
+/// let signal = "eedadn drvtee eandsr raavrd atevrs tsrnev sdttsa rasrtv \
+///     nssdts ntnada svetve tesnvt vntsnd vrdear dvrsen enarar";
+/// let columns = column_frequencies(signal.split_whitespace());
+/// assert_eq!(columns[0].count(&'e'), 3);
+/// assert_eq!(most_common_per_column(&columns), "easter");
+/// assert_eq!(least_common_per_column(&columns), "advent");
+/// ```
+pub fn column_frequencies<I>(lines: I) -> Vec<Counter<char>>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut columns: Vec<Counter<char>> = Vec::new();
+    for line in lines {
+        for (x, c) in line.as_ref().chars().enumerate() {
+            if x == columns.len() {
+                columns.push(Counter::new());
+            }
+            columns[x].add(c);
+        }
+    }
+    columns
+}
+
+/// the most common char of each column, see `Counter::most_common` for ties
+pub fn most_common_per_column(columns: &[Counter<char>]) -> String {
+    columns
+        .iter()
+        .filter_map(|c| c.most_common())
+        .map(|(&c, _)| c)
+        .collect()
+}
+
+/// the least common char of each column, see `Counter::least_common` for ties
+pub fn least_common_per_column(columns: &[Counter<char>]) -> String {
+    columns
+        .iter()
+        .filter_map(|c| c.least_common())
+        .map(|(&c, _)| c)
+        .collect()
+}
@@ -0,0 +1,132 @@
+//! Incremental Zobrist hashing of grids.
+//!
+//! Every (cell, value) pair gets a random 64 bit key and the hash of a grid is the xor of the
+//! keys of its cells. Changing a cell only takes two xors to update the hash, so the states
+//! of a big automaton can be recorded step after step (e.g. for cycle detection) without
+//! hashing the whole grid each time.
+//!
+//! As with any 64 bit hash, two different states may collide, which is very unlikely over
+//! the few millions of states of a puzzle.
+//!
+//! ```
+//! use aoc_tools::{grid::Grid, zobrist::ZobristGrid};
+//! let grid = Grid::from_vec(3, 1, "#..".chars().collect());
+//! let encode = |&c: &char| (c == '#') as usize;
+//! let mut z = ZobristGrid::new(grid, 2, encode, 7);
+//! let start = z.hash();
+//! z.set(0, 0, '.');
+//! z.set(2, 0, '#');
+//! assert_ne!(z.hash(), start);
+//! z.set(2, 0, '.');
+//! z.set(0, 0, '#');
+//! assert_eq!(z.hash(), start);
+//! ```
+use crate::{grid::Grid, rand::Rng};
+
+/// Random keys for each of `cells` cells holding one of `values` values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZobristTable {
+    keys: Vec<u64>,
+    values: usize,
+}
+
+impl ZobristTable {
+    /// draws the keys from an `Rng` seeded with `seed`
+    pub fn new(cells: usize, values: usize, seed: u64) -> ZobristTable {
+        let mut rng = Rng::new(seed);
+        ZobristTable {
+            keys: (0..cells * values).map(|_| rng.next_u64()).collect(),
+            values,
+        }
+    }
+
+    /// key of `value` at the flat index `cell`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is not below the number of values given at construction.
+    pub fn key(&self, cell: usize, value: usize) -> u64 {
+        assert!(value < self.values, "value out of the table");
+        self.keys[cell * self.values + value]
+    }
+}
+
+/// A grid keeping its Zobrist hash up to date. Values are mapped to the rows of the table by
+/// `encode`, which must return a number below the number of values of the table.
+#[derive(Debug, Clone)]
+pub struct ZobristGrid<T, F> {
+    grid: Grid<T>,
+    table: ZobristTable,
+    encode: F,
+    hash: u64,
+}
+
+impl<T, F: Fn(&T) -> usize> ZobristGrid<T, F> {
+    /// wraps `grid`, whose cells may hold `values` different values
+    pub fn new(grid: Grid<T>, values: usize, encode: F, seed: u64) -> ZobristGrid<T, F> {
+        let table = ZobristTable::new(grid.size(), values, seed);
+        let mut z = ZobristGrid {
+            grid,
+            table,
+            encode,
+            hash: 0,
+        };
+        z.hash = z.rehash();
+        z
+    }
+
+    /// hash of the current state
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// computes the hash from scratch
+    pub fn rehash(&self) -> u64 {
+        self.grid
+            .iter()
+            .enumerate()
+            .fold(0, |h, (i, v)| h ^ self.table.key(i, (self.encode)(v)))
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> &T {
+        self.grid.get(x, y)
+    }
+
+    /// sets the cell at x,y, updating the hash, and returns the previous value
+    pub fn set(&mut self, x: usize, y: usize, value: T) -> T {
+        let i = self.grid.flat_index(x, y);
+        let new_key = self.table.key(i, (self.encode)(&value));
+        let old = std::mem::replace(self.grid.get_mut(x, y), value);
+        self.hash ^= self.table.key(i, (self.encode)(&old)) ^ new_key;
+        old
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.grid
+    }
+}
+
+//------------------------------
+// Tests
+//------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_incremental_matches_full_hash() {
+        let mut rng = Rng::new(1);
+        let grid = Grid::from_iter(8, 8, (0..64).map(|_| rng.gen_range(0..4u8)));
+        let mut z = ZobristGrid::new(grid, 4, |&v: &u8| v as usize, 99);
+        for _ in 0..200 {
+            let (x, y) = (rng.gen_range(0..8usize), rng.gen_range(0..8usize));
+            z.set(x, y, rng.gen_range(0..4u8));
+            assert_eq!(z.hash(), z.rehash());
+        }
+    }
+}